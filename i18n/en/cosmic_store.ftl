@@ -1,12 +1,180 @@
 cosmic-app-store = COSMIC App Store
 back = Back
 cancel = Cancel
+ok = OK
 install = Install
+loading = Loading...
 open = Open
 uninstall = Uninstall
 update = Update
 
+# Details
+developer-apps = {$count ->
+    [1] 1 app
+    *[other] {$count} apps
+}
+overview = Overview
+screenshots = Screenshots
+no-screenshots = No screenshots
+videos = Videos
+play-video = Play video
+version-history = Version history
+no-releases = No releases
+permissions = Permissions
+permissions-sandboxed = Sandboxed
+permissions-sandboxed-body = This app runs in a sandbox with limited access to your system.
+permissions-unrestricted = Unrestricted
+permissions-unrestricted-body = This app has full access to your files and system.
+releases = Releases
+release-version = Version {$version}
+verified = Verified
+requirements = Requirements
+required = Required
+recommended = Recommended
+requirement-control = Input: {$control}
+requirement-display = Display size {$length} pixels
+requirement-memory = Memory {$memory}
+requirement-not-met = Not met by this system
+launcher = Launcher
+desktop-file = Desktop file: {$path}
+desktop-file-missing = Desktop file not found: {$error}
+show-in-files = Show in Files
+launch-command = Command: {$command}
+launch-wrapper = Runs through the {$wrapper} sandbox
+launch-no-wrapper = Runs directly, without a sandbox
+show-older-releases = Show older releases
+hide-older-releases = Hide older releases
+reviews = Reviews
+ratings = {$count ->
+    [1] 1 rating
+    *[other] {$count} ratings
+}
+no-reviews = No reviews yet
+write-review = Write a review
+review-name = Name (optional)
+review-summary = Summary
+review-summary-limit = Up to {$max} characters
+review-description = Review
+review-description-limit = Between {$min} and {$max} characters
+review-helpful = Helpful
+review-not-helpful = Not helpful
+review-voted = Thank you for your feedback
+review-helpful-count = {$count ->
+    [1] 1 person found this helpful
+    *[other] {$count} people found this helpful
+}
+submit = Submit
+url-donate = Donate
+url-bug-tracker = Report an issue
+report-problem = Report a problem with this app
+url-contact = Contact
+url-faq = FAQ
+url-help = Help
+url-homepage = Website
+url-translate = Translate
+copy-link = Copy link
+install-with-app-store = Install with App Store
+add-favorite = Add to favorites
+remove-favorite = Remove from favorites
+license-free = Free software
+license-proprietary = Proprietary
+license-unknown = Unknown license
+license = License: {$license}
+content-all-ages = All ages
+content-age = Ages {$age}+
+content-violence = Violence
+content-drugs = Drugs and alcohol
+content-sex = Sex and nudity
+content-language = Language
+content-gambling = Gambling
+content-purchases = In-app purchases
+content-advertising = Advertising
+content-social = User interaction
+content-none = None
+content-mild = Mild
+content-moderate = Moderate
+content-intense = Intense
+
+# Search
+search-in-category = Search in {$category}
+search-filters = Filters
+all-sources = All sources
+all-categories = All categories
+source = Source
+arch-unsupported = Not available for {$arch}
+not-installable = Not installable
+category = Category
+categories = Categories
+installed = Installed
+installed-only = Installed only
+free-software-only = Free software only
+include-system-components = Include system components
+search-descriptions = Search descriptions
+sort-relevance = Relevance
+sort-name = Name
+sort-recently-updated = Recently updated
+sort-size = Size
+did-you-mean = Did you mean {$suggestion}?
+search-provides = Find a command or file
+search-provides-description = Search for provides: followed by a command or path to find the package that supplies it
+search-provides-start = Find
+filter-installed = Filter installed apps
+show-system-packages = Show system packages
+hide-system-packages = Hide system packages
+pin-search = Pin search
+unpin-search = Unpin search
+pinned-searches = Pinned searches
+app-of-the-day = App of the day
+favorites = Favorites
+popular = Popular
+recently-added = Recently added
+recently-updated = Recently updated
+load-more = Load more
+no-results = No results
+no-results-body = Try different keywords or check the spelling.
+clear-search = Clear search
+app-not-found = App not found
+app-not-found-body = No source provides an app with the ID {$id}.
+search-instead = Search instead
+no-installed = No apps installed
+no-installed-body = Apps you install will appear here.
+explore-apps = Explore apps
+no-backends = No software sources
+no-backends-body = No app sources could be loaded. Add a source like Flathub to find apps.
+add-flathub = Add Flathub
+offline = No network connection
+offline-body = Ratings, reviews, and screenshots are unavailable while offline.
+retry = Retry
+busy-backends = Loading sources...
+busy-installed = Loading installed apps...
+busy-updates = Checking for updates...
+inhibit-reason = Installing and updating apps
+recent-searches = Recent searches
+clear-history = Clear history
+
+# Package files
+install-file = Install {$name}?
+install-file-body = This package file will be installed using {$source}.
+install-file-unsupported = Cannot install {$name}
+install-file-repo-body = This file adds a new software source. Only add sources you trust.
+install-file-unsupported-body = Package files of type "{$format}" are not supported on this system.
+
+# Source trust
+source-signed = Signed
+source-signed-by = Signed by {$name}
+source-unsigned = Unsigned
+source-unsigned-warning = Packages from this source are not signed, so they could have been modified by anyone between the source and this system.
+install-unsigned = Install {$name} from an unsigned source?
+install-unsigned-body = {$source} does not verify package signatures. Only install from this source if you trust it and the network it is reached over.
+install-anyway = Install anyway
+
+# Resume operations
+resume-operations = Resume unfinished operations?
+resume-operations-body = These operations had not finished when the store last closed.
+resume = Resume
+
 # Nav Pages
+explore = Explore
 create = Create
 work = Work
 develop = Develop
@@ -17,6 +185,45 @@ socialize = Socialize
 utilities = Utilities
 installed-apps = Installed apps
 updates = Updates
+updates-count = Updates ({$count})
+update-available = Update available
+release-notes = Release notes
+hide-release-notes = Hide release notes
+update-available-version = Update available: {$version}
+
+# Firmware
+firmware-version = Version {$current} to {$new}
+firmware-urgency = Urgency: {$urgency}
+firmware-urgency-low = Low
+firmware-urgency-medium = Medium
+firmware-urgency-high = High
+firmware-urgency-critical = Critical
+firmware-issues = Fixes {$issues}
+firmware-requires-ac = The system must be connected to AC power during the update.
+firmware-needs-reboot = A restart is required to finish the update.
+update-firmware = Update firmware for {$name}?
+update-firmware-body = Do not disconnect the device or turn off the system until the update has finished.
+
+# Categories
+category-audio-video = Multimedia
+category-audio = Audio
+category-video = Video
+category-development = Development
+category-education = Education
+category-game = Games
+category-graphics = Graphics
+category-network = Internet
+category-office = Office
+category-science = Science
+category-settings = Settings
+category-system = System
+category-utility = Utilities
+category-chat = Chat
+category-email = Email
+category-web-browser = Web browsers
+category-music = Music
+category-photography = Photography
+category-text-editor = Text editors
 
 # Context Pages
 
@@ -29,3 +236,145 @@ theme = Theme
 match-desktop = Match desktop
 dark = Dark
 light = Light
+density = Density
+comfortable = Comfortable
+compact = Compact
+
+### Software
+software = Software
+foreign-architectures = Show apps for other architectures
+foreign-architectures-description = Include apps that are not available for {$arch} in search results and categories. They cannot be installed on this system.
+free-software-only-description = Hide apps with proprietary licenses everywhere in the store
+show-proprietary = Show proprietary apps
+hide-proprietary = Hide proprietary apps
+require-auth = Require authentication
+require-auth-description = Ask for an administrator password before installing, updating, or removing apps
+authentication-failed = Authentication failed
+content-age-limit-title = Age rating limit
+content-age-limit-description = Hide apps rated for older ages from browsing and search. Changing this may require administrator authentication.
+content-age-limit = Up to ages {$age}+
+catalog-language = App information language
+catalog-language-description = Language code for app names and descriptions, such as de or pt-BR. Leave empty to use the system language.
+preferred-format = Preferred package format
+preferred-format-description = Apps available from more than one source are shown and installed in this format
+prefer-flatpak = Flatpak
+prefer-native = Native packages
+
+### Backup
+backup = Backup
+export = Export
+export-app-list = Export app list
+export-app-list-description = Save a list of installed apps to restore on another system
+app-list-exported = App list exported
+app-list-exported-body = The list of installed apps was saved to {$path}.
+app-list-export-failed = Failed to export app list
+import = Import
+import-app-list = Import app list
+import-app-list-description = Install apps from a list saved at {$path}
+app-list-missing = {$count ->
+    [1] 1 app from the list is not installed.
+    *[other] {$count} apps from the list are not installed.
+}
+app-list-nothing-missing = All apps from the list are already installed.
+app-list-unavailable = {$count ->
+    [1] 1 app is not available from any source.
+    *[other] {$count} apps are not available from any source.
+}
+app-list-import-failed = Failed to import app list
+install-count = {$count ->
+    [1] Install 1 app
+    *[other] Install {$count} apps
+}
+export-settings = Export settings
+export-settings-description = Save favorites, preferences, sources, and keyboard shortcuts to carry to another system
+settings-exported = Settings exported
+settings-exported-body = Favorites, preferences, sources, and keyboard shortcuts were saved to {$path}.
+settings-export-failed = Failed to export settings
+import-settings = Import settings
+import-settings-description = Restore favorites, preferences, sources, and keyboard shortcuts saved at {$path}
+settings-import-failed = Failed to import settings
+import-repos = Change software sources?
+import-repos-body = The imported settings change which sources are enabled on this system.
+import-repo-enable = Enable {$name}
+import-repo-disable = Disable {$name}
+import-repos-apply = Change sources
+
+### Sources
+sources = Software sources
+sources-description = Enable optional repositories offered by your distribution
+manage = Manage
+no-optional-sources = No optional repositories are known for this system.
+optional-sources-notice = These repositories are not enabled by default. Their software may have licenses that restrict how you can use, share, or modify it.
+repo-proprietary = Contains proprietary software, or software that depends on it
+repo-patent-encumbered = Contains free software that may be restricted by patents in some countries
+
+### Storage
+storage = Storage
+storage-apps = Apps
+storage-runtimes = Runtimes
+storage-runtimes-description = Shared libraries and extensions used by apps
+storage-unused = Unused runtimes
+remove-unused = Remove
+
+### Cache
+cache = Cache
+cache-appstream = App information
+cache-icons = App icons
+cache-screenshots = Screenshots
+cache-reviews = Ratings and reviews
+calculating = Calculating…
+clear = Clear
+
+### Network
+network = Network
+proxy = Store proxy
+proxy-description = Used for ratings, reviews, screenshots, and firmware downloads instead of the system proxy. Apps and system updates always use the system proxy.
+proxy-placeholder = http://proxy.example.com:8080
+download-limit = Store download speed limit
+download-limit-description = Applies to ratings, reviews, screenshots, and firmware downloads. Apps and system updates are not limited.
+per-second = {$size}/s
+parallel-operations = Simultaneous installs and updates
+parallel-operations-description = Run fewer at a time on slow connections, the rest wait in the queue
+one-at-a-time = One at a time
+two-at-a-time = Two at a time
+four-at-a-time = Four at a time
+no-limit = No limit
+reviews-description = Show ratings and reviews from the Open Desktop Ratings Service
+reviews-server = Reviews server
+reviews-disabled = Reviews are disabled in settings
+
+### Notifications
+notifications = Notifications
+update-notifications = Notify when updates are available
+updates-notification = {$count ->
+    [1] 1 update available
+    *[other] {$count} updates available
+}
+updates-notification-more = {$apps} and {$count} more
+view-updates = View updates
+tray = Show in status area
+autostart = Start at login
+autostart-description = Check for updates in the background after logging in
+refresh-interval = Refresh sources
+refresh-interval-description = Download the latest app information from software sources
+hourly = Every hour
+daily = Every day
+never = Never
+
+### Keyboard shortcuts
+keyboard-shortcuts = Keyboard shortcuts
+press-shortcut = Press a shortcut...
+shortcut-disabled = Disabled
+reset-shortcuts = Reset shortcuts
+reset-shortcuts-description = Press Backspace while binding to disable a shortcut
+reset = Reset
+action-nav-back = Go back
+action-nav-forward = Go forward
+action-search = Search
+action-refresh = Refresh sources
+action-install = Install selected app
+action-settings = Open settings
+action-escape = Close dialog or search
+open-store = Open App Store
+update-all = Update all
+no-updates = No updates available
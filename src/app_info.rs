@@ -1,8 +1,17 @@
 use appstream::{
-    enums::{Bundle, Icon, ImageKind, Launchable},
+    enums::{
+        Bundle, ComponentKind, ContentState, Icon, ImageKind, Launchable, ProjectUrl, Provide, Size,
+    },
     xmltree, Component,
 };
-use std::{error::Error, fmt::Write};
+use std::{
+    cmp,
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Write},
+};
+
+use crate::search_fold;
 
 fn get_translatable<'a>(translatable: &'a appstream::TranslatableString, locale: &str) -> &'a str {
     match translatable.get_for_locale(locale) {
@@ -14,6 +23,19 @@ fn get_translatable<'a>(translatable: &'a appstream::TranslatableString, locale:
     }
 }
 
+fn get_translatable_list<'a>(
+    translatable: &'a appstream::TranslatableList,
+    locale: &str,
+) -> &'a [String] {
+    match translatable.get_for_locale(locale) {
+        Some(some) => some.as_slice(),
+        None => match translatable.get_default() {
+            Some(some) => some.as_slice(),
+            None => &[],
+        },
+    }
+}
+
 //TODO: handle p tags with xml:lang
 fn get_markup_translatable<'a>(
     translatable: &'a appstream::MarkupTranslatableString,
@@ -28,72 +50,182 @@ fn get_markup_translatable<'a>(
     }
 }
 
-fn write_node(
-    s: &mut String,
+fn push_text(spans: &mut Vec<AppMarkupSpan>, text: &str, emphasis: bool, code: bool) {
+    // Collapse whitespace like HTML, the block is trimmed later
+    let mut collapsed = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !collapsed.ends_with(' ') {
+                collapsed.push(' ');
+            }
+        } else {
+            collapsed.push(c);
+        }
+    }
+    if collapsed.is_empty() {
+        return;
+    }
+    spans.push(if code {
+        AppMarkupSpan::Code(collapsed)
+    } else if emphasis {
+        AppMarkupSpan::Emphasis(collapsed)
+    } else {
+        AppMarkupSpan::Text(collapsed)
+    });
+}
+
+fn parse_spans(
+    spans: &mut Vec<AppMarkupSpan>,
     node: &xmltree::XMLNode,
+    emphasis: bool,
+    code: bool,
     recursion: usize,
 ) -> Result<(), Box<dyn Error>> {
     if recursion >= 4 {
         return Err(format!("maximum recursion level reached").into());
     }
     match node {
-        xmltree::XMLNode::Element(element) => match element.name.as_str() {
-            //TODO: actually style these
-            "b" | "em" => {
-                for child in element.children.iter() {
-                    write_node(s, child, recursion + 1)?;
+        xmltree::XMLNode::Element(element) => {
+            let (emphasis, code) = match element.name.as_str() {
+                "b" | "em" | "i" | "strong" => (true, code),
+                "code" => (emphasis, true),
+                _ => {
+                    return Err(format!("unknown inline element {:?}", element.name).into());
                 }
+            };
+            for child in element.children.iter() {
+                parse_spans(spans, child, emphasis, code, recursion + 1)?;
             }
-            "code" | "pre" => {
+        }
+        xmltree::XMLNode::Text(text) => push_text(spans, text, emphasis, code),
+        xmltree::XMLNode::Comment(_) => {}
+        _ => {
+            return Err(format!("unknown node {:?}", node).into());
+        }
+    }
+    Ok(())
+}
+
+fn trim_spans(mut spans: Vec<AppMarkupSpan>) -> Vec<AppMarkupSpan> {
+    if let Some(span) = spans.first_mut() {
+        let text = span.text_mut();
+        *text = text.trim_start().to_string();
+    }
+    if let Some(span) = spans.last_mut() {
+        let text = span.text_mut();
+        *text = text.trim_end().to_string();
+    }
+    spans.retain(|span| !span.text().is_empty());
+    spans
+}
+
+fn parse_block(node: &xmltree::XMLNode) -> Result<Option<AppMarkup>, Box<dyn Error>> {
+    match node {
+        xmltree::XMLNode::Element(element) => match element.name.as_str() {
+            "p" => {
+                let mut spans = Vec::new();
                 for child in element.children.iter() {
-                    write_node(s, child, recursion + 1)?;
+                    parse_spans(&mut spans, child, false, false, 1)?;
                 }
+                Ok(Some(AppMarkup::Paragraph(trim_spans(spans))))
             }
-            "li" => {
+            "ol" | "ul" => {
+                let mut items = Vec::with_capacity(element.children.len());
                 for child in element.children.iter() {
-                    write_node(s, child, recursion + 1)?;
+                    match child {
+                        xmltree::XMLNode::Element(item) if item.name == "li" => {
+                            let mut spans = Vec::new();
+                            for item_child in item.children.iter() {
+                                parse_spans(&mut spans, item_child, false, false, 2)?;
+                            }
+                            items.push(trim_spans(spans));
+                        }
+                        xmltree::XMLNode::Text(text) if text.trim().is_empty() => {}
+                        xmltree::XMLNode::Comment(_) => {}
+                        _ => {
+                            return Err(format!("unknown list node {:?}", child).into());
+                        }
+                    }
                 }
+                Ok(Some(if element.name == "ol" {
+                    AppMarkup::OrderedList(items)
+                } else {
+                    AppMarkup::UnorderedList(items)
+                }))
             }
-            "ol" | "p" | "ul" => {
-                if !s.is_empty() {
-                    writeln!(s)?;
-                }
-                for (i, child) in element.children.iter().enumerate() {
-                    if element.name == "ol" {
-                        write!(s, "{:2}. ", i + 1)?;
-                    } else if element.name == "ul" {
-                        write!(s, " * ")?;
+            "pre" => {
+                let mut text = String::new();
+                for child in element.children.iter() {
+                    if let xmltree::XMLNode::Text(child_text) = child {
+                        text.push_str(child_text);
                     }
-                    write_node(s, child, recursion + 1)?;
-                }
-                if !s.ends_with("\n") {
-                    writeln!(s)?;
                 }
+                Ok(Some(AppMarkup::Preformatted(
+                    text.trim_matches('\n').to_string(),
+                )))
             }
-            _ => {
-                return Err(format!("unknown element {:?}", element.name).into());
-            }
+            _ => Err(format!("unknown element {:?}", element.name).into()),
         },
+        // Some descriptions are missing paragraph tags
         xmltree::XMLNode::Text(text) => {
-            for line in text.trim().lines() {
-                writeln!(s, "{}", line.trim())?;
+            let mut spans = Vec::new();
+            push_text(&mut spans, text, false, false);
+            let spans = trim_spans(spans);
+            if spans.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(AppMarkup::Paragraph(spans)))
             }
         }
-        _ => {
-            return Err(format!("unknown node {:?}", node).into());
+        xmltree::XMLNode::Comment(_) => Ok(None),
+        _ => Err(format!("unknown node {:?}", node).into()),
+    }
+}
+
+pub fn parse_markup(markup: &str) -> Result<Vec<AppMarkup>, Box<dyn Error>> {
+    let mut blocks = Vec::new();
+    for node in xmltree::Element::parse_all(markup.as_bytes())? {
+        if let Some(block) = parse_block(&node)? {
+            blocks.push(block);
         }
     }
-    Ok(())
+    Ok(blocks)
 }
 
-fn convert_markup(markup: &str) -> Result<String, Box<dyn Error>> {
+/// Plain text version of markup, used for searching
+pub fn markup_text(blocks: &[AppMarkup]) -> Result<String, fmt::Error> {
     let mut s = String::new();
-    for node in xmltree::Element::parse_all(markup.as_bytes())? {
-        write_node(&mut s, &node, 0)?;
+    for block in blocks.iter() {
+        match block {
+            AppMarkup::Paragraph(spans) => {
+                writeln!(s, "{}", spans_text(spans))?;
+            }
+            AppMarkup::OrderedList(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    writeln!(s, "{:2}. {}", i + 1, spans_text(item))?;
+                }
+            }
+            AppMarkup::UnorderedList(items) => {
+                for item in items.iter() {
+                    writeln!(s, " * {}", spans_text(item))?;
+                }
+            }
+            AppMarkup::Preformatted(text) => {
+                writeln!(s, "{}", text)?;
+            }
+        }
     }
     Ok(s)
 }
 
+pub fn spans_text(spans: &[AppMarkupSpan]) -> String {
+    let mut s = String::new();
+    for span in spans.iter() {
+        s.push_str(span.text());
+    }
+    s
+}
+
 // Replaced Icon due to skip_field not supported in bitcode
 #[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
 pub enum AppIcon {
@@ -101,28 +233,616 @@ pub enum AppIcon {
     Stock(String),
 }
 
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppMarkupSpan {
+    Text(String),
+    Emphasis(String),
+    Code(String),
+}
+
+impl AppMarkupSpan {
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Text(text) | Self::Emphasis(text) | Self::Code(text) => text,
+        }
+    }
+
+    fn text_mut(&mut self) -> &mut String {
+        match self {
+            Self::Text(text) | Self::Emphasis(text) | Self::Code(text) => text,
+        }
+    }
+}
+
+// Parsed appstream description markup
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppMarkup {
+    Paragraph(Vec<AppMarkupSpan>),
+    OrderedList(Vec<Vec<AppMarkupSpan>>),
+    UnorderedList(Vec<Vec<AppMarkupSpan>>),
+    Preformatted(String),
+}
+
+// Replaced Release due to skip_field not supported in bitcode
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub struct AppRelease {
+    pub version: String,
+    /// Release date in seconds from the unix epoch
+    pub timestamp_opt: Option<i64>,
+    pub description_opt: Option<Vec<AppMarkup>>,
+}
+
 // Replaced Screenshot due to skip_field not supported in bitcode
 #[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
 pub struct AppScreenshot {
     pub caption: String,
     pub url: String,
+    /// Desktop environment and style, like plasma:dark, not parsed by the appstream crate
+    pub environment_opt: Option<String>,
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub struct AppVideo {
+    pub caption: String,
+    pub url: String,
+}
+
+impl AppScreenshot {
+    /// Returns Some(true) for dark variants, Some(false) for light variants, and None if the
+    /// screenshot does not have a variant
+    pub fn dark_opt(&self) -> Option<bool> {
+        let environment = self.environment_opt.as_ref()?;
+        Some(environment.ends_with(":dark"))
+    }
+}
+
+/// Parse screenshot environments from a component element, keyed by source image URL
+pub fn screenshot_environments_from_xml(component: &xmltree::Element) -> HashMap<String, String> {
+    let mut environments = HashMap::new();
+    let Some(screenshots) = component.get_child("screenshots") else {
+        return environments;
+    };
+    for node in screenshots.children.iter() {
+        let xmltree::XMLNode::Element(screenshot) = node else {
+            continue;
+        };
+        let Some(environment) = screenshot.attributes.get("environment") else {
+            continue;
+        };
+        for node in screenshot.children.iter() {
+            if let xmltree::XMLNode::Element(image) = node {
+                if &*image.name == "image" {
+                    if let Some(url) = image.get_text() {
+                        environments.insert(url.trim().to_string(), environment.clone());
+                    }
+                }
+            }
+        }
+    }
+    environments
+}
+
+// Replaced ComponentKind to only include supported kinds
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppKind {
+    DesktopApplication,
+    ConsoleApplication,
+    Addon,
+    Codec,
+    Runtime,
+}
+
+impl AppKind {
+    /// Returns None for component kinds that are not shown in the store
+    pub fn new(kind: &ComponentKind) -> Option<Self> {
+        match kind {
+            ComponentKind::DesktopApplication => Some(Self::DesktopApplication),
+            ComponentKind::ConsoleApplication => Some(Self::ConsoleApplication),
+            ComponentKind::Addon => Some(Self::Addon),
+            ComponentKind::Codec => Some(Self::Codec),
+            ComponentKind::Runtime => Some(Self::Runtime),
+            _ => None,
+        }
+    }
+}
+
+// Replaced ContentState due to skip_field not supported in bitcode
+#[derive(
+    Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd, bitcode::Decode, bitcode::Encode,
+)]
+pub enum AppContentState {
+    None,
+    Mild,
+    Moderate,
+    Intense,
+}
+
+impl AppContentState {
+    fn index(&self) -> Option<usize> {
+        match self {
+            Self::None => None,
+            Self::Mild => Some(0),
+            Self::Moderate => Some(1),
+            Self::Intense => Some(2),
+        }
+    }
+}
+
+/// Minimum age for an OARS attribute at mild, moderate, and intense levels, based on the
+/// Common Sense Media mapping used by libappstream
+fn content_attribute_ages(id: &str) -> [u8; 3] {
+    match id {
+        "violence-cartoon" => [3, 4, 6],
+        "violence-fantasy" => [3, 7, 8],
+        "violence-realistic" => [4, 9, 14],
+        "violence-bloodshed" => [9, 11, 18],
+        "violence-sexual" => [18, 18, 18],
+        "violence-desecration" => [11, 13, 15],
+        "violence-slavery" => [13, 15, 18],
+        "violence-worship" => [13, 15, 18],
+        "drugs-alcohol" => [11, 13, 16],
+        "drugs-narcotics" => [12, 14, 17],
+        "drugs-tobacco" => [10, 13, 13],
+        "sex-nudity" => [12, 14, 14],
+        "sex-themes" => [13, 14, 15],
+        "sex-homosexuality" => [10, 13, 18],
+        "sex-prostitution" => [12, 14, 18],
+        "sex-adultery" => [8, 10, 18],
+        "sex-appearance" => [10, 10, 15],
+        "language-profanity" => [8, 11, 14],
+        "language-humor" => [3, 8, 14],
+        "language-discrimination" => [9, 10, 11],
+        "money-gambling" => [7, 10, 18],
+        "social-chat" => [4, 13, 14],
+        "social-info" => [0, 13, 15],
+        "social-audio" => [15, 15, 15],
+        "social-location" => [13, 13, 13],
+        "social-contacts" => [12, 12, 12],
+        // Advertising, purchases, and unknown attributes do not affect the age rating
+        _ => [0, 0, 0],
+    }
+}
+
+// Replaced Provide due to skip_field not supported in bitcode
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppProvide {
+    Binary(String),
+    Id(String),
+    Library(String),
+}
+
+// Replaced ProjectUrl due to skip_field not supported in bitcode
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppUrl {
+    BugTracker(String),
+    Contact(String),
+    Donation(String),
+    Faq(String),
+    Help(String),
+    Homepage(String),
+    Translate(String),
+}
+
+impl AppUrl {
+    pub fn url(&self) -> &str {
+        match self {
+            Self::BugTracker(url)
+            | Self::Contact(url)
+            | Self::Donation(url)
+            | Self::Faq(url)
+            | Self::Help(url)
+            | Self::Homepage(url)
+            | Self::Translate(url) => url,
+        }
+    }
+}
+
+// Comparison used by appstream requires and recommends relations
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppCompare {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+impl AppCompare {
+    fn new(compare: &str) -> Option<Self> {
+        match compare {
+            "eq" | "==" => Some(Self::Eq),
+            "ne" | "!=" => Some(Self::Ne),
+            "lt" | "<<" | "<" => Some(Self::Lt),
+            "gt" | ">>" | ">" => Some(Self::Gt),
+            "le" | "<=" => Some(Self::Le),
+            "ge" | ">=" => Some(Self::Ge),
+            _ => None,
+        }
+    }
+
+    pub fn matches(&self, actual: u64, value: u64) -> bool {
+        match self {
+            Self::Eq => actual == value,
+            Self::Ne => actual != value,
+            Self::Lt => actual < value,
+            Self::Gt => actual > value,
+            Self::Le => actual <= value,
+            Self::Ge => actual >= value,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Ne => "≠",
+            Self::Lt => "<",
+            Self::Gt => ">",
+            Self::Le => "≤",
+            Self::Ge => "≥",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppRequirement {
+    /// Input method such as keyboard, pointing, touch, or gamepad
+    Control(String),
+    /// Length of the shortest side of the display in logical pixels
+    DisplayLength(AppCompare, u64),
+    /// Physical memory in MiB
+    Memory(u64),
+}
+
+// Display sizes used by older appstream files instead of pixel values
+fn display_length_value(value: &str) -> Option<u64> {
+    match value.trim() {
+        "xsmall" => Some(360),
+        "small" => Some(420),
+        "medium" => Some(760),
+        "large" => Some(900),
+        "xlarge" => Some(1200),
+        value => value.parse().ok(),
+    }
+}
+
+impl AppRequirement {
+    fn new(kind: &str, value: &str, compare_opt: Option<&str>) -> Option<Self> {
+        match kind {
+            "control" => Some(Self::Control(value.trim().to_string())),
+            "display_length" => {
+                // YAML data can include the comparison in the value, like ">=768"
+                let value = value.trim();
+                let split = value
+                    .find(|c: char| !matches!(c, '<' | '>' | '=' | '!'))
+                    .unwrap_or(value.len());
+                let (prefix, value) = value.split_at(split);
+                let compare = match compare_opt.unwrap_or(prefix) {
+                    "" => AppCompare::Ge,
+                    compare => AppCompare::new(compare)?,
+                };
+                Some(Self::DisplayLength(compare, display_length_value(value)?))
+            }
+            "memory" => Some(Self::Memory(value.trim().parse().ok()?)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub struct AppRelation {
+    /// True for requires, false for recommends
+    pub required: bool,
+    pub requirement: AppRequirement,
+}
+
+/// Parse requires and recommends relations from a component element
+pub fn relations_from_xml(component: &xmltree::Element) -> Vec<AppRelation> {
+    let mut relations = Vec::new();
+    for (name, required) in [("requires", true), ("recommends", false)] {
+        let Some(relation) = component.get_child(name) else {
+            continue;
+        };
+        for node in relation.children.iter() {
+            let xmltree::XMLNode::Element(e) = node else {
+                continue;
+            };
+            let value = e.get_text().unwrap_or_default();
+            let compare_opt = e.attributes.get("compare").map(|x| x.as_str());
+            if let Some(requirement) = AppRequirement::new(&e.name, &value, compare_opt) {
+                relations.push(AppRelation {
+                    required,
+                    requirement,
+                });
+            }
+        }
+    }
+    relations
+}
+
+/// Parse Requires and Recommends relations from a DEP-11 document
+pub fn relations_from_yaml(value: &serde_yaml::Value) -> Vec<AppRelation> {
+    let mut relations = Vec::new();
+    for (name, required) in [("Requires", true), ("Recommends", false)] {
+        let Some(sequence) = value[name].as_sequence() else {
+            continue;
+        };
+        for item in sequence {
+            let Some(mapping) = item.as_mapping() else {
+                continue;
+            };
+            for (kind, value) in mapping.iter() {
+                let Some(kind) = kind.as_str() else {
+                    continue;
+                };
+                let value = match value {
+                    serde_yaml::Value::Number(number) => number.to_string(),
+                    serde_yaml::Value::String(string) => string.clone(),
+                    _ => continue,
+                };
+                if let Some(requirement) = AppRequirement::new(kind, &value, None) {
+                    relations.push(AppRelation {
+                        required,
+                        requirement,
+                    });
+                }
+            }
+        }
+    }
+    relations
+}
+
+/// Custom metadata set by the remote when the developer has been verified, returns the verified
+/// website or account name, which may be empty
+fn verified_from_custom<'a>(custom: impl Iterator<Item = (&'a str, &'a str)>) -> Option<String> {
+    let mut verified = false;
+    let mut website_opt = None;
+    let mut login_name_opt = None;
+    for (key, value) in custom {
+        match key {
+            "flathub::verification::verified" => verified = value == "true",
+            "flathub::verification::website" => website_opt = Some(value),
+            "flathub::verification::login_name" => login_name_opt = Some(value),
+            _ => {}
+        }
+    }
+    if verified {
+        Some(website_opt.or(login_name_opt).unwrap_or("").to_string())
+    } else {
+        None
+    }
+}
+
+/// Parse verification from custom values in a component element
+pub fn verified_from_xml(component: &xmltree::Element) -> Option<String> {
+    let custom = component.get_child("custom")?;
+    let mut values = Vec::new();
+    for node in custom.children.iter() {
+        if let xmltree::XMLNode::Element(e) = node {
+            if let (Some(key), Some(value)) = (e.attributes.get("key"), e.get_text()) {
+                values.push((key.clone(), value.into_owned()));
+            }
+        }
+    }
+    verified_from_custom(
+        values
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str())),
+    )
+}
+
+/// Parse verification from the Custom mapping in a DEP-11 document
+pub fn verified_from_yaml(value: &serde_yaml::Value) -> Option<String> {
+    let custom = value["Custom"].as_mapping()?;
+    verified_from_custom(
+        custom
+            .iter()
+            .filter_map(|(key, value)| Some((key.as_str()?, value.as_str()?))),
+    )
+}
+
+/// Text matched by searches, folded once when an app is loaded instead of on every search
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub struct AppSearchText {
+    pub name: String,
+    pub summary: String,
+    pub keywords: Vec<String>,
+}
+
+impl AppSearchText {
+    pub fn new(name: &str, summary: &str, keywords: &[String]) -> Self {
+        Self {
+            name: search_fold(name),
+            summary: search_fold(summary),
+            keywords: keywords
+                .iter()
+                .map(|keyword| search_fold(keyword))
+                .collect(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
 pub struct AppInfo {
     pub origin_opt: Option<String>,
+    pub kind: AppKind,
     pub name: String,
     pub summary: String,
-    pub description: String,
+    pub description_markup: Vec<AppMarkup>,
+    pub developer_name: String,
+    pub project_group_opt: Option<String>,
+    pub keywords: Vec<String>,
+    pub license_opt: Option<String>,
     pub pkgnames: Vec<String>,
     pub categories: Vec<String>,
     pub desktop_ids: Vec<String>,
     pub flatpak_refs: Vec<String>,
+    pub mimetypes: Vec<String>,
     pub icons: Vec<AppIcon>,
+    pub provides: Vec<AppProvide>,
+    pub releases: Vec<AppRelease>,
     pub screenshots: Vec<AppScreenshot>,
+    pub videos: Vec<AppVideo>,
+    /// OARS content attributes, attributes that are not present have no such content
+    pub content_rating: Vec<(String, AppContentState)>,
+    pub urls: Vec<AppUrl>,
+    /// Hardware and display requirements, not parsed by the appstream crate
+    pub relations: Vec<AppRelation>,
+    /// Set if the remote verified the developer, contains the verified website or account name
+    pub verified_opt: Option<String>,
+    /// Installed size in bytes, falling back to download size
+    pub size_opt: Option<u64>,
+    pub search_text: AppSearchText,
+}
+
+/// Current CPU architecture, using the names from flatpak refs
+pub fn current_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86" => "i386",
+        "powerpc64" => "ppc64le",
+        arch => arch,
+    }
 }
 
 impl AppInfo {
+    /// Placeholder info for a local package file that has no appstream data
+    pub fn package_file(name: String) -> Self {
+        let search_text = AppSearchText::new(&name, "", &[]);
+        Self {
+            origin_opt: None,
+            kind: AppKind::DesktopApplication,
+            name,
+            summary: String::new(),
+            description_markup: Vec::new(),
+            developer_name: String::new(),
+            project_group_opt: None,
+            keywords: Vec::new(),
+            license_opt: None,
+            pkgnames: Vec::new(),
+            categories: Vec::new(),
+            desktop_ids: Vec::new(),
+            flatpak_refs: Vec::new(),
+            mimetypes: Vec::new(),
+            icons: Vec::new(),
+            provides: Vec::new(),
+            releases: Vec::new(),
+            screenshots: Vec::new(),
+            videos: Vec::new(),
+            content_rating: Vec::new(),
+            urls: Vec::new(),
+            relations: Vec::new(),
+            verified_opt: None,
+            size_opt: None,
+            search_text,
+        }
+    }
+
+    /// Returns true if the project license is a known free software license
+    pub fn license_is_free(&self) -> bool {
+        self.license_free_opt() == Some(true)
+    }
+
+    /// Returns Some(true) for free software, Some(false) for proprietary software, and None if
+    /// the license is missing or unknown
+    pub fn license_free_opt(&self) -> Option<bool> {
+        crate::spdx::is_free(self.license_opt.as_deref()?)
+    }
+
+    /// CPU architectures from flatpak refs, empty if the architecture is not known
+    pub fn architectures(&self) -> Vec<&str> {
+        let mut architectures = Vec::new();
+        for flatpak_ref in self.flatpak_refs.iter() {
+            // Refs use the format kind/name/arch/branch
+            if let Some(arch) = flatpak_ref.split('/').nth(2) {
+                if !arch.is_empty() && !architectures.contains(&arch) {
+                    architectures.push(arch);
+                }
+            }
+        }
+        architectures
+    }
+
+    /// Returns false if the app is only available for other CPU architectures
+    pub fn arch_supported(&self) -> bool {
+        let architectures = self.architectures();
+        architectures.is_empty() || architectures.contains(&current_arch())
+    }
+
+    /// Returns true if the app runs in a sandbox, which is currently only flatpak apps
+    pub fn sandboxed(&self) -> bool {
+        !self.flatpak_refs.is_empty()
+    }
+
+    /// Returns true for runtimes, addons, codecs, and console only applications
+    pub fn is_system_component(&self) -> bool {
+        self.kind != AppKind::DesktopApplication
+            || self
+                .categories
+                .iter()
+                .any(|category| category == "ConsoleOnly")
+    }
+
+    /// Minimum age from the OARS content rating, None if there is no content rating
+    pub fn content_age_opt(&self) -> Option<u8> {
+        if self.content_rating.is_empty() {
+            return None;
+        }
+        let mut age = 0;
+        for (id, state) in self.content_rating.iter() {
+            if let Some(index) = state.index() {
+                age = cmp::max(age, content_attribute_ages(id)[index]);
+            }
+        }
+        Some(age)
+    }
+
+    /// Set screenshot environments from a map of source image URLs to environments
+    pub fn set_screenshot_environments(&mut self, environments: &HashMap<String, String>) {
+        for screenshot in self.screenshots.iter_mut() {
+            screenshot.environment_opt = environments.get(&screenshot.url).cloned();
+        }
+    }
+
+    /// Screenshots matching the theme mode with their indexes, screenshots without a variant are
+    /// always included
+    pub fn screenshots_for_theme(&self, dark: bool) -> Vec<(usize, &AppScreenshot)> {
+        let screenshots: Vec<_> = self
+            .screenshots
+            .iter()
+            .enumerate()
+            .filter(|(_, screenshot)| screenshot.dark_opt().map_or(true, |x| x == dark))
+            .collect();
+        if screenshots.is_empty() {
+            // Show all variants if none match
+            self.screenshots.iter().enumerate().collect()
+        } else {
+            screenshots
+        }
+    }
+
+    /// Release with the most recent timestamp
+    pub fn latest_release_opt(&self) -> Option<&AppRelease> {
+        self.releases
+            .iter()
+            .max_by_key(|release| release.timestamp_opt)
+    }
+
+    /// Timestamp of the first release, used to estimate when the app was added
+    pub fn added_opt(&self) -> Option<i64> {
+        self.releases
+            .iter()
+            .filter_map(|release| release.timestamp_opt)
+            .min()
+    }
+
+    /// Timestamp of the most recent release
+    pub fn updated_opt(&self) -> Option<i64> {
+        self.releases
+            .iter()
+            .filter_map(|release| release.timestamp_opt)
+            .max()
+    }
+
     pub fn new(origin_opt: Option<&str>, component: Component, locale: &str) -> Self {
         let name = get_translatable(&component.name, locale);
         let summary = component
@@ -133,7 +853,7 @@ impl AppInfo {
             .description
             .as_ref()
             .map_or("", |x| get_markup_translatable(x, locale));
-        let description = match convert_markup(description_markup) {
+        let description_markup = match parse_markup(description_markup) {
             Ok(ok) => ok,
             Err(err) => {
                 log::warn!(
@@ -142,9 +862,14 @@ impl AppInfo {
                     origin_opt,
                     err
                 );
-                String::new()
+                Vec::new()
             }
         };
+        let keywords = component
+            .keywords
+            .as_ref()
+            .map_or(&[][..], |x| get_translatable_list(x, locale))
+            .to_vec();
         let categories = component
             .categories
             .into_iter()
@@ -185,35 +910,141 @@ impl AppInfo {
                 _ => None,
             })
             .collect();
+        let provides = component
+            .provides
+            .into_iter()
+            .filter_map(|provide| match provide {
+                Provide::Binary(binary) => Some(AppProvide::Binary(binary)),
+                Provide::Id(id) => Some(AppProvide::Id(id.0)),
+                Provide::Library(path) => Some(AppProvide::Library(path.to_str()?.to_string())),
+                _ => None,
+            })
+            .collect();
+        let mut size_opt = None;
+        let mut releases = Vec::with_capacity(component.releases.len());
+        for release in component.releases.into_iter() {
+            if size_opt.is_none() {
+                let mut download_size_opt = None;
+                for size in release.sizes.iter() {
+                    match size {
+                        Size::Installed(installed_size) => size_opt = Some(*installed_size),
+                        Size::Download(download_size) => download_size_opt = Some(*download_size),
+                    }
+                }
+                size_opt = size_opt.or(download_size_opt);
+            }
+            let description_opt = release
+                .description
+                .as_ref()
+                .map(|x| get_markup_translatable(x, locale))
+                .and_then(|markup| match parse_markup(markup) {
+                    Ok(ok) => Some(ok),
+                    Err(err) => {
+                        log::warn!(
+                            "failed to parse description of {:?} release {:?}: {}",
+                            component.id,
+                            release.version,
+                            err
+                        );
+                        None
+                    }
+                });
+            releases.push(AppRelease {
+                version: release.version,
+                timestamp_opt: release.date.map(|date| date.timestamp()),
+                description_opt,
+            });
+        }
+        let content_rating = component
+            .content_rating
+            .map_or(Vec::new(), |content_rating| {
+                content_rating
+                    .attributes
+                    .into_iter()
+                    .map(|attribute| {
+                        let state = match attribute.value {
+                            ContentState::Mild => AppContentState::Mild,
+                            ContentState::Moderate => AppContentState::Moderate,
+                            ContentState::Intense => AppContentState::Intense,
+                            _ => AppContentState::None,
+                        };
+                        (attribute.id.to_string(), state)
+                    })
+                    .collect()
+            });
+        let urls = component
+            .urls
+            .into_iter()
+            .filter_map(|url| match url {
+                ProjectUrl::BugTracker(url) => Some(AppUrl::BugTracker(url.to_string())),
+                ProjectUrl::Contact(url) => Some(AppUrl::Contact(url.to_string())),
+                ProjectUrl::Donation(url) => Some(AppUrl::Donation(url.to_string())),
+                ProjectUrl::Faq(url) => Some(AppUrl::Faq(url.to_string())),
+                ProjectUrl::Help(url) => Some(AppUrl::Help(url.to_string())),
+                ProjectUrl::Homepage(url) => Some(AppUrl::Homepage(url.to_string())),
+                ProjectUrl::Translate(url) => Some(AppUrl::Translate(url.to_string())),
+                _ => None,
+            })
+            .collect();
         let mut screenshots = Vec::new();
+        let mut videos = Vec::new();
         for screenshot in component.screenshots.into_iter() {
+            let caption = screenshot
+                .caption
+                .as_ref()
+                .map_or("", |x| get_translatable(x, locale))
+                .to_string();
+            //TODO: support other video sizes and codecs
+            if let Some(video) = screenshot.videos.first() {
+                videos.push(AppVideo {
+                    caption: caption.clone(),
+                    url: video.url.to_string(),
+                });
+            }
             //TODO: better handle multiple images per screenshot
             for image in screenshot.images.into_iter() {
                 if matches!(image.kind, ImageKind::Source) {
                     screenshots.push(AppScreenshot {
-                        caption: screenshot
-                            .caption
-                            .as_ref()
-                            .map_or("", |x| get_translatable(x, locale))
-                            .to_string(),
+                        caption,
                         url: image.url.to_string(),
+                        environment_opt: None,
                     });
                     break;
                 }
             }
         }
 
+        let search_text = AppSearchText::new(name, summary, &keywords);
         Self {
             origin_opt: origin_opt.map(|x| x.to_string()),
+            kind: AppKind::new(&component.kind).unwrap_or(AppKind::DesktopApplication),
             name: name.to_string(),
             summary: summary.to_string(),
-            description,
+            description_markup,
+            developer_name: component
+                .developer_name
+                .as_ref()
+                .map_or("", |x| get_translatable(x, locale))
+                .to_string(),
+            project_group_opt: component.project_group,
+            keywords,
+            license_opt: component.project_license.map(|x| x.0),
             pkgnames: component.pkgname.map_or(Vec::new(), |x| vec![x]),
             categories,
             desktop_ids,
             flatpak_refs,
+            mimetypes: component.mimetypes,
             icons,
+            provides,
+            releases,
             screenshots,
+            videos,
+            content_rating,
+            urls,
+            relations: Vec::new(),
+            verified_opt: None,
+            size_opt,
+            search_text,
         }
     }
 }
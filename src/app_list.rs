@@ -0,0 +1,83 @@
+// Exported list of installed apps, used to restore apps on another system
+
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{AppInfo, Package, SYSTEM_ID};
+
+pub const APP_LIST_VERSION: u32 = 1;
+pub const APP_LIST_FILE_NAME: &'static str = "cosmic-store-apps.json";
+
+/// Default location of exported app lists
+pub fn app_list_path() -> PathBuf {
+    dirs::document_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_default()
+        .join(APP_LIST_FILE_NAME)
+}
+
+/// App from an imported list that is not installed on this system
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AppListMissing {
+    pub backend_name: &'static str,
+    pub id: String,
+    pub info: Arc<AppInfo>,
+    pub selected: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AppListEntry {
+    pub id: String,
+    pub backend: String,
+    /// Remote or repository the app was installed from
+    #[serde(default, rename = "origin", skip_serializing_if = "Option::is_none")]
+    pub origin_opt: Option<String>,
+    #[serde(default)]
+    pub version: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AppList {
+    pub version: u32,
+    pub apps: Vec<AppListEntry>,
+}
+
+impl AppList {
+    pub fn new(installed: &[(&'static str, Package)]) -> Self {
+        let apps = installed
+            .iter()
+            // Packages without appstream data cannot be matched on another system
+            .filter(|(_, package)| package.id != SYSTEM_ID)
+            .map(|(backend_name, package)| AppListEntry {
+                id: package.id.clone(),
+                backend: backend_name.to_string(),
+                origin_opt: package.info.origin_opt.clone(),
+                version: package.version.clone(),
+            })
+            .collect();
+        Self {
+            version: APP_LIST_VERSION,
+            apps,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let data = fs::read(path)?;
+        let app_list: Self = serde_json::from_slice(&data)?;
+        if app_list.version > APP_LIST_VERSION {
+            return Err(format!("unsupported app list version {}", app_list.version).into());
+        }
+        Ok(app_list)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let data = serde_json::to_vec_pretty(self)?;
+        fs::write(path, data)?;
+        Ok(())
+    }
+}
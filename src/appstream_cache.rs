@@ -1,5 +1,5 @@
 use appstream::{
-    enums::{ComponentKind, Icon, ImageKind, Launchable},
+    enums::{Icon, ImageKind, Launchable},
     url::Url,
     xmltree, Component, Image, ParseError, Screenshot,
 };
@@ -18,11 +18,39 @@ use std::{
     time::{Instant, SystemTime},
 };
 
-use crate::{AppIcon, AppInfo};
+use crate::{
+    app_info::{
+        markup_text, relations_from_xml, relations_from_yaml, screenshot_environments_from_xml,
+        verified_from_xml, verified_from_yaml,
+    },
+    search_fold, AppIcon, AppInfo, AppKind,
+};
 
 const PREFIXES: &'static [&'static str] = &["/usr/share", "/var/lib", "/var/cache"];
 const CATALOGS: &'static [&'static str] = &["swcatalog", "app-info"];
 
+/// Colored avatar with the first letter of the app name, for apps without a resolvable icon
+fn fallback_icon(id: &str, name: &str) -> widget::icon::Handle {
+    const COLORS: [&str; 8] = [
+        "#e01b24", "#ff7800", "#e5a50a", "#2ec27e", "#1c71d8", "#9141ac", "#986a44", "#63452c",
+    ];
+    // Simple string hash so the color is the same on every run
+    let hash = id
+        .bytes()
+        .fold(0u32, |hash, b| hash.wrapping_mul(31).wrapping_add(b.into()));
+    let color = COLORS[hash as usize % COLORS.len()];
+    let letter: String = name
+        .chars()
+        .find(|c| c.is_alphanumeric())
+        .map(|c| c.to_uppercase().collect())
+        .unwrap_or_default();
+    let svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="128" height="128" viewBox="0 0 128 128"><rect x="8" y="8" width="112" height="112" rx="24" fill="{}"/><text x="64" y="64" dy="0.35em" text-anchor="middle" font-family="sans-serif" font-size="64" font-weight="bold" fill="#ffffff">{}</text></svg>"##,
+        color, letter
+    );
+    widget::icon::from_svg_bytes(svg.into_bytes())
+}
+
 #[derive(
     Clone,
     Copy,
@@ -51,6 +79,8 @@ pub struct AppstreamCache {
     pub locale: String,
     pub infos: HashMap<String, Arc<AppInfo>>,
     pub pkgnames: HashMap<String, HashSet<String>>,
+    /// Folded developer names and project groups to ids
+    pub developers: HashMap<String, HashSet<String>>,
 }
 
 impl AppstreamCache {
@@ -170,7 +200,12 @@ impl AppstreamCache {
 
     /// Versioned filename of cache
     fn cache_filename() -> &'static str {
-        "appstream_cache-v0-1.bitcode-v0-5"
+        "appstream_cache-v0-18.bitcode-v0-5"
+    }
+
+    /// Versioned filename of long descriptions, which are stored apart from the cache
+    fn descriptions_filename() -> &'static str {
+        "descriptions-v0-1.bitcode-v0-5"
     }
 
     /// Remove all files from cache not matching filename
@@ -222,7 +257,9 @@ impl AppstreamCache {
                 continue;
             }
 
-            if entry.file_name() != Self::cache_filename() {
+            if entry.file_name() != Self::cache_filename()
+                && entry.file_name() != Self::descriptions_filename()
+            {
                 match fs::remove_file(&path) {
                     Ok(()) => {
                         log::info!("removed outdated cache file {:?}", entry.path());
@@ -284,9 +321,10 @@ impl AppstreamCache {
             return false;
         }
 
-        // Everything matches, copy infos and pkgnames
+        // Everything matches, copy infos, pkgnames, and developers
         self.infos = cache.infos;
         self.pkgnames = cache.pkgnames;
+        self.developers = cache.developers;
 
         let duration = start.elapsed();
         log::info!("loaded cache {:?} in {:?}", cache_name, duration);
@@ -328,14 +366,90 @@ impl AppstreamCache {
             }
         }
 
+        // Descriptions are folded here so searching them does not need to fold every keystroke
+        let descriptions: HashMap<String, String> = self
+            .infos
+            .par_iter()
+            .filter_map(|(id, info)| {
+                let description = markup_text(&info.description_markup).ok()?;
+                Some((id.clone(), search_fold(&description)))
+            })
+            .collect();
+        let descriptions_path = cache_dir.join(Self::descriptions_filename());
+        match bitcode::encode(&descriptions) {
+            Ok(bitcode) => match atomicwrites::AtomicFile::new(
+                &descriptions_path,
+                atomicwrites::OverwriteBehavior::AllowOverwrite,
+            )
+            .write(|file| file.write_all(&bitcode))
+            {
+                Ok(()) => {}
+                Err(err) => {
+                    log::warn!(
+                        "failed to write descriptions {:?}: {}",
+                        descriptions_path,
+                        err
+                    );
+                }
+            },
+            Err(err) => {
+                log::warn!("failed to encode descriptions {:?}: {}", cache_name, err);
+            }
+        }
+
         let duration = start.elapsed();
         log::info!("saved cache {:?} in {:?}", cache_name, duration);
     }
 
+    /// Load folded long descriptions saved with the cache, returns an empty map on errors
+    pub fn load_descriptions(&self, cache_name: &str) -> HashMap<String, String> {
+        let start = Instant::now();
+
+        let descriptions_path = match self.cache_dir(cache_name) {
+            Some(cache_dir) => cache_dir.join(Self::descriptions_filename()),
+            None => {
+                log::warn!("failed to find cache directory");
+                return HashMap::new();
+            }
+        };
+
+        let data = match fs::read(&descriptions_path) {
+            Ok(ok) => ok,
+            Err(err) => {
+                log::warn!(
+                    "failed to read descriptions {:?}: {}",
+                    descriptions_path,
+                    err
+                );
+                return HashMap::new();
+            }
+        };
+
+        let descriptions = match bitcode::decode::<HashMap<String, String>>(&data) {
+            Ok(ok) => ok,
+            Err(err) => {
+                log::warn!("failed to decode descriptions {:?}: {}", cache_name, err);
+                return HashMap::new();
+            }
+        };
+
+        let duration = start.elapsed();
+        log::info!("loaded descriptions {:?} in {:?}", cache_name, duration);
+        descriptions
+    }
+
+    /// Returns true if the app id has the developer name or project group, which must be folded
+    pub fn developer_matches(&self, developer: &str, id: &str) -> bool {
+        self.developers
+            .get(developer)
+            .map_or(false, |ids| ids.contains(id))
+    }
+
     /// Reload from original package sources
     pub fn load_original(&mut self) {
         self.infos.clear();
         self.pkgnames.clear();
+        self.developers.clear();
 
         let path_results: Vec<_> = self
             .path_tags
@@ -413,6 +527,18 @@ impl AppstreamCache {
                         .or_insert_with(|| HashSet::new())
                         .insert(id.clone());
                 }
+                for developer in [Some(&info.developer_name), info.project_group_opt.as_ref()]
+                    .into_iter()
+                    .flatten()
+                {
+                    let key = search_fold(developer.trim());
+                    if !key.is_empty() {
+                        self.developers
+                            .entry(key)
+                            .or_insert_with(|| HashSet::new())
+                            .insert(id.clone());
+                    }
+                }
                 match self.infos.insert(id.clone(), info) {
                     Some(_old) => {
                         //TODO: merge based on priority
@@ -462,7 +588,7 @@ impl AppstreamCache {
         None
     }
 
-    pub fn icon(&self, info: &AppInfo) -> widget::icon::Handle {
+    pub fn icon(&self, id: &str, info: &AppInfo) -> widget::icon::Handle {
         let mut icon_opt = None;
         let mut cached_size = 0;
         for info_icon in info.icons.iter() {
@@ -490,11 +616,7 @@ impl AppstreamCache {
                 }
             }
         }
-        icon_opt.unwrap_or_else(|| {
-            widget::icon::from_name("package-x-generic")
-                .size(128)
-                .handle()
-        })
+        icon_opt.unwrap_or_else(|| fallback_icon(id, &info.name))
     }
 
     fn parse_xml<R: Read>(
@@ -519,21 +641,20 @@ impl AppstreamCache {
                     if &*e.name == "component" {
                         match Component::try_from(e) {
                             Ok(component) => {
-                                if component.kind != ComponentKind::DesktopApplication {
-                                    // Skip anything that is not a desktop application
-                                    //TODO: should we allow more components?
+                                if AppKind::new(&component.kind).is_none() {
+                                    // Skip anything that is not an application or system component
                                     return None;
                                 }
 
                                 let id = component.id.to_string();
-                                return Some((
-                                    id,
-                                    Arc::new(AppInfo::new(
-                                        origin_opt.map(|x| x.as_str()),
-                                        component,
-                                        locale,
-                                    )),
-                                ));
+                                let mut info =
+                                    AppInfo::new(origin_opt.map(|x| x.as_str()), component, locale);
+                                info.relations = relations_from_xml(e);
+                                info.verified_opt = verified_from_xml(e);
+                                info.set_screenshot_environments(
+                                    &screenshot_environments_from_xml(e),
+                                );
+                                return Some((id, Arc::new(info)));
                             }
                             Err(err) => {
                                 log::error!(
@@ -584,9 +705,8 @@ impl AppstreamCache {
             } else {
                 match Component::deserialize(&value) {
                     Ok(mut component) => {
-                        if component.kind != ComponentKind::DesktopApplication {
-                            // Skip anything that is not a desktop application
-                            //TODO: should we allow more components?
+                        if AppKind::new(&component.kind).is_none() {
+                            // Skip anything that is not an application or system component
                             continue;
                         }
 
@@ -714,6 +834,7 @@ impl AppstreamCache {
                             }
                         }
 
+                        let mut screenshot_environments = HashMap::new();
                         if let Some(screenshots) = value["Screenshots"].as_sequence() {
                             if &component.id.0 == "com.system76.CosmicEdit" {
                                 println!("{:?}", screenshots);
@@ -732,6 +853,14 @@ impl AppstreamCache {
                                                 }
                                                 None => path_str.to_string(),
                                             };
+                                            if let Some(environment) =
+                                                screenshot["environment"].as_str()
+                                            {
+                                                screenshot_environments.insert(
+                                                    url_str.clone(),
+                                                    environment.to_string(),
+                                                );
+                                            }
                                             match Url::parse(&url_str) {
                                                 Ok(url) => {
                                                     images.push(Image {
@@ -768,10 +897,11 @@ impl AppstreamCache {
                         }
 
                         let id = component.id.to_string();
-                        infos.push((
-                            id,
-                            Arc::new(AppInfo::new(origin_opt.as_deref(), component, locale)),
-                        ));
+                        let mut info = AppInfo::new(origin_opt.as_deref(), component, locale);
+                        info.relations = relations_from_yaml(&value);
+                        info.verified_opt = verified_from_yaml(&value);
+                        info.set_screenshot_environments(&screenshot_environments);
+                        infos.push((id, Arc::new(info)));
                     }
                     Err(err) => {
                         log::error!("failed to parse {:?} in {:?}: {}", value["ID"], path, err);
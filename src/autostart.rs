@@ -0,0 +1,42 @@
+// Autostart entry, used to check for updates in the background after logging in
+// https://specifications.freedesktop.org/autostart-spec/latest/
+
+use std::{error::Error, fs, path::PathBuf};
+
+const AUTOSTART_FILE_NAME: &'static str = "com.system76.CosmicStore.desktop";
+const AUTOSTART_ENTRY: &'static str = "[Desktop Entry]
+Name=COSMIC App Store
+Exec=cosmic-store --hidden
+Terminal=false
+Type=Application
+Icon=com.system76.CosmicStore
+NoDisplay=true
+X-GNOME-Autostart-enabled=true
+";
+
+fn autostart_path() -> Option<PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join("autostart")
+            .join(AUTOSTART_FILE_NAME),
+    )
+}
+
+/// Returns true if the store starts hidden after logging in
+pub fn enabled() -> bool {
+    autostart_path().map_or(false, |path| path.is_file())
+}
+
+/// Install or remove the autostart entry
+pub fn set_enabled(enabled: bool) -> Result<(), Box<dyn Error>> {
+    let path = autostart_path().ok_or("failed to find config directory")?;
+    if enabled {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, AUTOSTART_ENTRY)?;
+    } else if path.exists() {
+        fs::remove_file(&path)?;
+    }
+    Ok(())
+}
@@ -1,14 +1,80 @@
-use libflatpak::{gio::Cancellable, prelude::*, Installation, Ref, RefKind, Transaction};
+use libflatpak::{
+    gio::Cancellable, glib::Bytes, prelude::*, Installation, Ref, RefKind, Remote, Transaction,
+};
 use std::{
     cell::Cell,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     error::Error,
+    fs,
+    path::Path,
     sync::{Arc, Mutex},
 };
 
-use super::{Backend, Package};
+use super::{Backend, Package, SourceTrust, StorageUsage};
 use crate::{AppInfo, AppstreamCache, OperationKind};
 
+/// User installation first, then the system installation. Operations on the system
+/// installation are run by flatpak-system-helper after polkit authorization, so the store
+/// does not need to run as root.
+fn installations() -> Result<Vec<Installation>, Box<dyn Error>> {
+    let mut installations = vec![Installation::new_user(Cancellable::NONE)?];
+    match Installation::new_system(Cancellable::NONE) {
+        Ok(inst) => installations.push(inst),
+        Err(err) => {
+            log::warn!("failed to open flatpak system installation: {}", err);
+        }
+    }
+    Ok(installations)
+}
+
+fn installation_name(inst: &Installation) -> &'static str {
+    if inst.is_user() {
+        "user"
+    } else {
+        "system"
+    }
+}
+
+fn transaction(
+    inst: &Installation,
+    callback: Arc<Mutex<Box<dyn FnMut(f32) + 'static>>>,
+) -> Result<Transaction, Box<dyn Error>> {
+    let total_ops = Arc::new(Cell::new(0));
+    let tx = Transaction::for_installation(inst, Cancellable::NONE)?;
+    {
+        let total_ops = total_ops.clone();
+        tx.connect_ready(move |tx| {
+            total_ops.set(tx.operations().len());
+            true
+        });
+    }
+    let started_ops = Arc::new(Cell::new(0));
+    tx.connect_new_operation(move |_, op, progress| {
+        let current_op = started_ops.get();
+        started_ops.set(current_op + 1);
+        let progress_per_op = 100.0 / (total_ops.get().max(started_ops.get()) as f32);
+        log::info!(
+            "Operation {}: {} {:?}",
+            current_op,
+            op.operation_type(),
+            op.get_ref()
+        );
+        let callback = callback.clone();
+        progress.connect_changed(move |progress| {
+            log::info!(
+                "{}: {}%",
+                progress.status().unwrap_or_default(),
+                progress.progress()
+            );
+            let op_progress = (progress.progress() as f32) / 100.0;
+            let total_progress = ((current_op as f32) + op_progress) * progress_per_op;
+            let mut callback = callback.lock().unwrap();
+            callback(total_progress)
+        });
+    });
+    Ok(tx)
+}
+
 #[derive(Debug)]
 pub struct Flatpak {
     appstream_cache: AppstreamCache,
@@ -16,11 +82,23 @@ pub struct Flatpak {
 
 impl Flatpak {
     pub fn new(locale: &str) -> Result<Self, Box<dyn Error>> {
-        //TODO: should we support system installations?
-        let inst = Installation::new_user(Cancellable::NONE)?;
         let mut paths = Vec::new();
         let mut icons_paths = Vec::new();
-        for remote in inst.list_remotes(Cancellable::NONE)? {
+        let mut remotes = Vec::new();
+        for inst in installations()? {
+            // One broken installation should not hide the remotes of the others
+            match inst.list_remotes(Cancellable::NONE) {
+                Ok(ok) => remotes.extend(ok),
+                Err(err) => {
+                    log::warn!(
+                        "failed to list remotes of flatpak {} installation: {}",
+                        installation_name(&inst),
+                        err
+                    );
+                }
+            }
+        }
+        for remote in remotes {
             if let Some(appstream_dir) = remote.appstream_dir(None).and_then(|x| x.path()) {
                 let xml_gz_path = appstream_dir.join("appstream.xml.gz");
                 if xml_gz_path.is_file() {
@@ -59,10 +137,11 @@ impl Flatpak {
 
                 Some(Package {
                     id: id.to_string(),
-                    icon: self.appstream_cache.icon(info),
+                    icon: self.appstream_cache.icon(id.as_str(), info),
                     info: info.clone(),
                     version: r.appdata_version().unwrap_or_default().to_string(),
                     extra,
+                    firmware_opt: None,
                 })
             }
             None => {
@@ -83,28 +162,172 @@ impl Backend for Flatpak {
         &self.appstream_cache
     }
 
+    fn descriptions(&self) -> HashMap<String, String> {
+        self.appstream_cache.load_descriptions("flatpak")
+    }
+
+    fn refresh(&self) -> Result<(), Box<dyn Error>> {
+        for inst in installations()? {
+            let remotes = match inst.list_remotes(Cancellable::NONE) {
+                Ok(ok) => ok,
+                Err(err) => {
+                    log::warn!(
+                        "failed to list remotes of flatpak {} installation: {}",
+                        installation_name(&inst),
+                        err
+                    );
+                    continue;
+                }
+            };
+            for remote in remotes {
+                if remote.is_disabled() {
+                    continue;
+                }
+                let Some(remote_name) = remote.name() else {
+                    continue;
+                };
+                log::info!(
+                    "refreshing appstream for remote {} in {} installation",
+                    remote_name,
+                    installation_name(&inst)
+                );
+                // One broken remote should not keep the others from refreshing
+                if let Err(err) = inst.update_appstream_sync(&remote_name, None, Cancellable::NONE)
+                {
+                    log::warn!(
+                        "failed to refresh appstream for remote {} in {} installation: {}",
+                        remote_name,
+                        installation_name(&inst),
+                        err
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn source_trust(&self, origin_opt: Option<&str>) -> SourceTrust {
+        let Some(origin) = origin_opt else {
+            return SourceTrust::Unknown;
+        };
+        let installations = match installations() {
+            Ok(ok) => ok,
+            Err(err) => {
+                log::warn!("failed to open flatpak installations: {}", err);
+                return SourceTrust::Unknown;
+            }
+        };
+        for inst in installations {
+            match inst.remote_by_name(origin, Cancellable::NONE) {
+                Ok(remote) => {
+                    return if remote.gpg_verify() {
+                        SourceTrust::Signed(Some(
+                            remote
+                                .title()
+                                .map_or_else(|| origin.to_string(), |title| title.to_string()),
+                        ))
+                    } else {
+                        SourceTrust::Unsigned
+                    };
+                }
+                Err(err) => {
+                    log::debug!(
+                        "failed to find remote {} in {} installation: {}",
+                        origin,
+                        installation_name(&inst),
+                        err
+                    );
+                }
+            }
+        }
+        SourceTrust::Unknown
+    }
+
     fn installed(&self) -> Result<Vec<Package>, Box<dyn Error>> {
-        //TODO: should we support system installations?
-        let inst = Installation::new_user(Cancellable::NONE)?;
         let mut packages = Vec::new();
-        //TODO: show non-desktop items?
-        for r in inst.list_installed_refs_by_kind(RefKind::App, Cancellable::NONE)? {
-            if let Some(package) = self.ref_to_package(r) {
-                packages.push(package);
+        for inst in installations()? {
+            //TODO: show non-desktop items?
+            let refs = match inst.list_installed_refs_by_kind(RefKind::App, Cancellable::NONE) {
+                Ok(ok) => ok,
+                Err(err) => {
+                    log::warn!(
+                        "failed to list apps in flatpak {} installation: {}",
+                        installation_name(&inst),
+                        err
+                    );
+                    continue;
+                }
+            };
+            for r in refs {
+                if let Some(package) = self.ref_to_package(r) {
+                    packages.push(package);
+                }
             }
         }
         Ok(packages)
     }
 
+    fn storage_usage(&self) -> Result<Option<StorageUsage>, Box<dyn Error>> {
+        let mut usage = StorageUsage::default();
+        for inst in installations()? {
+            let (unused_refs, refs) =
+                match inst
+                    .list_unused_refs(None, Cancellable::NONE)
+                    .and_then(|unused_refs| {
+                        Ok((unused_refs, inst.list_installed_refs(Cancellable::NONE)?))
+                    }) {
+                    Ok(ok) => ok,
+                    Err(err) => {
+                        log::warn!(
+                            "failed to list refs in flatpak {} installation: {}",
+                            installation_name(&inst),
+                            err
+                        );
+                        continue;
+                    }
+                };
+            let unused: HashSet<String> = unused_refs
+                .iter()
+                .filter_map(|r| r.format_ref())
+                .map(|r_str| r_str.to_string())
+                .collect();
+            for r in refs {
+                let size = r.installed_size();
+                if r.kind() == RefKind::App {
+                    usage.apps += size;
+                } else {
+                    usage.runtimes += size;
+                    if r.format_ref()
+                        .map_or(false, |r_str| unused.contains(r_str.as_str()))
+                    {
+                        usage.unused += size;
+                    }
+                }
+            }
+        }
+        Ok(Some(usage))
+    }
+
     fn updates(&self) -> Result<Vec<Package>, Box<dyn Error>> {
-        //TODO: should we support system installations?
-        let inst = Installation::new_user(Cancellable::NONE)?;
         let mut packages = Vec::new();
-        for r in inst.list_installed_refs_for_update(Cancellable::NONE)? {
-            // Only show apps
-            if r.kind() == RefKind::App {
-                if let Some(package) = self.ref_to_package(r) {
-                    packages.push(package);
+        for inst in installations()? {
+            let refs = match inst.list_installed_refs_for_update(Cancellable::NONE) {
+                Ok(ok) => ok,
+                Err(err) => {
+                    log::warn!(
+                        "failed to list updates in flatpak {} installation: {}",
+                        installation_name(&inst),
+                        err
+                    );
+                    continue;
+                }
+            };
+            for r in refs {
+                // Only show apps
+                if r.kind() == RefKind::App {
+                    if let Some(package) = self.ref_to_package(r) {
+                        packages.push(package);
+                    }
                 }
             }
         }
@@ -119,41 +342,7 @@ impl Backend for Flatpak {
         callback: Box<dyn FnMut(f32) + 'static>,
     ) -> Result<(), Box<dyn Error>> {
         let callback = Arc::new(Mutex::new(callback));
-        //TODO: should we support system installations?
-        let inst = Installation::new_user(Cancellable::NONE)?;
-        let total_ops = Arc::new(Cell::new(0));
-        let tx = Transaction::for_installation(&inst, Cancellable::NONE)?;
-        {
-            let total_ops = total_ops.clone();
-            tx.connect_ready(move |tx| {
-                total_ops.set(tx.operations().len());
-                true
-            });
-        }
-        let started_ops = Arc::new(Cell::new(0));
-        tx.connect_new_operation(move |_, op, progress| {
-            let current_op = started_ops.get();
-            started_ops.set(current_op + 1);
-            let progress_per_op = 100.0 / (total_ops.get().max(started_ops.get()) as f32);
-            log::info!(
-                "Operation {}: {} {:?}",
-                current_op,
-                op.operation_type(),
-                op.get_ref()
-            );
-            let callback = callback.clone();
-            progress.connect_changed(move |progress| {
-                log::info!(
-                    "{}: {}%",
-                    progress.status().unwrap_or_default(),
-                    progress.progress()
-                );
-                let op_progress = (progress.progress() as f32) / 100.0;
-                let total_progress = ((current_op as f32) + op_progress) * progress_per_op;
-                let mut callback = callback.lock().unwrap();
-                callback(total_progress)
-            });
-        });
+        let installations = installations()?;
         match kind {
             OperationKind::Install => {
                 for r_str in info.flatpak_refs.iter() {
@@ -164,34 +353,84 @@ impl Backend for Flatpak {
                             continue;
                         }
                     };
-                    for remote in inst.list_remotes(Cancellable::NONE)? {
-                        let Some(remote_name) = remote.name() else {
-                            continue;
-                        };
-                        match inst.fetch_remote_ref_sync(
-                            &remote_name,
-                            r.kind(),
-                            &r.name().unwrap_or_default(),
-                            r.arch().as_deref(),
-                            r.branch().as_deref(),
-                            Cancellable::NONE,
-                        ) {
-                            Ok(_) => {}
+                    // Remotes of the user installation are preferred, as they do not need authorization
+                    for inst in installations.iter() {
+                        let mut remotes = match inst.list_remotes(Cancellable::NONE) {
+                            Ok(ok) => ok,
                             Err(err) => {
-                                log::info!("failed to find {} in {}: {}", id, remote_name, err);
+                                log::warn!(
+                                    "failed to list remotes of flatpak {} installation: {}",
+                                    installation_name(inst),
+                                    err
+                                );
                                 continue;
                             }
                         };
+                        // Prefer the remote that the app info was loaded from
+                        if let Some(origin) = &info.origin_opt {
+                            remotes.sort_by_key(|remote| {
+                                remote.name().as_deref() != Some(origin.as_str())
+                            });
+                        }
+                        for remote in remotes {
+                            let Some(remote_name) = remote.name() else {
+                                continue;
+                            };
+                            match inst.fetch_remote_ref_sync(
+                                &remote_name,
+                                r.kind(),
+                                &r.name().unwrap_or_default(),
+                                r.arch().as_deref(),
+                                r.branch().as_deref(),
+                                Cancellable::NONE,
+                            ) {
+                                Ok(_) => {}
+                                Err(err) => {
+                                    log::info!("failed to find {} in {}: {}", id, remote_name, err);
+                                    continue;
+                                }
+                            };
 
-                        log::info!("installing flatpak {} from remote {}", r_str, remote_name);
-                        tx.add_install(&remote_name, &r_str, &[])?;
+                            log::info!(
+                                "installing flatpak {} from remote {} in {} installation",
+                                r_str,
+                                remote_name,
+                                installation_name(inst)
+                            );
+                            let tx = transaction(inst, callback.clone())?;
+                            tx.add_install(&remote_name, &r_str, &[])?;
+                            tx.run(Cancellable::NONE)?;
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+            OperationKind::InstallFile => {
+                let inst = &installations[0];
+                let path = Path::new(id);
+                let data = Bytes::from_owned(fs::read(path)?);
+                match path.extension().and_then(|x| x.to_str()) {
+                    Some("flatpakref") => {
+                        log::info!("installing flatpakref {}", id);
+                        let tx = transaction(inst, callback.clone())?;
+                        tx.add_install_flatpakref(&data)?;
                         tx.run(Cancellable::NONE)?;
-                        return Ok(());
                     }
+                    Some("flatpakrepo") => {
+                        // The remote is named after the file, like flathub.flatpakrepo
+                        let name = path
+                            .file_stem()
+                            .and_then(|x| x.to_str())
+                            .ok_or_else(|| format!("invalid flatpakrepo name {}", id))?;
+                        log::info!("adding remote {} from {}", name, id);
+                        let remote = Remote::from_file(name, &data)?;
+                        inst.add_remote(&remote, true, Cancellable::NONE)?;
+                    }
+                    _ => return Err(format!("{} is not a supported package file", id).into()),
                 }
+                return Ok(());
             }
-            OperationKind::Uninstall => {
-                //TODO: deduplicate code
+            OperationKind::Uninstall | OperationKind::Update => {
                 for r_str in info.flatpak_refs.iter() {
                     let r = match Ref::parse(r_str) {
                         Ok(ok) => ok,
@@ -200,55 +439,78 @@ impl Backend for Flatpak {
                             continue;
                         }
                     };
-                    match inst.installed_ref(
-                        r.kind(),
-                        &r.name().unwrap_or_default(),
-                        r.arch().as_deref(),
-                        r.branch().as_deref(),
-                        Cancellable::NONE,
-                    ) {
-                        Ok(_) => {}
-                        Err(err) => {
-                            log::info!("failed to find {} installed locally: {}", id, err);
-                            continue;
-                        }
-                    };
+                    // Use the installation that the app is installed in
+                    for inst in installations.iter() {
+                        match inst.installed_ref(
+                            r.kind(),
+                            &r.name().unwrap_or_default(),
+                            r.arch().as_deref(),
+                            r.branch().as_deref(),
+                            Cancellable::NONE,
+                        ) {
+                            Ok(_) => {}
+                            Err(err) => {
+                                log::info!(
+                                    "failed to find {} in {} installation: {}",
+                                    id,
+                                    installation_name(inst),
+                                    err
+                                );
+                                continue;
+                            }
+                        };
 
-                    log::info!("uninstalling flatpak {}", r_str);
-                    tx.add_uninstall(&r_str)?;
-                    tx.run(Cancellable::NONE)?;
-                    return Ok(());
+                        let tx = transaction(inst, callback.clone())?;
+                        if kind == OperationKind::Uninstall {
+                            log::info!(
+                                "uninstalling flatpak {} from {} installation",
+                                r_str,
+                                installation_name(inst)
+                            );
+                            tx.add_uninstall(&r_str)?;
+                        } else {
+                            log::info!(
+                                "updating flatpak {} in {} installation",
+                                r_str,
+                                installation_name(inst)
+                            );
+                            tx.add_update(&r_str, &[], None)?;
+                        }
+                        tx.run(Cancellable::NONE)?;
+                        return Ok(());
+                    }
                 }
             }
-            OperationKind::Update => {
-                //TODO: deduplicate code
-                for r_str in info.flatpak_refs.iter() {
-                    let r = match Ref::parse(r_str) {
+            OperationKind::RemoveUnused => {
+                for inst in installations.iter() {
+                    let refs = match inst.list_unused_refs(None, Cancellable::NONE) {
                         Ok(ok) => ok,
                         Err(err) => {
-                            log::warn!("failed to parse flatpak ref {:?}: {}", r_str, err);
+                            log::warn!(
+                                "failed to list unused refs in flatpak {} installation: {}",
+                                installation_name(inst),
+                                err
+                            );
                             continue;
                         }
                     };
-                    match inst.installed_ref(
-                        r.kind(),
-                        &r.name().unwrap_or_default(),
-                        r.arch().as_deref(),
-                        r.branch().as_deref(),
-                        Cancellable::NONE,
-                    ) {
-                        Ok(_) => {}
-                        Err(err) => {
-                            log::info!("failed to find {} installed locally: {}", id, err);
-                            continue;
+                    if refs.is_empty() {
+                        continue;
+                    }
+                    let tx = transaction(inst, callback.clone())?;
+                    for r in refs {
+                        if let Some(r_str) = r.format_ref() {
+                            log::info!(
+                                "removing unused flatpak {} from {} installation",
+                                r_str,
+                                installation_name(inst)
+                            );
+                            tx.add_uninstall(&r_str)?;
                         }
-                    };
-
-                    log::info!("updating flatpak {}", r_str);
-                    tx.add_update(&r_str, &[], None)?;
+                    }
                     tx.run(Cancellable::NONE)?;
-                    return Ok(());
                 }
+                return Ok(());
             }
         }
         Err(format!("package {id} not found").into())
@@ -0,0 +1,291 @@
+// Device firmware updates from LVFS, through the fwupd daemon
+// https://fwupd.github.io/libfwupd/
+
+use cosmic::{iced::futures, widget};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, error::Error, fs, os::fd::AsRawFd, sync::Arc};
+use zbus::{
+    blocking::Connection,
+    dbus_proxy,
+    zvariant::{Array, Fd, OwnedValue, Value},
+};
+
+use super::{Backend, FirmwareRelease, FirmwareUrgency, Package};
+use crate::{
+    app_info::{parse_markup, AppRelease, AppSearchText},
+    http, AppInfo, AppstreamCache, OperationKind,
+};
+
+// https://github.com/fwupd/fwupd/blob/main/libfwupd/fwupd-enums.h
+const DEVICE_FLAG_UPDATABLE: u64 = 1 << 1;
+const DEVICE_FLAG_REQUIRE_AC: u64 = 1 << 3;
+const DEVICE_FLAG_NEEDS_REBOOT: u64 = 1 << 8;
+const DEVICE_FLAG_NEEDS_SHUTDOWN: u64 = 1 << 17;
+
+#[dbus_proxy(
+    interface = "org.freedesktop.fwupd",
+    default_service = "org.freedesktop.fwupd",
+    default_path = "/"
+)]
+trait Daemon {
+    fn get_devices(&self) -> zbus::Result<Vec<HashMap<String, OwnedValue>>>;
+    fn get_upgrades(&self, device_id: &str) -> zbus::Result<Vec<HashMap<String, OwnedValue>>>;
+    fn install(&self, id: &str, handle: Fd, options: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+    #[dbus_proxy(property)]
+    fn daemon_version(&self) -> zbus::Result<String>;
+}
+
+fn dict_str(dict: &HashMap<String, OwnedValue>, key: &str) -> Option<String> {
+    dict.get(key)?
+        .downcast_ref::<str>()
+        .map(|value| value.to_string())
+}
+
+fn dict_u32(dict: &HashMap<String, OwnedValue>, key: &str) -> Option<u32> {
+    dict.get(key)?.downcast_ref::<u32>().copied()
+}
+
+fn dict_u64(dict: &HashMap<String, OwnedValue>, key: &str) -> Option<u64> {
+    dict.get(key)?.downcast_ref::<u64>().copied()
+}
+
+fn dict_strs(dict: &HashMap<String, OwnedValue>, key: &str) -> Vec<String> {
+    dict.get(key)
+        .and_then(|value| value.downcast_ref::<Array>())
+        .map_or(Vec::new(), |array| {
+            array
+                .get()
+                .iter()
+                .filter_map(|value| value.downcast_ref::<str>().map(|x| x.to_string()))
+                .collect()
+        })
+}
+
+/// Hex encoded checksum of data, using the algorithm that matches the length of the expected
+/// checksum
+fn checksum_matches(data: &[u8], checksum: &str) -> Option<bool> {
+    let digest: Vec<u8> = match checksum.len() {
+        40 => Sha1::digest(data).to_vec(),
+        64 => Sha256::digest(data).to_vec(),
+        _ => return None,
+    };
+    let mut hex = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    Some(hex.eq_ignore_ascii_case(checksum))
+}
+
+#[derive(Debug)]
+pub struct Fwupd {
+    connection: Connection,
+    // Firmware has no appstream data of its own, releases come from fwupd
+    appstream_cache: AppstreamCache,
+}
+
+impl Fwupd {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        let connection = Connection::system()?;
+        // Fails if the daemon is not installed
+        let version = DaemonProxyBlocking::new(&connection)?.daemon_version()?;
+        log::info!("fwupd daemon version {}", version);
+        Ok(Self {
+            connection,
+            appstream_cache: AppstreamCache::default(),
+        })
+    }
+
+    fn daemon(&self) -> Result<DaemonProxyBlocking<'_>, Box<dyn Error>> {
+        Ok(DaemonProxyBlocking::new(&self.connection)?)
+    }
+
+    fn package(
+        &self,
+        device: &HashMap<String, OwnedValue>,
+        release: &HashMap<String, OwnedValue>,
+    ) -> Option<Package> {
+        let id = dict_str(device, "DeviceId")?;
+        let name = dict_str(device, "Name").unwrap_or_else(|| id.clone());
+        let version = dict_str(release, "Version")?;
+        let flags = dict_u64(device, "Flags").unwrap_or(0);
+        let summary = dict_str(release, "Summary")
+            .or_else(|| dict_str(device, "Summary"))
+            .unwrap_or_default();
+        let description_opt = dict_str(release, "Description").and_then(|description| {
+            match parse_markup(&description) {
+                Ok(ok) => Some(ok),
+                Err(err) => {
+                    log::warn!(
+                        "failed to parse description of {:?} release {:?}: {}",
+                        id,
+                        version,
+                        err
+                    );
+                    None
+                }
+            }
+        });
+        let icon_name = dict_strs(device, "Icon")
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| "application-x-firmware".to_string());
+        let firmware = FirmwareRelease {
+            current_version: dict_str(device, "Version").unwrap_or_default(),
+            urgency: match dict_u32(release, "Urgency") {
+                Some(1) => FirmwareUrgency::Low,
+                Some(2) => FirmwareUrgency::Medium,
+                Some(3) => FirmwareUrgency::High,
+                Some(4) => FirmwareUrgency::Critical,
+                _ => FirmwareUrgency::Unknown,
+            },
+            issues: dict_strs(release, "Issues"),
+            requires_ac: flags & DEVICE_FLAG_REQUIRE_AC != 0,
+            needs_reboot: flags & (DEVICE_FLAG_NEEDS_REBOOT | DEVICE_FLAG_NEEDS_SHUTDOWN) != 0,
+            detach_caption_opt: dict_str(release, "DetachCaption"),
+        };
+        Some(Package {
+            id,
+            icon: widget::icon::from_name(icon_name).size(128).handle(),
+            info: Arc::new(AppInfo {
+                origin_opt: dict_str(release, "RemoteId"),
+                search_text: AppSearchText::new(&name, &summary, &[]),
+                summary,
+                developer_name: dict_str(device, "Vendor").unwrap_or_default(),
+                license_opt: dict_str(release, "License"),
+                releases: vec![AppRelease {
+                    version: version.clone(),
+                    timestamp_opt: dict_u64(release, "Created").map(|x| x as i64),
+                    description_opt,
+                }],
+                size_opt: dict_u64(release, "Size"),
+                ..AppInfo::package_file(name)
+            }),
+            version,
+            extra: HashMap::new(),
+            firmware_opt: Some(firmware),
+        })
+    }
+}
+
+impl Backend for Fwupd {
+    fn load_cache(&mut self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn info_cache(&self) -> &AppstreamCache {
+        &self.appstream_cache
+    }
+
+    fn descriptions(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    fn refresh(&self) -> Result<(), Box<dyn Error>> {
+        // Metadata is refreshed by fwupd-refresh.timer, which also verifies its signature
+        Ok(())
+    }
+
+    fn installed(&self) -> Result<Vec<Package>, Box<dyn Error>> {
+        // Devices are only shown when they have updates
+        Ok(Vec::new())
+    }
+
+    fn updates(&self) -> Result<Vec<Package>, Box<dyn Error>> {
+        let daemon = self.daemon()?;
+        let mut packages = Vec::new();
+        for device in daemon.get_devices()? {
+            if dict_u64(&device, "Flags").unwrap_or(0) & DEVICE_FLAG_UPDATABLE == 0 {
+                continue;
+            }
+            let Some(device_id) = dict_str(&device, "DeviceId") else {
+                continue;
+            };
+            // Errors are returned for devices without upgrades
+            let releases = match daemon.get_upgrades(&device_id) {
+                Ok(ok) => ok,
+                Err(err) => {
+                    log::debug!("no upgrades for firmware device {}: {}", device_id, err);
+                    continue;
+                }
+            };
+            // Releases are sorted newest first
+            if let Some(package) = releases
+                .first()
+                .and_then(|release| self.package(&device, release))
+            {
+                packages.push(package);
+            }
+        }
+        Ok(packages)
+    }
+
+    fn operation(
+        &self,
+        kind: OperationKind,
+        package_id: &str,
+        _info: &AppInfo,
+        mut f: Box<dyn FnMut(f32) + 'static>,
+    ) -> Result<(), Box<dyn Error>> {
+        if kind != OperationKind::Update {
+            return Err(format!("{:?} is not supported for firmware", kind).into());
+        }
+        let daemon = self.daemon()?;
+        let releases = daemon.get_upgrades(package_id)?;
+        let release = releases
+            .first()
+            .ok_or_else(|| format!("no firmware update for {}", package_id))?;
+        let uri = dict_strs(release, "Locations")
+            .into_iter()
+            .next()
+            .or_else(|| dict_str(release, "Uri"))
+            .ok_or_else(|| format!("no download location for {}", package_id))?;
+
+        let cache_dir = dirs::cache_dir()
+            .ok_or("failed to find cache directory")?
+            .join("cosmic-store")
+            .join("firmware");
+        fs::create_dir_all(&cache_dir)?;
+        let file_name = uri.rsplit('/').next().unwrap_or("firmware.cab");
+        let path = cache_dir.join(file_name);
+
+        log::info!("downloading firmware {} to {:?}", uri, path);
+        f(0.0);
+        // Operations run on blocking threads of the async runtime, so reqwest can be used. The
+        // partial file of an interrupted update is continued
+        futures::executor::block_on(http::download_file(&uri, &path, |progress| {
+            f(progress / 2.0)
+        }))?;
+        let data = fs::read(&path)?;
+        let checksums = dict_strs(release, "Checksum");
+        match checksums
+            .iter()
+            .find_map(|checksum| checksum_matches(&data, checksum))
+        {
+            Some(true) => {}
+            Some(false) => {
+                // Start over on the next attempt
+                if let Err(err) = fs::remove_file(&path) {
+                    log::warn!("failed to remove {:?}: {}", path, err);
+                }
+                return Err(format!("checksum mismatch for {}", uri).into());
+            }
+            None => {
+                // The daemon still verifies the signature of the cabinet archive
+                log::warn!("no supported checksum for {}", uri);
+            }
+        }
+        f(50.0);
+        let file = fs::File::open(&path)?;
+
+        log::info!("installing firmware {:?} on {}", path, package_id);
+        //TODO: show progress from the daemon Percentage property
+        let res = daemon.install(package_id, Fd::from(file.as_raw_fd()), HashMap::new());
+        if let Err(err) = fs::remove_file(&path) {
+            log::warn!("failed to remove {:?}: {}", path, err);
+        }
+        res?;
+        f(100.0);
+        Ok(())
+    }
+}
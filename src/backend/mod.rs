@@ -1,8 +1,8 @@
 use cosmic::widget;
 use rayon::prelude::*;
-use std::{collections::HashMap, error::Error, fmt, sync::Arc, time::Instant};
+use std::{collections::HashMap, error::Error, fmt, path::Path, sync::Arc, time::Instant};
 
-use crate::{AppInfo, AppstreamCache, OperationKind};
+use crate::{AppInfo, AppProvide, AppstreamCache, OperationKind};
 
 #[cfg(feature = "flatpak")]
 mod flatpak;
@@ -10,6 +10,33 @@ mod flatpak;
 #[cfg(feature = "packagekit")]
 mod packagekit;
 
+#[cfg(feature = "fwupd")]
+mod fwupd;
+
+/// Urgency of a firmware release, as set by the vendor on LVFS
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FirmwareUrgency {
+    Unknown,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+/// Details of a firmware update that are shown before installing it
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FirmwareRelease {
+    /// Version currently running on the device
+    pub current_version: String,
+    pub urgency: FirmwareUrgency,
+    /// Security issues fixed by the release, usually CVE identifiers
+    pub issues: Vec<String>,
+    pub requires_ac: bool,
+    pub needs_reboot: bool,
+    /// Instructions to put the device in update mode
+    pub detach_caption_opt: Option<String>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Package {
     pub id: String,
@@ -17,13 +44,58 @@ pub struct Package {
     pub info: Arc<AppInfo>,
     pub version: String,
     pub extra: HashMap<String, String>,
+    /// Set for device firmware updates
+    pub firmware_opt: Option<FirmwareRelease>,
+}
+
+/// Whether packages from a remote or repository have their signatures checked
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SourceTrust {
+    /// Signatures are verified, with the name of the signing remote if known
+    Signed(Option<String>),
+    Unsigned,
+    Unknown,
+}
+
+/// Why an optional repository is not enabled by default
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RepoLicense {
+    /// Contains proprietary software, or free software that depends on it
+    Proprietary,
+    /// Contains free software that may be restricted by patents in some countries
+    PatentEncumbered,
+}
+
+/// Optional third-party or non-free repository known to the distribution
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Repo {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub license: RepoLicense,
+}
+
+/// Disk space used by installed packages in bytes
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StorageUsage {
+    pub apps: u64,
+    pub runtimes: u64,
+    /// Runtimes and extensions that no installed app uses, included in runtimes
+    pub unused: u64,
 }
 
 pub trait Backend: fmt::Debug + Send + Sync {
     fn load_cache(&mut self) -> Result<(), Box<dyn Error>>;
     fn info_cache(&self) -> &AppstreamCache;
+    /// Folded long descriptions by app ID, read from disk so they are only kept in memory while
+    /// description search is enabled
+    fn descriptions(&self) -> HashMap<String, String>;
+    /// Download the latest metadata from remotes or repositories
+    fn refresh(&self) -> Result<(), Box<dyn Error>>;
     fn installed(&self) -> Result<Vec<Package>, Box<dyn Error>>;
     fn updates(&self) -> Result<Vec<Package>, Box<dyn Error>>;
+    //TODO: limit connections used by each operation, PackageKit and libflatpak do not expose
+    // this so only the number of parallel operations can be set
     fn operation(
         &self,
         kind: OperationKind,
@@ -31,12 +103,63 @@ pub trait Backend: fmt::Debug + Send + Sync {
         info: &AppInfo,
         f: Box<dyn FnMut(f32) + 'static>,
     ) -> Result<(), Box<dyn Error>>;
+    /// Disk space used by installed packages, None if the backend does not report it
+    fn storage_usage(&self) -> Result<Option<StorageUsage>, Box<dyn Error>> {
+        Ok(None)
+    }
+    /// Optional repositories known to the distribution that can be enabled or disabled
+    fn repos(&self) -> Result<Vec<Repo>, Box<dyn Error>> {
+        Ok(Vec::new())
+    }
+    fn repo_enable(&self, _id: &str, _enabled: bool) -> Result<(), Box<dyn Error>> {
+        Err("enabling repositories is not supported".into())
+    }
+    /// Check if packages from the remote or repository an app was loaded from are signed
+    fn source_trust(&self, _origin_opt: Option<&str>) -> SourceTrust {
+        SourceTrust::Unknown
+    }
+    /// Find packages that provide a command or file path, by default using the binaries listed
+    /// in appstream data
+    fn what_provides(&self, path: &str) -> Result<Vec<Package>, Box<dyn Error>> {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let appstream_cache = self.info_cache();
+        let mut packages = Vec::new();
+        for (id, info) in appstream_cache.infos.iter() {
+            let provides = info.provides.iter().any(|provide| match provide {
+                AppProvide::Binary(binary) => binary == name,
+                _ => false,
+            });
+            if provides {
+                packages.push(Package {
+                    id: id.clone(),
+                    icon: appstream_cache.icon(id, info),
+                    info: info.clone(),
+                    version: String::new(),
+                    extra: HashMap::new(),
+                    firmware_opt: None,
+                });
+            }
+        }
+        Ok(packages)
+    }
 }
 
 pub type Backends = HashMap<&'static str, Arc<dyn Backend>>;
 
-pub fn backends(locale: &str) -> Backends {
+/// Name of the backend that installs a local package file, based on its extension
+pub fn file_backend_name(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()? {
+        "flatpakref" | "flatpakrepo" => Some("flatpak"),
+        "deb" | "rpm" => Some("packagekit"),
+        // Snaps are not supported as there is no snap backend
+        _ => None,
+    }
+}
+
+/// Load all backends, returning errors for backends that failed to load
+pub fn backends(locale: &str) -> (Backends, Vec<String>) {
     let mut backends = Backends::new();
+    let mut errors = Vec::new();
 
     #[cfg(feature = "flatpak")]
     {
@@ -49,6 +172,7 @@ pub fn backends(locale: &str) -> Backends {
             }
             Err(err) => {
                 log::error!("failed to load flatpak backend: {}", err);
+                errors.push(format!("failed to load flatpak backend: {}", err));
             }
         }
     }
@@ -64,22 +188,48 @@ pub fn backends(locale: &str) -> Backends {
             }
             Err(err) => {
                 log::error!("failed to load packagekit backend: {}", err);
+                errors.push(format!("failed to load packagekit backend: {}", err));
             }
         }
     }
 
-    backends.par_iter_mut().for_each(|(backend_name, backend)| {
+    #[cfg(feature = "fwupd")]
+    {
         let start = Instant::now();
-        match Arc::get_mut(backend).unwrap().load_cache() {
-            Ok(()) => {
+        match fwupd::Fwupd::new() {
+            Ok(backend) => {
+                backends.insert("fwupd", Arc::new(backend));
                 let duration = start.elapsed();
-                log::info!("loaded {} backend cache in {:?}", backend_name, duration);
+                log::info!("initialized fwupd backend in {:?}", duration);
             }
             Err(err) => {
-                log::error!("failed to load {} backend cache: {}", backend_name, err);
+                // The fwupd daemon is optional, so this is not shown as an error
+                log::warn!("failed to load fwupd backend: {}", err);
             }
         }
-    });
+    }
+
+    let cache_errors: Vec<String> = backends
+        .par_iter_mut()
+        .filter_map(|(backend_name, backend)| {
+            let start = Instant::now();
+            match Arc::get_mut(backend).unwrap().load_cache() {
+                Ok(()) => {
+                    let duration = start.elapsed();
+                    log::info!("loaded {} backend cache in {:?}", backend_name, duration);
+                    None
+                }
+                Err(err) => {
+                    log::error!("failed to load {} backend cache: {}", backend_name, err);
+                    Some(format!(
+                        "failed to load {} backend cache: {}",
+                        backend_name, err
+                    ))
+                }
+            }
+        })
+        .collect();
+    errors.extend(cache_errors);
 
     //TODO: Workaround for xml-rs memory leak when loading appstream data
     {
@@ -91,5 +241,5 @@ pub fn backends(locale: &str) -> Backends {
         log::info!("trimmed allocations in {:?}", duration);
     }
 
-    backends
+    (backends, errors)
 }
@@ -3,10 +3,13 @@ use packagekit_zbus::{
     zbus::blocking::Connection, PackageKit::PackageKitProxyBlocking,
     Transaction::TransactionProxyBlocking,
 };
-use std::{collections::HashMap, error::Error, fmt::Write, sync::Arc};
+use std::{collections::HashMap, error::Error, sync::Arc};
 
-use super::{Backend, Package};
-use crate::{AppInfo, AppstreamCache, OperationKind, SYSTEM_ID};
+use super::{Backend, Package, Repo, RepoLicense};
+use crate::{
+    app_info::AppSearchText, AppInfo, AppKind, AppMarkup, AppMarkupSpan, AppstreamCache,
+    OperationKind, SYSTEM_ID,
+};
 
 struct TransactionPackage {
     info: u32,
@@ -64,6 +67,60 @@ fn transaction_handle(
     Ok(packages)
 }
 
+/// Repositories that distributions ship disabled, matched by the start of their ID
+const KNOWN_REPOS: &[(&str, RepoLicense)] = &[
+    // Fedora
+    ("rpmfusion-free", RepoLicense::PatentEncumbered),
+    ("rpmfusion-nonfree", RepoLicense::Proprietary),
+    ("google-chrome", RepoLicense::Proprietary),
+    // openSUSE
+    ("packman", RepoLicense::PatentEncumbered),
+    ("repo-non-oss", RepoLicense::Proprietary),
+];
+
+/// Components of Debian and Ubuntu archives that are not free software
+const KNOWN_APT_COMPONENTS: &[&str] = &[
+    "contrib",
+    "multiverse",
+    "non-free",
+    "non-free-firmware",
+    "partner",
+    "restricted",
+];
+
+fn known_repo(id: &str) -> Option<RepoLicense> {
+    // Debug symbols, sources, and testing repositories are left to the package manager
+    if ["debug", "source", "testing"]
+        .iter()
+        .any(|part| id.contains(part))
+    {
+        return None;
+    }
+    // Apt repository IDs are source lines like "deb URI suite component...", which can only be
+    // toggled as a whole, so lines that also have free components are not shown
+    if let Some((_, line)) = id.split_once("deb ") {
+        // Skip options like [arch=amd64 signed-by=...]
+        let line = match line.trim_start().strip_prefix('[') {
+            Some(options) => options.split_once(']').map_or("", |(_, line)| line),
+            None => line,
+        };
+        let components: Vec<_> = line.split_whitespace().skip(2).collect();
+        return if !components.is_empty()
+            && components
+                .iter()
+                .all(|component| KNOWN_APT_COMPONENTS.contains(component))
+        {
+            Some(RepoLicense::Proprietary)
+        } else {
+            None
+        };
+    }
+    KNOWN_REPOS
+        .iter()
+        .find(|(prefix, _)| id.starts_with(prefix))
+        .map(|(_, license)| *license)
+}
+
 // https://lazka.github.io/pgi-docs/PackageKitGlib-1.0/enums.html#PackageKitGlib.FilterEnum
 #[repr(u64)]
 enum FilterKind {
@@ -138,10 +195,11 @@ impl Packagekit {
                             Some(info) => {
                                 packages.push(Package {
                                     id: id.clone(),
-                                    icon: self.appstream_cache.icon(info),
+                                    icon: self.appstream_cache.icon(id, info),
                                     info: info.clone(),
                                     version: version_opt.unwrap_or("").to_string(),
                                     extra: HashMap::new(),
+                                    firmware_opt: None,
                                 });
                             }
                             None => {
@@ -167,12 +225,17 @@ impl Packagekit {
                 system_packages.len(),
                 if system_packages.len() == 1 { "" } else { "s" }
             );
-            let mut description = String::new();
+            let mut items = Vec::with_capacity(system_packages.len());
             let mut pkgnames = Vec::with_capacity(system_packages.len());
             for (package_name, version) in system_packages {
-                let _ = writeln!(description, " * {}: {}", package_name, version);
+                items.push(vec![AppMarkupSpan::Text(format!(
+                    "{}: {}",
+                    package_name, version
+                ))]);
                 pkgnames.push(package_name);
             }
+            let description_markup = vec![AppMarkup::UnorderedList(items)];
+            let search_text = AppSearchText::new(&name, &summary, &[]);
             //TODO: translate
             packages.push(Package {
                 id: SYSTEM_ID.to_string(),
@@ -182,18 +245,34 @@ impl Packagekit {
                 //TODO: fill in more AppInfo fields
                 info: Arc::new(AppInfo {
                     origin_opt: None,
+                    kind: AppKind::Runtime,
                     name,
                     summary,
-                    description,
+                    description_markup,
+                    developer_name: String::new(),
+                    project_group_opt: None,
+                    keywords: Vec::new(),
+                    license_opt: None,
                     pkgnames,
                     categories: Vec::new(),
                     desktop_ids: Vec::new(),
                     flatpak_refs: Vec::new(),
+                    mimetypes: Vec::new(),
                     icons: Vec::new(),
+                    provides: Vec::new(),
+                    releases: Vec::new(),
                     screenshots: Vec::new(),
+                    videos: Vec::new(),
+                    content_rating: Vec::new(),
+                    urls: Vec::new(),
+                    relations: Vec::new(),
+                    verified_opt: None,
+                    size_opt: None,
+                    search_text,
                 }),
                 version: String::new(),
                 extra: HashMap::new(),
+                firmware_opt: None,
             });
         }
         Ok(packages)
@@ -210,6 +289,67 @@ impl Backend for Packagekit {
         &self.appstream_cache
     }
 
+    fn descriptions(&self) -> HashMap<String, String> {
+        self.appstream_cache.load_descriptions("packagekit")
+    }
+
+    fn refresh(&self) -> Result<(), Box<dyn Error>> {
+        let tx = self.transaction()?;
+        log::info!("refreshing package cache");
+        tx.refresh_cache(false)?;
+        transaction_handle(tx, |_| {})?;
+        Ok(())
+    }
+
+    fn repos(&self) -> Result<Vec<Repo>, Box<dyn Error>> {
+        let tx = self.transaction()?;
+        tx.get_repo_list(FilterKind::None as u64)?;
+        let mut repos = Vec::new();
+        for signal in tx.receive_all_signals()? {
+            match signal.member() {
+                Some(member) => match member.as_str() {
+                    "ErrorCode" => {
+                        let (code, details) = signal.body::<(u32, String)>()?;
+                        return Err(format!("{details} (code {code})").into());
+                    }
+                    "RepoDetail" => {
+                        // https://www.freedesktop.org/software/PackageKit/gtk-doc/Transaction.html#Transaction::RepoDetail
+                        let (id, name, enabled) = signal.body::<(String, String, bool)>()?;
+                        if let Some(license) = known_repo(&id) {
+                            repos.push(Repo {
+                                id,
+                                name,
+                                enabled,
+                                license,
+                            });
+                        }
+                    }
+                    "Finished" => {
+                        break;
+                    }
+                    _ => {
+                        log::warn!("unknown signal {}", member);
+                    }
+                },
+                None => {}
+            }
+        }
+        Ok(repos)
+    }
+
+    fn repo_enable(&self, id: &str, enabled: bool) -> Result<(), Box<dyn Error>> {
+        let tx = self.transaction()?;
+        tx.set_hints(&["interactive=true"])?;
+        log::info!(
+            "{} repository {}",
+            if enabled { "enabling" } else { "disabling" },
+            id
+        );
+        tx.repo_enable(id, enabled)?;
+        transaction_handle(tx, |_| {})?;
+        Ok(())
+    }
+
     fn installed(&self) -> Result<Vec<Package>, Box<dyn Error>> {
         let tx = self.transaction()?;
         tx.get_packages(FilterKind::Installed as u64)?;
@@ -229,6 +369,22 @@ impl Backend for Packagekit {
         info: &AppInfo,
         mut f: Box<dyn FnMut(f32) + 'static>,
     ) -> Result<(), Box<dyn Error>> {
+        if kind == OperationKind::RemoveUnused {
+            return Err("removing unused packages is not supported".into());
+        }
+        if kind == OperationKind::InstallFile {
+            // The package ID is the path of a local .deb or .rpm file
+            let tx = self.transaction()?;
+            tx.set_hints(&["interactive=true"])?;
+            log::info!("installing package file {}", package_id);
+            // Downloaded packages are usually unsigned, so trusted only would always fail. The user
+            // confirmed the install and PackageKit asks for authentication to install it
+            tx.install_files(TransactionFlag::None as u64, &[package_id])?;
+            transaction_handle(tx, |progress| {
+                f(progress.percentage as f32);
+            })?;
+            return Ok(());
+        }
         let mut package_names = Vec::with_capacity(info.pkgnames.len());
         for pkgname in &info.pkgnames {
             package_names.push(pkgname.as_str());
@@ -240,12 +396,14 @@ impl Backend for Packagekit {
             let tx = self.transaction()?;
             log::info!("resolve packages for {:?}", package_names);
             let filter = match kind {
-                OperationKind::Install | OperationKind::Update => {
+                OperationKind::Install | OperationKind::InstallFile | OperationKind::Update => {
                     FilterKind::NotInstalled as u64
                         | FilterKind::Newest as u64
                         | FilterKind::Arch as u64
                 }
-                OperationKind::Uninstall => FilterKind::Installed as u64,
+                OperationKind::Uninstall | OperationKind::RemoveUnused => {
+                    FilterKind::Installed as u64
+                }
             };
             tx.resolve(filter, &package_names)?;
             transaction_handle(tx, |_| {})?
@@ -257,12 +415,12 @@ impl Backend for Packagekit {
         let tx = self.transaction()?;
         tx.set_hints(&["interactive=true"])?;
         match kind {
-            OperationKind::Install => {
+            OperationKind::Install | OperationKind::InstallFile => {
                 log::info!("installing packages {:?}", package_ids);
                 //TODO: transaction flags
                 tx.install_packages(TransactionFlag::OnlyTrusted as u64, &package_ids)?;
             }
-            OperationKind::Uninstall => {
+            OperationKind::Uninstall | OperationKind::RemoveUnused => {
                 log::info!("uninstalling packages {:?}", package_ids);
                 //TODO: transaction flags?
                 tx.remove_packages(0, &package_ids, true, true)?;
@@ -285,4 +443,65 @@ impl Backend for Packagekit {
         })?;
         Ok(())
     }
+
+    fn what_provides(&self, path: &str) -> Result<Vec<Package>, Box<dyn Error>> {
+        // Commands without a path are looked up in the default binary directory, other provides
+        // such as "font(:lang=ja)" are passed as is
+        let path = if path.starts_with('/') || path.contains('(') {
+            path.to_string()
+        } else {
+            format!("/usr/bin/{}", path)
+        };
+        let tx = self.transaction()?;
+        log::info!("finding packages that provide {:?}", path);
+        tx.what_provides(
+            FilterKind::Newest as u64 | FilterKind::Arch as u64,
+            &[path.as_str()],
+        )?;
+        let tx_packages = transaction_handle(tx, |_| {})?;
+
+        let mut packages = Vec::new();
+        for tx_package in tx_packages {
+            let mut parts = tx_package.package_id.split(';');
+            let Some(package_name) = parts.next() else {
+                continue;
+            };
+            let version = parts.next().unwrap_or("").to_string();
+            match self.appstream_cache.pkgnames.get(package_name) {
+                Some(ids) => {
+                    for id in ids.iter() {
+                        if let Some(info) = self.appstream_cache.infos.get(id) {
+                            packages.push(Package {
+                                id: id.clone(),
+                                icon: self.appstream_cache.icon(id, info),
+                                info: info.clone(),
+                                version: version.clone(),
+                                extra: HashMap::new(),
+                                firmware_opt: None,
+                            });
+                        }
+                    }
+                }
+                None => {
+                    // Packages without components are shown by package name so they can be installed
+                    packages.push(Package {
+                        id: package_name.to_string(),
+                        icon: widget::icon::from_name("package-x-generic")
+                            .size(128)
+                            .handle(),
+                        info: Arc::new(AppInfo {
+                            search_text: AppSearchText::new(package_name, &tx_package.summary, &[]),
+                            summary: tx_package.summary,
+                            pkgnames: vec![package_name.to_string()],
+                            ..AppInfo::package_file(package_name.to_string())
+                        }),
+                        version,
+                        extra: HashMap::new(),
+                        firmware_opt: None,
+                    });
+                }
+            }
+        }
+        Ok(packages)
+    }
 }
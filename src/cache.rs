@@ -0,0 +1,115 @@
+// Disk caches of the store, which can be measured and cleared from settings
+
+use std::{
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Cache names used by backends for their parsed appstream data
+const APPSTREAM_CACHE_NAMES: &[&str] = &["flatpak", "packagekit"];
+
+/// Appstream data deployed by flatpak for each remote and architecture of the user installation.
+/// Icons of the system installation and of PackageKit catalogs are owned by root.
+fn flatpak_appstream_deploys() -> Vec<PathBuf> {
+    let Some(appstream_dir) = dirs::data_dir().map(|x| x.join("flatpak").join("appstream")) else {
+        return Vec::new();
+    };
+    let mut deploys = Vec::new();
+    for remote_entry in fs::read_dir(&appstream_dir).into_iter().flatten().flatten() {
+        for arch_entry in fs::read_dir(remote_entry.path())
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            if arch_entry.path().join("active").is_dir() {
+                deploys.push(arch_entry.path());
+            }
+        }
+    }
+    deploys
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum CacheKind {
+    Appstream,
+    Icons,
+    Screenshots,
+    Reviews,
+}
+
+impl CacheKind {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Appstream,
+            Self::Icons,
+            Self::Screenshots,
+            Self::Reviews,
+        ]
+    }
+
+    fn dirs(&self) -> Vec<PathBuf> {
+        let Some(cache_dir) = dirs::cache_dir().map(|x| x.join("cosmic-store")) else {
+            return Vec::new();
+        };
+        match self {
+            Self::Appstream => APPSTREAM_CACHE_NAMES
+                .iter()
+                .map(|cache_name| cache_dir.join(cache_name))
+                .collect(),
+            Self::Icons => flatpak_appstream_deploys()
+                .into_iter()
+                .map(|deploy| deploy.join("active").join("icons"))
+                .collect(),
+            Self::Screenshots => vec![cache_dir.join("screenshots")],
+            Self::Reviews => vec![cache_dir.join("odrs")],
+        }
+    }
+
+    /// Returns true if the cache is in the store's own cache directory. Icons are deployed by
+    /// flatpak with the appstream data of remotes, so only flatpak replaces them.
+    pub fn clearable(&self) -> bool {
+        !matches!(self, Self::Icons)
+    }
+
+    /// Total size of cache files in bytes
+    pub fn size(&self) -> u64 {
+        self.dirs().iter().map(|dir| dir_size(dir)).sum()
+    }
+
+    pub fn clear(&self) -> Result<(), Box<dyn Error>> {
+        if !self.clearable() {
+            return Err(format!("{:?} cache is not owned by the store", self).into());
+        }
+        for dir in self.dirs() {
+            match fs::remove_dir_all(&dir) {
+                Ok(()) => {
+                    log::info!("removed cache directory {:?}", dir);
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    return Err(format!("failed to remove {:?}: {}", dir, err).into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let read_dir = match fs::read_dir(dir) {
+        Ok(ok) => ok,
+        Err(_) => return 0,
+    };
+    let mut size = 0;
+    for entry in read_dir.flatten() {
+        match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => size += dir_size(&entry.path()),
+            Ok(metadata) => size += metadata.len(),
+            Err(err) => {
+                log::warn!("failed to read metadata of {:?}: {}", entry.path(), err);
+            }
+        }
+    }
+    size
+}
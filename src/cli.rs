@@ -0,0 +1,425 @@
+// Command line interface, runs without the GUI using the same backends
+
+use cosmic::{
+    cosmic_config::{self, CosmicConfigEntry},
+    iced::futures,
+    Application,
+};
+use serde_json::json;
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+    sync::Arc,
+};
+
+use crate::{
+    backend::{self, Backends, Package},
+    config::{Config, CONFIG_VERSION},
+    format_size, polkit, search_fold, search_weight, App, AppInfo, AppKind, OperationKind,
+};
+
+const USAGE: &'static str = "Usage:
+  cosmic-store [--hidden] [--search <query> | --details <id> | <id> | <uri> | <file>]
+  cosmic-store search <query>
+  cosmic-store info <id>
+  cosmic-store install <id>
+  cosmic-store remove <id>
+  cosmic-store list [--updates]
+  cosmic-store --check-updates
+
+Options:
+  --json  Print machine-readable output
+
+--check-updates exits with 100 if updates are available, 0 if not, and 1 on errors";
+
+/// Exit code of --check-updates when updates are available, matching dnf check-update
+const EXIT_UPDATES_AVAILABLE: i32 = 100;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Command {
+    Search(String),
+    Info(String),
+    Install(String),
+    Remove(String),
+    List { updates: bool },
+    CheckUpdates,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl Command {
+    /// Parse a subcommand, returns None if the arguments are for the GUI
+    pub fn parse(args: &[String]) -> Option<Result<(Self, Format), String>> {
+        let (name, rest) = args.split_first()?;
+        let format = if rest.iter().any(|arg| arg == "--json") {
+            Format::Json
+        } else {
+            Format::Text
+        };
+        let rest: Vec<String> = rest
+            .iter()
+            .filter(|arg| *arg != "--json")
+            .cloned()
+            .collect();
+        let single = |rest: &[String]| match rest {
+            [arg] => Ok(arg.clone()),
+            _ => Err(format!("{} requires one argument\n{}", name, USAGE)),
+        };
+        let command = match name.as_str() {
+            "search" => {
+                if rest.is_empty() {
+                    Err(format!("search requires a query\n{}", USAGE))
+                } else {
+                    Ok(Self::Search(rest.join(" ")))
+                }
+            }
+            "info" => single(&rest).map(Self::Info),
+            "install" => single(&rest).map(Self::Install),
+            "remove" => single(&rest).map(Self::Remove),
+            "list" => match rest.as_slice() {
+                [] => Ok(Self::List { updates: false }),
+                [arg] if arg == "--updates" => Ok(Self::List { updates: true }),
+                _ => Err(format!("unknown list arguments {:?}\n{}", rest, USAGE)),
+            },
+            "--check-updates" => {
+                if rest.is_empty() {
+                    Ok(Self::CheckUpdates)
+                } else {
+                    Err(format!(
+                        "unknown check-updates arguments {:?}\n{}",
+                        rest, USAGE
+                    ))
+                }
+            }
+            "help" | "--help" | "-h" => Err(USAGE.to_string()),
+            _ => return None,
+        };
+        Some(command.map(|command| (command, format)))
+    }
+}
+
+fn load_backends() -> Backends {
+    let locale = sys_locale::get_locale().unwrap_or_else(|| {
+        log::warn!("failed to get system locale, falling back to en-US");
+        String::from("en-US")
+    });
+    let (backends, errors) = backend::backends(&locale);
+    for err in errors {
+        eprintln!("{}", err);
+    }
+    backends
+}
+
+fn backend_names(backends: &Backends) -> Vec<&'static str> {
+    let mut backend_names: Vec<_> = backends.keys().copied().collect();
+    backend_names.sort();
+    backend_names
+}
+
+fn packages(backends: &Backends, updates: bool) -> Result<Vec<(&'static str, Package)>, String> {
+    let mut packages = Vec::new();
+    for backend_name in backend_names(backends) {
+        let backend = &backends[backend_name];
+        let res = if updates {
+            backend.updates()
+        } else {
+            backend.installed()
+        };
+        match res {
+            Ok(backend_packages) => {
+                for package in backend_packages {
+                    packages.push((backend_name, package));
+                }
+            }
+            Err(err) => {
+                return Err(format!("failed to list {} packages: {}", backend_name, err));
+            }
+        }
+    }
+    Ok(packages)
+}
+
+/// Find an app by ID, using the first backend that provides it
+fn find(backends: &Backends, id: &str) -> Option<(&'static str, Arc<AppInfo>)> {
+    backend_names(backends)
+        .into_iter()
+        .find_map(|backend_name| {
+            let info = backends[backend_name].info_cache().infos.get(id)?;
+            Some((backend_name, info.clone()))
+        })
+}
+
+/// Ask for authentication if it is required for operations in settings
+fn authorize() -> Result<(), String> {
+    let config = match cosmic_config::Config::new(App::APP_ID, CONFIG_VERSION) {
+        Ok(config_handler) => match Config::get_entry(&config_handler) {
+            Ok(ok) => ok,
+            Err((errs, config)) => {
+                log::info!("errors loading config: {:?}", errs);
+                config
+            }
+        },
+        // Without settings it is not known if authentication is required
+        Err(err) => return Err(format!("failed to load config: {}", err)),
+    };
+    if !config.require_auth {
+        return Ok(());
+    }
+    match futures::executor::block_on(polkit::authorize(polkit::OPERATION_ACTION)) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("not authorized to install or remove apps".to_string()),
+        Err(err) => Err(format!("failed to check authorization: {}", err)),
+    }
+}
+
+fn operation(
+    backends: &Backends,
+    kind: OperationKind,
+    backend_name: &'static str,
+    id: &str,
+    info: &AppInfo,
+    format: Format,
+) -> Result<(), String> {
+    authorize()?;
+    let res = backends[backend_name].operation(
+        kind,
+        id,
+        info,
+        Box::new(move |progress| {
+            // Progress is only useful to people watching
+            if format == Format::Text {
+                eprint!("\r{:.0}%", progress);
+                let _ = io::stderr().flush();
+            }
+        }),
+    );
+    if format == Format::Text {
+        eprintln!();
+    }
+    res.map_err(|err| err.to_string())?;
+    if format == Format::Json {
+        println!(
+            "{}",
+            json!({
+                "id": id,
+                "backend": backend_name,
+                "operation": format!("{:?}", kind).to_lowercase(),
+            })
+        );
+    }
+    Ok(())
+}
+
+fn search(backends: &Backends, input: &str, format: Format) -> Result<(), String> {
+    let input_folded = search_fold(input);
+    let pattern = regex::escape(&input_folded);
+    let regex = regex::RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|err| format!("failed to parse regex {:?}: {}", pattern, err))?;
+    let mut ids = HashSet::new();
+    let mut results = Vec::new();
+    for backend_name in backend_names(backends) {
+        for (id, info) in backends[backend_name].info_cache().infos.iter() {
+            if info.kind != AppKind::DesktopApplication || !info.arch_supported() {
+                continue;
+            }
+            // Use the first backend that provides the app
+            if ids.contains(id) {
+                continue;
+            }
+            if let Some(weight) = search_weight(&regex, &input_folded, id, info, None) {
+                ids.insert(id.clone());
+                results.push((weight, backend_name, id.clone(), info.clone()));
+            }
+        }
+    }
+    results.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.3.name.cmp(&b.3.name)));
+    match format {
+        Format::Text => {
+            for (_weight, backend_name, id, info) in results {
+                println!(
+                    "{} ({}): {} - {}",
+                    id, backend_name, info.name, info.summary
+                );
+            }
+        }
+        Format::Json => {
+            let results: Vec<_> = results
+                .into_iter()
+                .map(|(_weight, backend_name, id, info)| {
+                    json!({
+                        "id": id,
+                        "backend": backend_name,
+                        "name": info.name,
+                        "summary": info.summary,
+                    })
+                })
+                .collect();
+            println!("{}", json!(results));
+        }
+    }
+    Ok(())
+}
+
+fn info(backends: &Backends, id: &str, format: Format) -> Result<(), String> {
+    let (backend_name, info) =
+        find(backends, id).ok_or_else(|| format!("app {:?} not found", id))?;
+    if format == Format::Json {
+        println!(
+            "{}",
+            json!({
+                "id": id,
+                "backend": backend_name,
+                "name": info.name,
+                "summary": info.summary,
+                "developer": info.developer_name,
+                "version": info.releases.first().map(|release| &release.version),
+                "license": info.license_opt,
+                "size": info.size_opt,
+            })
+        );
+        return Ok(());
+    }
+    println!("{}", info.name);
+    println!("{}", info.summary);
+    println!("ID: {}", id);
+    println!("Source: {}", backend_name);
+    if !info.developer_name.is_empty() {
+        println!("Developer: {}", info.developer_name);
+    }
+    if let Some(release) = info.releases.first() {
+        println!("Version: {}", release.version);
+    }
+    if let Some(license) = &info.license_opt {
+        println!("License: {}", license);
+    }
+    if let Some(size) = info.size_opt {
+        println!("Size: {}", format_size(size));
+    }
+    Ok(())
+}
+
+fn install(backends: &Backends, id: &str, format: Format) -> Result<(), String> {
+    let (backend_name, info) =
+        find(backends, id).ok_or_else(|| format!("app {:?} not found", id))?;
+    if packages(backends, false)?
+        .iter()
+        .any(|(other_backend_name, package)| {
+            *other_backend_name == backend_name && package.id == id
+        })
+    {
+        if format == Format::Text {
+            println!("{} is already installed", info.name);
+        } else {
+            println!("{}", json!({ "id": id, "backend": backend_name }));
+        }
+        return Ok(());
+    }
+    if format == Format::Text {
+        println!("Installing {} from {}", info.name, backend_name);
+    }
+    operation(
+        backends,
+        OperationKind::Install,
+        backend_name,
+        id,
+        &info,
+        format,
+    )
+}
+
+fn remove(backends: &Backends, id: &str, format: Format) -> Result<(), String> {
+    let (backend_name, package) = packages(backends, false)?
+        .into_iter()
+        .find(|(_, package)| package.id == id)
+        .ok_or_else(|| format!("app {:?} is not installed", id))?;
+    if format == Format::Text {
+        println!("Removing {} from {}", package.info.name, backend_name);
+    }
+    operation(
+        backends,
+        OperationKind::Uninstall,
+        backend_name,
+        &package.id,
+        &package.info,
+        format,
+    )
+}
+
+fn list(backends: &Backends, updates: bool, format: Format) -> Result<(), String> {
+    print_packages(packages(backends, updates)?, format);
+    Ok(())
+}
+
+fn print_packages(packages: Vec<(&'static str, Package)>, format: Format) {
+    match format {
+        Format::Text => {
+            for (backend_name, package) in packages {
+                println!(
+                    "{} ({}): {} {}",
+                    package.id, backend_name, package.info.name, package.version
+                );
+            }
+        }
+        Format::Json => {
+            let packages: Vec<_> = packages
+                .into_iter()
+                .map(|(backend_name, package)| {
+                    json!({
+                        "id": package.id,
+                        "backend": backend_name,
+                        "name": package.info.name,
+                        "version": package.version,
+                    })
+                })
+                .collect();
+            println!("{}", json!(packages));
+        }
+    }
+}
+
+/// Refresh metadata and print updates, returns true if updates are available
+fn check_updates(backends: &Backends, format: Format) -> Result<bool, String> {
+    for backend_name in backend_names(backends) {
+        // Updates may still be found from older metadata, for example when offline
+        if let Err(err) = backends[backend_name].refresh() {
+            eprintln!("failed to refresh {} metadata: {}", backend_name, err);
+        }
+    }
+    let updates = packages(backends, true)?;
+    let available = !updates.is_empty();
+    print_packages(updates, format);
+    Ok(available)
+}
+
+/// Run a subcommand, returning the exit code
+pub fn run(command: Command, format: Format) -> i32 {
+    let backends = load_backends();
+    let res =
+        match &command {
+            Command::Search(input) => search(&backends, input, format).map(|()| 0),
+            Command::Info(id) => info(&backends, id, format).map(|()| 0),
+            Command::Install(id) => install(&backends, id, format).map(|()| 0),
+            Command::Remove(id) => remove(&backends, id, format).map(|()| 0),
+            Command::List { updates } => list(&backends, *updates, format).map(|()| 0),
+            Command::CheckUpdates => check_updates(&backends, format).map(|available| {
+                if available {
+                    EXIT_UPDATES_AVAILABLE
+                } else {
+                    0
+                }
+            }),
+        };
+    match res {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("{}", err);
+            1
+        }
+    }
+}
@@ -2,11 +2,36 @@
 
 use cosmic::{
     cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry},
-    theme,
+    cosmic_theme, theme,
 };
 use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    key_bind::{default_key_binds, KeyBind},
+    odrs::ODRS_URL,
+    Action, NavPage,
+};
 
 pub const CONFIG_VERSION: u64 = 1;
+pub const SYNC_CONFIG_ID: &'static str = "com.system76.CosmicStore.Sync";
+pub const SYNC_CONFIG_VERSION: u64 = 1;
+pub const SYNC_FILE_NAME: &'static str = "cosmic-store-settings.json";
+
+/// Default location of exported settings
+pub fn sync_file_path() -> PathBuf {
+    dirs::document_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_default()
+        .join(SYNC_FILE_NAME)
+}
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum AppTheme {
@@ -25,15 +50,252 @@ impl AppTheme {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Density {
+    Comfortable,
+    Compact,
+}
+
+impl Density {
+    /// Theme spacing, shifted down one step when compact
+    pub fn spacing(&self) -> cosmic_theme::Spacing {
+        let spacing = theme::active().cosmic().spacing;
+        match self {
+            Self::Comfortable => spacing,
+            Self::Compact => cosmic_theme::Spacing {
+                space_none: spacing.space_none,
+                space_xxxs: spacing.space_none,
+                space_xxs: spacing.space_xxxs,
+                space_xs: spacing.space_xxs,
+                space_s: spacing.space_xs,
+                space_m: spacing.space_s,
+                space_l: spacing.space_m,
+                space_xl: spacing.space_l,
+                space_xxl: spacing.space_xl,
+                space_xxxl: spacing.space_xxl,
+            },
+        }
+    }
+
+    /// Icon size, reduced by a quarter when compact
+    pub fn icon_size(&self, size: u16) -> u16 {
+        match self {
+            Self::Comfortable => size,
+            Self::Compact => size * 3 / 4,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PreferredFormat {
+    Flatpak,
+    Native,
+}
+
+impl PreferredFormat {
+    /// Lower ranks are used first when an app is provided by more than one backend
+    pub fn backend_rank(&self, backend_name: &str) -> usize {
+        match (self, backend_name) {
+            (Self::Flatpak, "flatpak") => 0,
+            (Self::Flatpak, _) => 1,
+            (Self::Native, "flatpak") => 1,
+            (Self::Native, _) => 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum RefreshInterval {
+    Hourly,
+    Daily,
+    Never,
+}
+
+impl RefreshInterval {
+    /// Time between metadata refreshes, None if metadata is only refreshed manually
+    pub fn duration_opt(&self) -> Option<Duration> {
+        match self {
+            Self::Hourly => Some(Duration::from_secs(60 * 60)),
+            Self::Daily => Some(Duration::from_secs(24 * 60 * 60)),
+            Self::Never => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ViewLayout {
+    Grid,
+    List,
+}
+
 #[derive(Clone, CosmicConfigEntry, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
 pub struct Config {
-    pub app_theme: AppTheme,
+    /// Language of app names and descriptions, the system locale is used if None
+    pub catalog_locale: Option<String>,
+    /// Download rate limit in bytes per second for transfers made by the store, None for no limit
+    pub download_limit: Option<u64>,
+    /// Show apps that are only available for other architectures
+    pub foreign_architectures: bool,
+    /// Hide apps with proprietary licenses, apps with unknown licenses are still shown
+    pub free_software_only: bool,
+    pub key_binds: BTreeMap<Action, KeyBind>,
+    /// Hide apps with an OARS content rating above this age, None to show all apps
+    pub max_content_age: Option<u8>,
+    /// Page shown on startup, the last page that was visited
+    pub nav_page: NavPage,
+    pub notifications: bool,
+    /// Operations that run at the same time, all queued operations run if None
+    pub parallel_operations: Option<usize>,
+    /// Package format shown and installed when an app is available from more than one source
+    pub preferred_format: PreferredFormat,
+    /// Manual proxy URL for requests made by the store, the system proxy is used if empty
+    pub proxy: String,
+    pub refresh_interval: RefreshInterval,
+    /// Ask for administrator authentication before installing or removing apps
+    pub require_auth: bool,
+    /// Ratings and reviews server, reviews are disabled if None
+    pub reviews_server: Option<String>,
+    pub search_history: Vec<String>,
+    pub tray: bool,
+    pub window_maximized: bool,
+    /// Size of the window when it is not maximized, the default size is used if None
+    pub window_size: Option<(u32, u32)>,
 }
 
 impl Default for Config {
+    fn default() -> Self {
+        Self {
+            catalog_locale: None,
+            download_limit: None,
+            foreign_architectures: false,
+            free_software_only: false,
+            key_binds: default_key_binds(),
+            max_content_age: None,
+            nav_page: NavPage::Explore,
+            notifications: true,
+            parallel_operations: None,
+            preferred_format: PreferredFormat::Flatpak,
+            proxy: String::new(),
+            refresh_interval: RefreshInterval::Daily,
+            require_auth: false,
+            reviews_server: Some(ODRS_URL.to_string()),
+            search_history: Vec::new(),
+            tray: false,
+            window_maximized: false,
+            window_size: None,
+        }
+    }
+}
+
+impl Config {
+    /// Settings imported from another system, keeping the window state, last page, and search
+    /// history of this one. Authentication and age rating limits are also kept, as changing them
+    /// from settings needs authorization.
+    pub fn imported(&self, config: Self) -> Self {
+        Self {
+            max_content_age: self.max_content_age,
+            nav_page: self.nav_page,
+            require_auth: self.require_auth,
+            search_history: self.search_history.clone(),
+            window_maximized: self.window_maximized,
+            window_size: self.window_size,
+            ..config
+        }
+    }
+}
+
+/// Favorites and preferences that are not tied to this system, kept apart from [`Config`] so
+/// they can be synced between devices
+#[derive(Clone, CosmicConfigEntry, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    pub app_theme: AppTheme,
+    pub density: Density,
+    pub favorites: Vec<String>,
+    pub pinned_searches: Vec<String>,
+    pub view_layout: ViewLayout,
+}
+
+impl Default for SyncConfig {
     fn default() -> Self {
         Self {
             app_theme: AppTheme::System,
+            density: Density::Comfortable,
+            favorites: Vec::new(),
+            pinned_searches: Vec::new(),
+            view_layout: ViewLayout::Grid,
+        }
+    }
+}
+
+impl SyncConfig {
+    pub fn is_favorite(&self, id: &str) -> bool {
+        self.favorites.iter().any(|x| x == id)
+    }
+}
+
+/// Repository state saved with exported settings
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SettingsRepo {
+    pub backend: String,
+    pub id: String,
+    pub enabled: bool,
+}
+
+/// Exported settings, used to restore preferences on another system
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SettingsFile {
+    /// Version of [`SyncConfig`], named for compatibility with files that only contained it
+    version: u64,
+    /// Version of [`Config`], None for files that only contained the sync config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    config_version: Option<u64>,
+    #[serde(flatten)]
+    pub sync_config: SyncConfig,
+    #[serde(default, rename = "config", skip_serializing_if = "Option::is_none")]
+    pub config_opt: Option<Config>,
+    #[serde(default)]
+    pub repos: Vec<SettingsRepo>,
+}
+
+impl SettingsFile {
+    pub fn new(config: &Config, sync_config: &SyncConfig, repos: Vec<SettingsRepo>) -> Self {
+        Self {
+            version: SYNC_CONFIG_VERSION,
+            config_version: Some(CONFIG_VERSION),
+            sync_config: sync_config.clone(),
+            // Searches are private to this system
+            config_opt: Some(Config {
+                search_history: Vec::new(),
+                ..config.clone()
+            }),
+            repos,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let data = fs::read(path)?;
+        let file: Self = serde_json::from_slice(&data)?;
+        if file.version > SYNC_CONFIG_VERSION {
+            return Err(format!("unsupported settings version {}", file.version).into());
+        }
+        // Config fields added after the file was exported are filled with their defaults
+        if let Some(config_version) = file.config_version {
+            if config_version > CONFIG_VERSION {
+                return Err(format!("unsupported config version {}", config_version).into());
+            }
         }
+        // Version 1 is the only version of both so far, so no file needs migrating. Files with
+        // older versions must be converted here when a version is added.
+        Ok(file)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let data = serde_json::to_vec_pretty(self)?;
+        // Written atomically so a failed export does not leave a truncated file in place
+        atomicwrites::AtomicFile::new(path, atomicwrites::OverwriteBehavior::AllowOverwrite)
+            .write(|file| file.write_all(&data))?;
+        Ok(())
     }
 }
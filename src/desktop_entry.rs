@@ -0,0 +1,49 @@
+// Desktop files of installed apps, used to launch them and to debug launch failures
+
+use std::{error::Error, path::PathBuf};
+
+/// Commands that run apps inside a sandbox
+const SANDBOX_WRAPPERS: &[&str] = &["bwrap", "firejail", "flatpak", "snap"];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DesktopEntry {
+    pub path: PathBuf,
+    pub exec: String,
+    /// Command that starts the app in a sandbox, such as flatpak
+    pub wrapper_opt: Option<String>,
+}
+
+/// Find the desktop file for a desktop ID in the XDG data directories
+pub fn desktop_file_path(desktop_id: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let mut file_name = desktop_id.to_string();
+    if !file_name.ends_with(".desktop") {
+        file_name.push_str(".desktop");
+    }
+    let xdg_dirs = xdg::BaseDirectories::with_prefix("applications")?;
+    xdg_dirs
+        .find_data_file(&file_name)
+        .ok_or_else(|| format!("failed to find desktop file for {:?}", file_name).into())
+}
+
+impl DesktopEntry {
+    pub fn load(desktop_id: &str) -> Result<Self, Box<dyn Error>> {
+        let path = desktop_file_path(desktop_id)?;
+        let entry = freedesktop_entry_parser::parse_entry(&path)?;
+        let exec = entry
+            .section("Desktop Entry")
+            .attr("Exec")
+            .ok_or_else(|| format!("no exec section in {:?}", path))?
+            .to_string();
+        let wrapper_opt = exec
+            .split_whitespace()
+            .next()
+            .and_then(|command| command.rsplit('/').next())
+            .filter(|name| SANDBOX_WRAPPERS.contains(name))
+            .map(|name| name.to_string());
+        Ok(Self {
+            path,
+            exec,
+            wrapper_opt,
+        })
+    }
+}
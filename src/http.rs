@@ -0,0 +1,145 @@
+// Shared HTTP client, so all requests use the same proxy settings and download rate limit
+
+use std::{
+    error::Error,
+    fs,
+    io::Write,
+    path::Path,
+    sync::{Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+lazy_static::lazy_static! {
+    // Proxies from the environment, such as https_proxy, are used unless overridden
+    static ref CLIENT: RwLock<reqwest::Client> = RwLock::new(reqwest::Client::new());
+    // Download rate limit in bytes per second, shared by all transfers
+    static ref DOWNLOAD_LIMIT: RwLock<Option<u64>> = RwLock::new(None);
+    // Time when the data read so far is within the download rate limit
+    static ref DOWNLOAD_NEXT: Mutex<Instant> = Mutex::new(Instant::now());
+}
+
+/// Use a manual proxy URL for requests made by the store, or the system proxy if empty. PackageKit
+/// and flatpak make their own requests, which always use the system proxy.
+pub fn set_proxy(proxy: &str) {
+    let mut builder = reqwest::Client::builder();
+    if !proxy.is_empty() {
+        match reqwest::Proxy::all(proxy) {
+            Ok(proxy) => {
+                builder = builder.proxy(proxy);
+            }
+            Err(err) => {
+                log::warn!("invalid proxy {:?}: {}", proxy, err);
+            }
+        }
+    }
+    match builder.build() {
+        Ok(client) => match CLIENT.write() {
+            Ok(mut guard) => *guard = client,
+            Err(err) => {
+                log::warn!("failed to update HTTP client: {}", err);
+            }
+        },
+        Err(err) => {
+            log::warn!("failed to create HTTP client: {}", err);
+        }
+    }
+}
+
+pub fn client() -> reqwest::Client {
+    match CLIENT.read() {
+        Ok(guard) => guard.clone(),
+        Err(err) => err.into_inner().clone(),
+    }
+}
+
+/// Limit downloads made by the store to a number of bytes per second, or remove the limit if None.
+/// PackageKit and flatpak make their own requests, which are not limited.
+pub fn set_download_limit(download_limit: Option<u64>) {
+    match DOWNLOAD_LIMIT.write() {
+        Ok(mut guard) => *guard = download_limit,
+        Err(err) => *err.into_inner() = download_limit,
+    }
+}
+
+fn download_limit() -> Option<u64> {
+    match DOWNLOAD_LIMIT.read() {
+        Ok(guard) => *guard,
+        Err(err) => *err.into_inner(),
+    }
+}
+
+/// Wait until reading a chunk of data keeps all transfers within the download rate limit
+async fn throttle(len: usize, download_limit: u64) {
+    let deadline = {
+        let mut next = match DOWNLOAD_NEXT.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        *next = (*next).max(Instant::now())
+            + Duration::from_secs_f64(len as f64 / download_limit.max(1) as f64);
+        *next
+    };
+    tokio::time::sleep_until(tokio::time::Instant::from_std(deadline)).await;
+}
+
+/// Read the body of a response, following the download rate limit
+pub async fn bytes(mut response: reqwest::Response) -> reqwest::Result<Vec<u8>> {
+    let Some(download_limit) = download_limit() else {
+        return Ok(response.bytes().await?.to_vec());
+    };
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        data.extend_from_slice(&chunk);
+        throttle(chunk.len(), download_limit).await;
+    }
+    Ok(data)
+}
+
+/// Download to a file, continuing the partial download that an interrupted attempt left next to
+/// it with a ".part" extension. Progress is reported from 0 to 100 if the size is known.
+pub async fn download_file(
+    url: &str,
+    path: &Path,
+    mut f: impl FnMut(f32),
+) -> Result<(), Box<dyn Error>> {
+    let mut part_name = path
+        .file_name()
+        .ok_or("download path has no file name")?
+        .to_os_string();
+    part_name.push(".part");
+    let part_path = path.with_file_name(part_name);
+    let mut offset = fs::metadata(&part_path).map_or(0, |metadata| metadata.len());
+    let mut request = client().get(url);
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let mut response = request.send().await?;
+    let mut file = if offset > 0 && response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+        log::info!("resuming download of {} after {} bytes", url, offset);
+        fs::OpenOptions::new().append(true).open(&part_path)?
+    } else if offset > 0 && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // The partial download already has all of the data
+        fs::rename(&part_path, path)?;
+        return Ok(());
+    } else {
+        // Servers that do not support ranges send everything again
+        response = response.error_for_status()?;
+        offset = 0;
+        fs::File::create(&part_path)?
+    };
+    let total_opt = response.content_length().map(|len| len + offset);
+    let download_limit_opt = download_limit();
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk)?;
+        offset += chunk.len() as u64;
+        if let Some(total) = total_opt {
+            f((offset as f32 * 100.0) / total.max(1) as f32);
+        }
+        if let Some(download_limit) = download_limit_opt {
+            throttle(chunk.len(), download_limit).await;
+        }
+    }
+    file.sync_all()?;
+    fs::rename(&part_path, path)?;
+    Ok(())
+}
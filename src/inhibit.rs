@@ -0,0 +1,20 @@
+// Inhibit suspend while operations run, so the system does not sleep mid-transaction
+// https://www.freedesktop.org/wiki/Software/systemd/inhibit/
+
+use zbus::{dbus_proxy, zvariant::OwnedFd, Connection};
+
+#[dbus_proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+trait Manager {
+    fn inhibit(&self, what: &str, who: &str, why: &str, mode: &str) -> zbus::Result<OwnedFd>;
+}
+
+/// Take a suspend inhibitor, which is released when the returned file descriptor is dropped
+pub async fn inhibit(who: &str, why: &str) -> zbus::Result<OwnedFd> {
+    let connection = Connection::system().await?;
+    let proxy = ManagerProxy::new(&connection).await?;
+    proxy.inhibit("sleep", who, why, "block").await
+}
@@ -1,6 +1,9 @@
-use cosmic::iced::keyboard::{Key, Modifiers};
+use cosmic::iced::keyboard::{key::Named, Key, Modifiers};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fmt};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
 
 use crate::Action;
 
@@ -19,6 +22,30 @@ pub struct KeyBind {
 }
 
 impl KeyBind {
+    /// Key bind for a key press, returns None for presses of only a modifier key
+    pub fn new(modifiers: Modifiers, key: Key) -> Option<Self> {
+        if let Key::Named(Named::Alt | Named::Control | Named::Shift | Named::Super) = key {
+            return None;
+        }
+        let mut key_bind_modifiers = Vec::new();
+        if modifiers.logo() {
+            key_bind_modifiers.push(Modifier::Super);
+        }
+        if modifiers.control() {
+            key_bind_modifiers.push(Modifier::Ctrl);
+        }
+        if modifiers.alt() {
+            key_bind_modifiers.push(Modifier::Alt);
+        }
+        if modifiers.shift() {
+            key_bind_modifiers.push(Modifier::Shift);
+        }
+        Some(Self {
+            modifiers: key_bind_modifiers,
+            key,
+        })
+    }
+
     pub fn matches(&self, modifiers: Modifiers, key: &Key) -> bool {
         key == &self.key
             && modifiers.logo() == self.modifiers.contains(&Modifier::Super)
@@ -41,23 +68,36 @@ impl fmt::Display for KeyBind {
     }
 }
 
-//TODO: load from config
-pub fn key_binds() -> HashMap<KeyBind, Action> {
-    let mut key_binds = HashMap::new();
+/// Default key binds, actions without one can still be bound in settings
+pub fn default_key_binds() -> BTreeMap<Action, KeyBind> {
+    let mut key_binds = BTreeMap::new();
 
     macro_rules! bind {
-        ([$($modifier:ident),+ $(,)?], $key:expr, $action:ident) => {{
+        ([$($modifier:ident),* $(,)?], $key:expr, $action:ident) => {{
             key_binds.insert(
+                Action::$action,
                 KeyBind {
-                    modifiers: vec![$(Modifier::$modifier),+],
+                    modifiers: vec![$(Modifier::$modifier),*],
                     key: $key,
                 },
-                Action::$action,
             );
         }};
     }
 
+    bind!([Alt], Key::Named(Named::ArrowLeft), NavBack);
+    bind!([Alt], Key::Named(Named::ArrowRight), NavForward);
     bind!([Ctrl], Key::Character("f".into()), SearchActivate);
+    bind!([], Key::Named(Named::F5), Refresh);
+    bind!([Ctrl], Key::Character("i".into()), Install);
+    bind!([Ctrl], Key::Character(",".into()), Settings);
 
     key_binds
 }
+
+/// Key binds from the config, keyed by the key bind for lookup on key presses
+pub fn key_binds(config_key_binds: &BTreeMap<Action, KeyBind>) -> HashMap<KeyBind, Action> {
+    config_key_binds
+        .iter()
+        .map(|(action, key_bind)| (key_bind.clone(), *action))
+        .collect()
+}
@@ -2,54 +2,137 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use cosmic::{
-    app::{message, Command, Core, Settings},
+    app::{message, Command, Core, DbusActivationDetails, DbusActivationMessage, Settings},
     cosmic_config::{self, CosmicConfigEntry},
     cosmic_theme, executor,
     iced::{
         event::{self, Event},
         futures::{self, SinkExt},
-        keyboard::{Event as KeyEvent, Key, Modifiers},
+        keyboard::{key::Named, Event as KeyEvent, Key, Modifiers},
+        mouse::{Button as MouseButton, Event as MouseEvent},
         subscription::{self, Subscription},
-        window, Alignment, Length,
+        widget::scrollable::{self, AbsoluteOffset},
+        window, Alignment, Length, Size,
     },
     theme, widget, Application, ApplicationExt, Element,
 };
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::{
     any::TypeId,
     cmp,
-    collections::{BTreeMap, HashMap, VecDeque},
-    env, process,
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    env, fs, ops,
+    path::{Path, PathBuf},
+    process,
     sync::Arc,
     time::{self, Instant},
 };
+use unicode_normalization::UnicodeNormalization;
 
-use app_info::{AppIcon, AppInfo};
+use app_list::{app_list_path, AppList, AppListMissing};
+mod app_list;
+
+mod autostart;
+
+use app_info::{
+    AppContentState, AppIcon, AppInfo, AppKind, AppMarkup, AppMarkupSpan, AppProvide, AppRelease,
+    AppRequirement, AppScreenshot, AppUrl,
+};
 mod app_info;
 
 use appstream_cache::AppstreamCache;
 mod appstream_cache;
 
-use backend::{Backends, Package};
+use backend::{
+    file_backend_name, Backends, FirmwareRelease, FirmwareUrgency, Package, Repo, RepoLicense,
+    SourceTrust, StorageUsage,
+};
 mod backend;
 
-use config::{AppTheme, Config, CONFIG_VERSION};
+use cache::CacheKind;
+mod cache;
+
+mod cli;
+
+use config::{
+    sync_file_path, AppTheme, Config, Density, PreferredFormat, RefreshInterval, SettingsFile,
+    SettingsRepo, SyncConfig, ViewLayout, CONFIG_VERSION, SYNC_CONFIG_ID, SYNC_CONFIG_VERSION,
+};
 mod config;
 
+use desktop_entry::DesktopEntry;
+mod desktop_entry;
+
 use icon_cache::icon_cache_get;
 mod icon_cache;
 
-use key_bind::{key_binds, KeyBind};
+mod http;
+
+mod inhibit;
+
+use key_bind::{default_key_binds, key_binds, KeyBind};
 mod key_bind;
 
 mod localize;
 
-use operation::{Operation, OperationKind};
+mod notification;
+
+use operation::{saved_operations_load, saved_operations_save, Operation, OperationKind};
 mod operation;
 
+use odrs::{OdrsRating, OdrsReview, OdrsReviewDraft};
+mod odrs;
+
+mod polkit;
+
+mod report;
+
+mod screenshot_cache;
+
+mod search_provider;
+
+use session::SessionReply;
+mod session;
+
+mod spdx;
+
+mod tray;
+
 const ICON_SIZE_SEARCH: u16 = 48;
 const ICON_SIZE_PACKAGE: u16 = 64;
+const ICON_SIZE_GRID: u16 = 64;
 const ICON_SIZE_DETAILS: u16 = 128;
+const FEATURED_INTERVAL: time::Duration = time::Duration::from_secs(10);
+//TODO: load from a remote curation list
+const FEATURED_IDS: &'static [&'static str] = &[
+    "org.mozilla.firefox",
+    "org.gimp.GIMP",
+    "org.inkscape.Inkscape",
+    "org.kde.krita",
+    "org.blender.Blender",
+    "com.obsproject.Studio",
+    "org.libreoffice.LibreOffice",
+    "com.valvesoftware.Steam",
+];
+const RESULTS_PAGE_SIZE: usize = 128;
+const SEARCH_HISTORY_LEN: usize = 10;
+/// URI scheme used to pass search phrases to a running instance
+const SEARCH_SCHEME: &'static str = "cosmic-store-search";
+const NAV_HISTORY_LEN: usize = 64;
+const RECENT_LIMIT: usize = 8;
+// OARS age rating limits offered in settings
+const CONTENT_AGE_LIMITS: [u8; 4] = [3, 7, 12, 16];
+// Download rate limits offered in settings, in bytes per second
+const DOWNLOAD_LIMITS: [u64; 4] = [250_000, 1_000_000, 5_000_000, 20_000_000];
+const POPULAR_LIMIT: usize = 8;
+const SKELETON_COUNT: usize = 8;
+// Window width at which the explore page shows a category sidebar
+const SIDEBAR_MIN_WIDTH: f32 = 1200.0;
+// Time after the last resize event before the window size is saved
+const WINDOW_SAVE_DELAY: time::Duration = time::Duration::from_millis(500);
+// Window width below which layouts stack vertically, such as when tiled to a quarter screen
+const NARROW_MAX_WIDTH: f32 = 600.0;
 const SYSTEM_ID: &'static str = "__SYSTEM__";
 
 /// Runs application with these settings
@@ -59,6 +142,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     localize::localize();
 
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Some(res) = cli::Command::parse(&args) {
+        match res {
+            Ok((command, format)) => process::exit(cli::run(command, format)),
+            Err(err) => {
+                eprintln!("{}", err);
+                process::exit(2);
+            }
+        }
+    }
+
     let (config_handler, config) = match cosmic_config::Config::new(App::APP_ID, CONFIG_VERSION) {
         Ok(config_handler) => {
             let config = match Config::get_entry(&config_handler) {
@@ -76,8 +170,29 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    let (sync_config_handler, sync_config) =
+        match cosmic_config::Config::new(SYNC_CONFIG_ID, SYNC_CONFIG_VERSION) {
+            Ok(sync_config_handler) => {
+                let sync_config = match SyncConfig::get_entry(&sync_config_handler) {
+                    Ok(ok) => ok,
+                    Err((errs, sync_config)) => {
+                        log::info!("errors loading sync config: {:?}", errs);
+                        sync_config
+                    }
+                };
+                (Some(sync_config_handler), sync_config)
+            }
+            Err(err) => {
+                log::error!("failed to create sync config handler: {}", err);
+                (None, SyncConfig::default())
+            }
+        };
+
     let mut settings = Settings::default();
-    settings = settings.theme(config.app_theme.theme());
+    settings = settings.theme(sync_config.app_theme.theme());
+    if let Some((width, height)) = config.window_size {
+        settings = settings.size(Size::new(width as f32, height as f32));
+    }
 
     #[cfg(target_os = "redox")]
     {
@@ -88,24 +203,89 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     //TODO: allow size limits on iced_winit
     //settings = settings.size_limits(Limits::NONE.min_width(400.0).min_height(200.0));
 
+    let mut hidden = false;
+    let mut target_opt = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--hidden" {
+            // Started at login to check for updates without showing a window
+            hidden = true;
+        } else if arg == "--details" {
+            target_opt = args.next().map(AppTarget::Id);
+        } else if arg == "--search" {
+            target_opt = args.next().map(AppTarget::Search);
+        } else if let Some(target) = AppTarget::from_uri(&arg) {
+            target_opt = Some(target);
+        } else if let Ok(path) = fs::canonicalize(&arg) {
+            // Package files opened from a file manager or browser
+            target_opt = Some(AppTarget::File(path));
+        } else if let Some(target) = AppTarget::from_id(&arg) {
+            target_opt = Some(target);
+        } else {
+            log::warn!("unknown argument {:?}", arg);
+        }
+    }
+
     let flags = Flags {
         config_handler,
         config,
+        sync_config_handler,
+        sync_config,
+        hidden,
+        // Passed to an already running instance as an activation action
+        target_uri_opt: target_opt.as_ref().map(AppTarget::uri),
+        target_opt,
     };
-    cosmic::app::run::<App>(settings, flags)?;
+    cosmic::app::run_single_instance::<App>(settings, flags)?;
 
     Ok(())
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub enum Action {
+    NavBack,
+    NavForward,
     SearchActivate,
+    Refresh,
+    Install,
+    Settings,
+    Escape,
 }
 
 impl Action {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::NavBack,
+            Self::NavForward,
+            Self::SearchActivate,
+            Self::Refresh,
+            Self::Install,
+            Self::Settings,
+            Self::Escape,
+        ]
+    }
+
     pub fn message(&self) -> Message {
         match self {
+            Self::NavBack => Message::NavBack,
+            Self::NavForward => Message::NavForward,
             Self::SearchActivate => Message::SearchActivate,
+            Self::Refresh => Message::Refresh,
+            Self::Install => Message::InstallSelected,
+            Self::Settings => Message::ToggleContextPage(ContextPage::Settings),
+            Self::Escape => Message::Escape,
+        }
+    }
+
+    fn title(&self) -> String {
+        match self {
+            Self::NavBack => fl!("action-nav-back"),
+            Self::NavForward => fl!("action-nav-forward"),
+            Self::SearchActivate => fl!("action-search"),
+            Self::Refresh => fl!("action-refresh"),
+            Self::Install => fl!("action-install"),
+            Self::Settings => fl!("action-settings"),
+            Self::Escape => fl!("action-escape"),
         }
     }
 }
@@ -114,62 +294,344 @@ impl Action {
 pub struct Flags {
     config_handler: Option<cosmic_config::Config>,
     config: Config,
+    sync_config_handler: Option<cosmic_config::Config>,
+    sync_config: SyncConfig,
+    /// Start with the window hidden
+    hidden: bool,
+    /// App to show once backends are loaded
+    target_opt: Option<AppTarget>,
+    target_uri_opt: Option<String>,
+}
+
+impl cosmic::app::CosmicFlags for Flags {
+    type SubCommand = String;
+    type Args = Vec<String>;
+
+    fn action(&self) -> Option<&String> {
+        self.target_uri_opt.as_ref()
+    }
+}
+
+/// App referenced by a URI or command line argument
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AppTarget {
+    /// AppStream component ID
+    Id(String),
+    /// Distribution package name
+    Package(String),
+    /// Snap name that can only be searched for, as there is no backend for it
+    Snap(String),
+    /// Search phrase
+    Search(String),
+    /// Local package file
+    File(PathBuf),
+}
+
+impl AppTarget {
+    /// Parse URIs used by "Install on Linux" links, such as:
+    /// - appstream://org.gnome.Calculator.desktop
+    /// - apt:gnome-calculator
+    /// - snap://gnome-calculator
+    /// - flatpak+https://dl.flathub.org/repo/appstream/org.gnome.Calculator.flatpakref
+    ///
+    /// Also parses cosmic-store-search:?q=calculator, which is used to forward searches
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        let (scheme, path) = uri.split_once(':')?;
+        if scheme == "file" {
+            let url = appstream::url::Url::parse(uri).ok()?;
+            return url.to_file_path().ok().map(Self::File);
+        }
+        if scheme == SEARCH_SCHEME {
+            // Search phrases are percent encoded so they can contain any character
+            let url = appstream::url::Url::parse(uri).ok()?;
+            return url
+                .query_pairs()
+                .find(|(key, _)| key == "q")
+                .map(|(_, value)| value.into_owned())
+                .filter(|input| !input.is_empty())
+                .map(Self::Search);
+        }
+        // Remove authority slashes and query parameters
+        let path = path.trim_start_matches('/');
+        let path = path.split('?').next().unwrap_or(path).trim_end_matches('/');
+        let target = match scheme {
+            "appstream" => Self::Id(path.to_string()),
+            // apt URIs may list multiple packages, only the first is shown
+            "apt" => Self::Package(path.split(',').next().unwrap_or(path).to_string()),
+            "snap" => Self::Snap(path.to_string()),
+            "flatpak+https" => {
+                let file_name = path.rsplit('/').next()?;
+                Self::Id(file_name.strip_suffix(".flatpakref")?.to_string())
+            }
+            _ => return None,
+        };
+        match &target {
+            Self::Id(name) | Self::Package(name) | Self::Snap(name) if name.is_empty() => None,
+            _ => Some(target),
+        }
+    }
+
+    /// Bare component ID such as com.system76.CosmicEdit, in reverse DNS format
+    pub fn from_id(arg: &str) -> Option<Self> {
+        let valid = !arg.starts_with('-')
+            && arg.split('.').count() >= 2
+            && arg.split('.').all(|part| {
+                !part.is_empty()
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            });
+        if valid {
+            Some(Self::Id(arg.to_string()))
+        } else {
+            None
+        }
+    }
+
+    /// URI that parses back to this target
+    pub fn uri(&self) -> String {
+        match self {
+            Self::Id(id) => format!("appstream://{}", id),
+            Self::Package(pkgname) => format!("apt://{}", pkgname),
+            Self::Snap(name) => format!("snap://{}", name),
+            Self::Search(input) => {
+                let query: String = appstream::url::form_urlencoded::Serializer::new(String::new())
+                    .append_pair("q", input)
+                    .finish();
+                format!("{}:?{}", SEARCH_SCHEME, query)
+            }
+            Self::File(path) => match appstream::url::Url::from_file_path(path) {
+                Ok(url) => url.to_string(),
+                Err(()) => format!("file://{}", path.display()),
+            },
+        }
+    }
 }
 
 /// Messages that are used specifically by our [`App`].
 #[derive(Clone, Debug)]
 pub enum Message {
+    AppListExport,
+    AppListExported(Result<PathBuf, String>),
+    AppListImport(PathBuf),
+    AppListImported(Result<AppList, String>),
+    AppListImportInstall,
+    AppListImportToggle(usize),
+    AppNotFoundSearch(String),
+    Autostart(bool),
     AppTheme(AppTheme),
-    Backends(Backends),
+    Density(Density),
+    Backends(Backends, Vec<String>),
+    BackendsReload,
+    Refresh,
+    ParallelOperations(Option<usize>),
+    RefreshInterval(RefreshInterval),
+    ErrorDismiss(usize),
+    ErrorRetry(usize),
+    Escape,
+    Favorite(String),
+    ForeignArchitectures(bool),
+    FreeSoftwareOnly(bool),
+    PreferredFormat(PreferredFormat),
+    FlathubAdd,
+    InstallFile(PathBuf),
+    InstallUnsigned,
+    InstallSelected,
+    CacheClear(CacheKind),
+    CacheCleared(CacheKind),
+    CacheSizes(Vec<(CacheKind, u64)>),
     CategoryResults(&'static str, Vec<SearchResult>),
+    RepoChanged,
+    RepoEnable(&'static str, String, bool),
+    Repos(Vec<(&'static str, Repo)>),
+    Storage(Vec<(&'static str, StorageUsage)>),
+    StorageRemoveUnused(&'static str),
     Config(Config),
+    DetailsTab(widget::segmented_button::Entity),
+    Developer(String),
+    DeveloperResults(String, Vec<SearchResult>),
     DialogCancel,
-    Installed(Vec<(&'static str, Package)>),
+    Featured(Vec<SearchResult>),
+    FeaturedNext,
+    FeaturedScreenshot(String, Vec<u8>),
+    FeaturedShown(usize),
+    Installed(Vec<(&'static str, Package)>, Vec<String>),
+    InstalledFilter(String),
     Key(Modifiers, Key),
+    KeyBindCapture(Action),
+    KeyBindsReset,
+    NavBack,
+    NavForward,
     OpenDesktopId(String),
+    CopyLink(String),
+    OpenUrl(String),
+    DesktopEntry(String, Result<DesktopEntry, String>),
+    ShowInFiles(PathBuf),
     Operation(OperationKind, &'static str, String, Arc<AppInfo>),
+    ResumeDiscard,
+    ResumeOperations,
+    UpdateFirmware,
     PendingComplete(u64),
     PendingError(u64, String),
     PendingProgress(u64, f32),
+    ProprietaryShown(bool),
+    ContentAgeLimit(Option<u8>),
+    OperationsAuthorized(bool),
+    RequireAuth(bool),
+    RequireAuthSet(bool),
+    ContentAgeLimitSet(Option<u8>),
+    SystemComponentsShown(bool),
+    CatalogLocaleInput(String),
+    CatalogLocaleSubmit,
+    ProxyInput(String),
+    ProxySubmit,
+    DownloadLimit(Option<u64>),
+    ReviewsServer(Option<String>),
+    ReviewsServerInput(String),
+    ReviewsServerSubmit,
+    Popular(Vec<SearchResult>),
+    Ratings(HashMap<String, OdrsRating>),
+    NetworkRetry,
+    Offline,
+    Recent(Vec<SearchResult>, Vec<SearchResult>),
+    ResultsLoadMore,
+    ReviewDraft(OdrsReviewDraft),
+    ReviewSubmit,
+    ReviewSubmitted(String, Result<(), String>),
+    ReviewVote(String, String, u64, bool),
+    ReviewWrite,
+    Reviews(String, Vec<OdrsReview>),
+    Scroll(AbsoluteOffset),
     SearchActivate,
     SearchClear,
+    SearchDescriptions(&'static str, Arc<HashMap<String, String>>),
+    SearchFilter(SearchFilter),
+    SearchHistory(usize),
+    SearchHistoryClear,
     SearchInput(String),
-    SearchResults(String, Vec<SearchResult>),
+    SearchPin(String),
+    SearchResults(String, &'static str, Vec<SearchResult>, Option<String>),
+    SearchRun(String),
+    SearchSort(SearchSort),
     SearchSubmit,
+    NavPage(NavPage),
+    OpenTarget(AppTarget),
+    SessionRequest(AppTarget, SessionReply),
     SelectInstalled(usize),
     SelectUpdates(usize),
-    SelectNone,
+    SelectFavorite(usize),
+    SelectPopular(usize),
+    SelectRecentlyAdded(usize),
+    SelectRecentlyUpdated(usize),
     SelectCategoryResult(usize),
+    SelectDeveloperResult(usize),
+    SelectFeatured(usize),
     SelectSearchResult(usize),
+    /// Select an app by ID, trying the given backend first
+    SelectAppId(Option<&'static str>, String),
     SelectedScreenshot(usize, String, Vec<u8>),
+    SelectedContentRatingExpanded(bool),
+    SelectedReleasesExpanded(bool),
     SelectedScreenshotShown(usize),
+    SelectedLightbox(bool),
+    SelectedSource(usize),
+    SyncConfig(SyncConfig),
+    SyncExport,
+    SyncExported(Result<PathBuf, String>),
+    SyncImport(PathBuf),
+    SyncImported(Result<SettingsFile, String>),
+    SyncRepos(Vec<(&'static str, Repo)>),
+    SyncReposApply,
     SystemThemeModeChange(cosmic_theme::ThemeMode),
+    Tray(bool),
     ToggleContextPage(ContextPage),
     Updates(Vec<(&'static str, Package)>),
+    UpdatesExpand(&'static str, String),
+    UpdateAll,
+    UpdatesNotifications(bool),
+    UpdatesOpen,
+    ViewLayout(ViewLayout),
+    WindowActivate,
     WindowClose,
+    WindowMaximized(bool),
+    WindowResized(f32, f32),
+    WindowSave(u64),
     WindowNew,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ContextPage {
+    SearchFilter,
     Settings,
+    Sources,
+    Storage,
 }
 
 impl ContextPage {
     fn title(&self) -> String {
         match self {
+            Self::SearchFilter => fl!("search-filters"),
             Self::Settings => fl!("settings"),
+            Self::Sources => fl!("sources"),
+            Self::Storage => fl!("storage"),
         }
     }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DialogPage {
+    AppListExported(Result<PathBuf, String>),
+    AppListImport {
+        missing: Vec<AppListMissing>,
+        unavailable: usize,
+    },
+    AppListImportFailed(String),
+    AppNotFound(String),
     FailedOperation(u64),
+    InstallFile(PathBuf),
+    InstallUnsigned(Operation),
+    ResumeOperations(Vec<Operation>),
+    SyncExported(Result<PathBuf, String>),
+    SyncImportFailed(String),
+    SyncRepos(Vec<(&'static str, Repo)>),
+    UpdateFirmware(Operation, FirmwareRelease),
+    WriteReview(OdrsReviewDraft),
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DetailsTab {
+    Overview,
+    Screenshots,
+    Reviews,
+    Releases,
+    Permissions,
+}
+
+impl DetailsTab {
+    fn all() -> &'static [Self] {
+        &[
+            Self::Overview,
+            Self::Screenshots,
+            Self::Reviews,
+            Self::Releases,
+            Self::Permissions,
+        ]
+    }
+
+    fn title(&self) -> String {
+        match self {
+            Self::Overview => fl!("overview"),
+            Self::Screenshots => fl!("screenshots"),
+            Self::Reviews => fl!("reviews"),
+            Self::Releases => fl!("version-history"),
+            Self::Permissions => fl!("permissions"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum NavPage {
+    Explore,
     Create,
     Work,
     Develop,
@@ -185,6 +647,9 @@ pub enum NavPage {
 impl NavPage {
     fn all() -> &'static [Self] {
         &[
+            Self::Explore,
+            Self::Installed,
+            Self::Updates,
             Self::Create,
             Self::Work,
             Self::Develop,
@@ -193,13 +658,12 @@ impl NavPage {
             Self::Relax,
             Self::Socialize,
             Self::Utilities,
-            Self::Installed,
-            Self::Updates,
         ]
     }
 
     fn title(&self) -> String {
         match self {
+            Self::Explore => fl!("explore"),
             Self::Create => fl!("create"),
             Self::Work => fl!("work"),
             Self::Develop => fl!("develop"),
@@ -235,6 +699,7 @@ impl NavPage {
 
     fn icon(&self) -> widget::icon::Icon {
         match self {
+            Self::Explore => icon_cache_get("store-home-symbolic", 16),
             Self::Create => icon_cache_get("store-create-symbolic", 16),
             Self::Work => icon_cache_get("store-work-symbolic", 16),
             Self::Develop => icon_cache_get("store-develop-symbolic", 16),
@@ -253,9 +718,14 @@ impl Package {
     pub fn card_view<'a>(
         &'a self,
         controls: Vec<Element<'a, Message>>,
+        view_layout: ViewLayout,
+        density: Density,
         spacing: &cosmic_theme::Spacing,
     ) -> Element<'a, Message> {
-        let width = 360.0 + 2.0 * spacing.space_s as f32;
+        let width = match view_layout {
+            ViewLayout::Grid => Length::Fixed(360.0 + 2.0 * spacing.space_s as f32),
+            ViewLayout::List => Length::Fill,
+        };
         let mut height = 88.0 + 2.0 * spacing.space_xxs as f32;
         let mut column = widget::column::with_children(vec![
             widget::text::body(&self.info.name)
@@ -284,7 +754,7 @@ impl Package {
         widget::container(
             widget::row::with_children(vec![
                 widget::icon::icon(self.icon.clone())
-                    .size(ICON_SIZE_PACKAGE)
+                    .size(density.icon_size(ICON_SIZE_PACKAGE))
                     .into(),
                 column.into(),
             ])
@@ -292,7 +762,7 @@ impl Package {
             .spacing(spacing.space_s),
         )
         .center_y()
-        .width(Length::Fixed(width))
+        .width(width)
         .height(Length::Fixed(height))
         .padding([spacing.space_xxs, spacing.space_s])
         .style(theme::Container::Card)
@@ -300,6 +770,516 @@ impl Package {
     }
 }
 
+/// Weight of a match in text, starting at base if the text equals the search phrase, base + 1 if
+/// the text starts with the search phrase, and base + 2 if the text contains the search phrase
+fn regex_weight(regex: &regex::Regex, text: &str, base: usize) -> Option<usize> {
+    let mat = regex.find(text)?;
+    if mat.range().start == 0 {
+        if mat.range().end == text.len() {
+            // Text equals search phrase
+            Some(base)
+        } else {
+            // Text starts with search phrase
+            Some(base + 1)
+        }
+    } else {
+        // Text contains search phrase
+        Some(base + 2)
+    }
+}
+
+/// Filters applied to search results without running the search again
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SearchFilter {
+    backend_opt: Option<&'static str>,
+    category_opt: Option<&'static str>,
+    description: bool,
+    free_only: bool,
+    installed_only: bool,
+    system_components: bool,
+}
+
+/// Returns true if the app supports the MIME type, which may end in a wildcard like image/*
+fn mime_matches(mime: &str, info: &AppInfo) -> bool {
+    match mime.strip_suffix("/*") {
+        Some(prefix) => info.mimetypes.iter().any(|x| {
+            x.split_once('/')
+                .map_or(false, |(x_prefix, _)| x_prefix.eq_ignore_ascii_case(prefix))
+        }),
+        None => info.mimetypes.iter().any(|x| x.eq_ignore_ascii_case(mime)),
+    }
+}
+
+/// Returns true if the app id, a desktop id, a binary, or a package name equals the input
+fn identifier_matches(input: &str, id: &str, info: &AppInfo) -> bool {
+    let matches = |identifier: &str| {
+        let identifier = identifier.trim_end_matches(".desktop");
+        // Match both full reverse DNS identifiers and their last component
+        identifier.eq_ignore_ascii_case(input)
+            || identifier
+                .rsplit('.')
+                .next()
+                .map_or(false, |x| x.eq_ignore_ascii_case(input))
+    };
+    matches(id)
+        || info.desktop_ids.iter().any(|x| matches(x))
+        || info.pkgnames.iter().any(|x| x.eq_ignore_ascii_case(input))
+        || info.provides.iter().any(|provide| match provide {
+            AppProvide::Binary(binary) => binary.eq_ignore_ascii_case(input),
+            AppProvide::Id(provide_id) => matches(provide_id),
+            _ => false,
+        })
+}
+
+/// Fold case and strip diacritics, so that "Tetriš" and "tetris" compare equal
+fn search_fold(text: &str) -> String {
+    text.chars().flat_map(search_fold_char).collect()
+}
+
+fn search_fold_char(c: char) -> impl Iterator<Item = char> {
+    std::iter::once(c)
+        .nfd()
+        .filter(|c| !unicode_normalization::char::is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+}
+
+/// Find the range of the regex match in the original text, where the regex matches folded text
+fn search_match_range(regex: &regex::Regex, text: &str) -> Option<ops::Range<usize>> {
+    // Map each byte of folded text to the byte offset of the original character
+    let mut folded = String::with_capacity(text.len());
+    let mut offsets = Vec::with_capacity(text.len() + 1);
+    for (offset, c) in text.char_indices() {
+        for folded_c in search_fold_char(c) {
+            folded.push(folded_c);
+            while offsets.len() < folded.len() {
+                offsets.push(offset);
+            }
+        }
+    }
+    offsets.push(text.len());
+
+    let mat = regex.find(&folded)?;
+    let start = offsets[mat.start()];
+    // The end of the match is the start of the next original character
+    let end = offsets[mat.end()..]
+        .iter()
+        .copied()
+        .find(|offset| *offset > start)
+        .unwrap_or(text.len());
+    Some(start..end)
+}
+
+/// Text with the matched range highlighted using the accent color
+fn highlighted_text<'a>(
+    text: &'a str,
+    range_opt: Option<&ops::Range<usize>>,
+    caption: bool,
+) -> Element<'a, Message> {
+    let text_widget = |segment: &'a str| {
+        if caption {
+            widget::text::caption(segment)
+        } else {
+            widget::text::body(segment)
+        }
+    };
+    match range_opt.filter(|range| text.get((*range).clone()).is_some()) {
+        Some(range) => widget::row::with_children(vec![
+            text_widget(&text[..range.start]).into(),
+            text_widget(&text[range.clone()])
+                .style(theme::Text::Accent)
+                .into(),
+            text_widget(&text[range.end..]).into(),
+        ])
+        .into(),
+        None => text_widget(text).into(),
+    }
+}
+
+/// Total physical memory in MiB, read from /proc/meminfo
+fn system_memory_mib() -> Option<u64> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    for line in meminfo.lines() {
+        if let Some(value) = line.strip_prefix("MemTotal:") {
+            let kib: u64 = value.trim().trim_end_matches("kB").trim().parse().ok()?;
+            return Some(kib / 1024);
+        }
+    }
+    None
+}
+
+/// Screenshots of the selected app matching the theme mode, and the index of the shown screenshot
+fn selected_screenshots(selected: &Selected) -> Option<(Vec<(usize, &AppScreenshot)>, usize)> {
+    let screenshots = selected
+        .info
+        .screenshots_for_theme(theme::active().cosmic().is_dark);
+    let screenshot_shown = selected
+        .screenshot_shown
+        .min(screenshots.len().checked_sub(1)?);
+    Some((screenshots, screenshot_shown))
+}
+
+/// Centered message shown when a page has nothing to show, with an optional action
+fn empty_state_view<'a>(
+    icon_name: &'static str,
+    title: String,
+    body: String,
+    action_opt: Option<(String, Message)>,
+    spacing: &cosmic_theme::Spacing,
+) -> Element<'a, Message> {
+    let mut column = widget::column::with_capacity(4)
+        .push(widget::icon::from_name(icon_name).size(64))
+        .push(widget::text::title3(title))
+        .push(widget::text::body(body))
+        .align_items(Alignment::Center)
+        .spacing(spacing.space_s);
+    if let Some((action, message)) = action_opt {
+        column = column.push(widget::button::suggested(action).on_press(message));
+    }
+    widget::container(column)
+        .center_x()
+        .width(Length::Fill)
+        .padding(spacing.space_xl)
+        .into()
+}
+
+/// Translated name of a freedesktop category id, if it is known
+// From https://specifications.freedesktop.org/menu-spec/latest/apa.html
+fn category_name(category: &str) -> Option<String> {
+    match category {
+        "AudioVideo" => Some(fl!("category-audio-video")),
+        "Audio" => Some(fl!("category-audio")),
+        "Video" => Some(fl!("category-video")),
+        "Development" => Some(fl!("category-development")),
+        "Education" => Some(fl!("category-education")),
+        "Game" => Some(fl!("category-game")),
+        "Graphics" => Some(fl!("category-graphics")),
+        "Network" => Some(fl!("category-network")),
+        "Office" => Some(fl!("category-office")),
+        "Science" => Some(fl!("category-science")),
+        "Settings" => Some(fl!("category-settings")),
+        "System" => Some(fl!("category-system")),
+        "Utility" => Some(fl!("category-utility")),
+        "Chat" => Some(fl!("category-chat")),
+        "Email" => Some(fl!("category-email")),
+        "WebBrowser" => Some(fl!("category-web-browser")),
+        "Music" => Some(fl!("category-music")),
+        "Photography" => Some(fl!("category-photography")),
+        "TextEditor" => Some(fl!("category-text-editor")),
+        _ => None,
+    }
+}
+
+/// Link to share an app, using the Flathub website for apps from Flathub
+fn app_link(selected: &Selected) -> String {
+    if selected.backend_name == "flatpak" && selected.info.origin_opt.as_deref() == Some("flathub")
+    {
+        format!("https://flathub.org/apps/{}", selected.id)
+    } else {
+        format!("appstream://{}", selected.id)
+    }
+}
+
+/// Human readable size using decimal units
+fn format_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if size < 1000 {
+        return format!("{} B", size);
+    }
+    let mut value = size as f64 / 1000.0;
+    let mut unit = UNITS[0];
+    for next_unit in UNITS.iter().skip(1) {
+        if value < 1000.0 {
+            break;
+        }
+        value /= 1000.0;
+        unit = next_unit;
+    }
+    format!("{:.1} {}", value, unit)
+}
+
+/// Names of loaded backends, with backends of the preferred package format first
+fn sorted_backend_names(
+    backends: &Backends,
+    preferred_format: PreferredFormat,
+) -> Vec<&'static str> {
+    let mut backend_names: Vec<_> = backends.keys().copied().collect();
+    backend_names
+        .sort_by_key(|backend_name| (preferred_format.backend_rank(backend_name), *backend_name));
+    backend_names
+}
+
+/// Keep one result for each app, from the backend of the preferred package format
+fn dedup_results(results: &mut Vec<SearchResult>, preferred_format: PreferredFormat) {
+    dedup_results_by(results, preferred_format, |result| result);
+}
+
+fn dedup_results_by<T>(
+    results: &mut Vec<T>,
+    preferred_format: PreferredFormat,
+    f: impl Fn(&T) -> &SearchResult,
+) {
+    let mut ranks = HashMap::<String, usize>::new();
+    for result in results.iter().map(&f) {
+        let rank = preferred_format.backend_rank(result.backend_name);
+        ranks
+            .entry(result.id.clone())
+            .and_modify(|best| *best = (*best).min(rank))
+            .or_insert(rank);
+    }
+    let mut ids = HashSet::new();
+    results.retain(|result| {
+        let result = f(result);
+        ranks[&result.id] == preferred_format.backend_rank(result.backend_name)
+            && ids.insert(result.id.clone())
+    });
+}
+
+/// Row of five star icons, stars is from 0.0 to 5.0
+fn stars_view(stars: f32, size: u16) -> Element<'static, Message> {
+    let mut row = widget::row::with_capacity(5);
+    for i in 0..5 {
+        let remaining = stars - i as f32;
+        row = row.push(
+            widget::icon::from_name(if remaining >= 0.75 {
+                "starred-symbolic"
+            } else if remaining >= 0.25 {
+                "semi-starred-symbolic"
+            } else {
+                "non-starred-symbolic"
+            })
+            .size(size)
+            .icon(),
+        );
+    }
+    row.into()
+}
+
+fn markup_spans_view(spans: &[AppMarkupSpan]) -> Element<'static, Message> {
+    let text = app_info::spans_text(spans);
+    //TODO: inline styling once rich text is supported, for now only fully emphasized blocks are styled
+    if !spans.is_empty()
+        && spans
+            .iter()
+            .all(|span| matches!(span, AppMarkupSpan::Emphasis(_)))
+    {
+        widget::text::heading(text).width(Length::Fill).into()
+    } else {
+        widget::text::body(text).width(Length::Fill).into()
+    }
+}
+
+fn markup_view(blocks: &[AppMarkup], spacing: &cosmic_theme::Spacing) -> Element<'static, Message> {
+    let list_view = |items: &[Vec<AppMarkupSpan>], ordered: bool| -> Element<'static, Message> {
+        let mut column = widget::column::with_capacity(items.len()).spacing(spacing.space_xxxs);
+        for (i, item) in items.iter().enumerate() {
+            column = column.push(
+                widget::row::with_children(vec![
+                    widget::text::body(if ordered {
+                        format!("{}.", i + 1)
+                    } else {
+                        "•".to_string()
+                    })
+                    .width(Length::Fixed(24.0))
+                    .into(),
+                    markup_spans_view(item),
+                ])
+                .spacing(spacing.space_xxs),
+            );
+        }
+        column.padding([0, 0, 0, spacing.space_s]).into()
+    };
+
+    let mut column = widget::column::with_capacity(blocks.len())
+        .spacing(spacing.space_s)
+        .width(Length::Fill);
+    for block in blocks.iter() {
+        column = column.push(match block {
+            AppMarkup::Paragraph(spans) => markup_spans_view(spans),
+            AppMarkup::OrderedList(items) => list_view(items, true),
+            AppMarkup::UnorderedList(items) => list_view(items, false),
+            AppMarkup::Preformatted(text) => {
+                widget::container(widget::text::body(text.clone()).width(Length::Fill))
+                    .padding(spacing.space_xs)
+                    .style(theme::Container::Card)
+                    .into()
+            }
+        });
+    }
+    column.into()
+}
+
+/// Versions, urgency, fixed issues, and requirements of a firmware update
+fn firmware_view(
+    firmware: &FirmwareRelease,
+    version: &str,
+    spacing: &cosmic_theme::Spacing,
+) -> Element<'static, Message> {
+    let mut column = widget::column::with_capacity(6).spacing(spacing.space_xxxs);
+    column = column.push(widget::text::body(fl!(
+        "firmware-version",
+        current = firmware.current_version.as_str(),
+        new = version
+    )));
+    let urgency_opt = match firmware.urgency {
+        FirmwareUrgency::Unknown => None,
+        FirmwareUrgency::Low => Some(fl!("firmware-urgency-low")),
+        FirmwareUrgency::Medium => Some(fl!("firmware-urgency-medium")),
+        FirmwareUrgency::High => Some(fl!("firmware-urgency-high")),
+        FirmwareUrgency::Critical => Some(fl!("firmware-urgency-critical")),
+    };
+    if let Some(urgency) = urgency_opt {
+        column = column.push(widget::text::body(fl!(
+            "firmware-urgency",
+            urgency = urgency
+        )));
+    }
+    if !firmware.issues.is_empty() {
+        column = column.push(widget::text::body(fl!(
+            "firmware-issues",
+            issues = firmware.issues.join(", ")
+        )));
+    }
+    if firmware.requires_ac {
+        column = column.push(widget::text::body(fl!("firmware-requires-ac")));
+    }
+    if firmware.needs_reboot {
+        column = column.push(widget::text::body(fl!("firmware-needs-reboot")));
+    }
+    if let Some(detach_caption) = &firmware.detach_caption_opt {
+        column = column.push(widget::text::body(detach_caption.clone()));
+    }
+    column.into()
+}
+
+/// Number of single character edits needed to change one string into another
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut curr = vec![0; b_chars.len() + 1];
+    for (i, a_c) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, b_c) in b_chars.iter().enumerate() {
+            let substitution = prev[j] + if a_c == *b_c { 0 } else { 1 };
+            curr[j + 1] = cmp::min(substitution, cmp::min(prev[j + 1], curr[j]) + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b_chars.len()]
+}
+
+/// Find the app name or name word closest to the folded input, for use when a search finds nothing
+fn search_suggestion(appstream_cache: &AppstreamCache, input: &str) -> Option<String> {
+    // Allow one edit for every three characters
+    let max_distance = cmp::max(1, input.chars().count() / 3);
+    appstream_cache
+        .infos
+        .par_iter()
+        .filter_map(|(_id, info)| {
+            let name = &info.search_text.name;
+            let mut candidates: Vec<&str> = name.split_whitespace().collect();
+            candidates.push(name);
+            candidates
+                .into_iter()
+                .map(|candidate| (edit_distance(input, candidate), candidate.to_string()))
+                .filter(|(distance, _)| *distance > 0 && *distance <= max_distance)
+                .min()
+        })
+        .min()
+        .map(|(_distance, candidate)| candidate)
+}
+
+/// Weight of a search result, lower is better. Returns None if there is no match. Input, regex,
+/// and description are expected to be folded using [`search_fold`]
+fn search_weight(
+    regex: &regex::Regex,
+    input: &str,
+    id: &str,
+    info: &AppInfo,
+    description_opt: Option<&str>,
+) -> Option<usize> {
+    match regex_weight(regex, &info.search_text.name, 0) {
+        // Name equals search phrase
+        Some(0) => Some(0),
+        name_weight_opt => {
+            if identifier_matches(input, id, info) {
+                // Identifier equals search phrase
+                Some(1)
+            } else {
+                name_weight_opt.map(|weight| weight + 1)
+            }
+        }
+    }
+    .or_else(|| {
+        info.search_text
+            .keywords
+            .iter()
+            .filter_map(|keyword| regex_weight(regex, keyword, 4))
+            .min()
+    })
+    .or_else(|| regex_weight(regex, &info.search_text.summary, 7))
+    .or_else(|| regex_weight(regex, description_opt?, 10))
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SearchSort {
+    Relevance,
+    Name,
+    RecentlyUpdated,
+    Size,
+}
+
+impl SearchSort {
+    fn all() -> &'static [Self] {
+        &[
+            Self::Relevance,
+            Self::Name,
+            Self::RecentlyUpdated,
+            Self::Size,
+        ]
+    }
+
+    fn title(&self) -> String {
+        match self {
+            Self::Relevance => fl!("sort-relevance"),
+            Self::Name => fl!("sort-name"),
+            Self::RecentlyUpdated => fl!("sort-recently-updated"),
+            Self::Size => fl!("sort-size"),
+        }
+    }
+
+    fn sort(&self, results: &mut [SearchResult]) {
+        let by_name = |a: &SearchResult, b: &SearchResult| {
+            lexical_sort::natural_lexical_cmp(&a.info.name, &b.info.name)
+        };
+        match self {
+            Self::Relevance => results.sort_by(|a, b| match a.weight.cmp(&b.weight) {
+                cmp::Ordering::Equal => by_name(a, b),
+                ordering => ordering,
+            }),
+            Self::Name => results.sort_by(by_name),
+            // Newest first, apps without release dates last
+            Self::RecentlyUpdated => {
+                results.sort_by(
+                    |a, b| match b.info.updated_opt().cmp(&a.info.updated_opt()) {
+                        cmp::Ordering::Equal => by_name(a, b),
+                        ordering => ordering,
+                    },
+                )
+            }
+            // Smallest first, apps without sizes last
+            Self::Size => results.sort_by(|a, b| match (a.info.size_opt, b.info.size_opt) {
+                (Some(a_size), Some(b_size)) => match a_size.cmp(&b_size) {
+                    cmp::Ordering::Equal => by_name(a, b),
+                    ordering => ordering,
+                },
+                (Some(_), None) => cmp::Ordering::Less,
+                (None, Some(_)) => cmp::Ordering::Greater,
+                (None, None) => by_name(a, b),
+            }),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SearchResult {
     backend_name: &'static str,
@@ -307,116 +1287,366 @@ pub struct SearchResult {
     icon: widget::icon::Handle,
     info: Arc<AppInfo>,
     weight: usize,
+    /// Byte range of the search match in the name
+    name_match_opt: Option<ops::Range<usize>>,
+    /// Byte range of the search match in the summary
+    summary_match_opt: Option<ops::Range<usize>>,
 }
 
 impl SearchResult {
-    pub fn card_view<'a>(&'a self, spacing: &cosmic_theme::Spacing) -> Element<'a, Message> {
+    pub fn card_view<'a>(
+        &'a self,
+        rating_opt: Option<&OdrsRating>,
+        installed: bool,
+        density: Density,
+        narrow: bool,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Element<'a, Message> {
+        let mut name_row = widget::row::with_capacity(2)
+            .align_items(Alignment::Center)
+            .spacing(spacing.space_xxs)
+            .push(
+                widget::container(highlighted_text(
+                    &self.info.name,
+                    self.name_match_opt.as_ref(),
+                    false,
+                ))
+                .width(Length::Fill),
+            );
+        if installed {
+            name_row =
+                name_row.push(widget::text::caption(fl!("installed")).style(theme::Text::Accent));
+        } else if !self.info.arch_supported() {
+            name_row = name_row.push(widget::text::caption(fl!("not-installable")));
+        }
+        let mut column = widget::column::with_capacity(3)
+            .push(widget::container(name_row).height(Length::Fixed(20.0)));
+        if narrow {
+            // Hide source and rating so the summary can wrap to three lines
+            column = column.push(
+                widget::container(highlighted_text(
+                    &self.info.summary,
+                    self.summary_match_opt.as_ref(),
+                    true,
+                ))
+                .height(Length::Fixed(42.0)),
+            );
+        } else {
+            column = column
+                .push(
+                    widget::container(highlighted_text(
+                        &self.info.summary,
+                        self.summary_match_opt.as_ref(),
+                        true,
+                    ))
+                    .height(Length::Fixed(28.0)),
+                )
+                .push(
+                    widget::row::with_children(vec![
+                        if self.info.verified_opt.is_some() {
+                            widget::icon::from_name("emblem-ok-symbolic")
+                                .size(12)
+                                .into()
+                        } else {
+                            widget::Space::with_width(Length::Shrink).into()
+                        },
+                        if self.info.sandboxed() {
+                            widget::icon::from_name("security-high-symbolic")
+                                .size(12)
+                                .into()
+                        } else {
+                            widget::Space::with_width(Length::Shrink).into()
+                        },
+                        //TODO: Combine origins
+                        widget::text::caption(self.info.origin_opt.as_deref().unwrap_or(""))
+                            .width(Length::Fill)
+                            .into(),
+                        match rating_opt.and_then(|rating| rating.average()) {
+                            Some(average) => stars_view(average, 12),
+                            None => widget::Space::with_width(Length::Shrink).into(),
+                        },
+                    ])
+                    .align_items(Alignment::Center),
+                );
+        }
         widget::container(
             widget::row::with_children(vec![
                 widget::icon::icon(self.icon.clone())
-                    .size(ICON_SIZE_SEARCH)
+                    .size(density.icon_size(ICON_SIZE_SEARCH))
                     .into(),
-                widget::column::with_children(vec![
-                    widget::text::body(&self.info.name)
-                        .height(Length::Fixed(20.0))
-                        .into(),
-                    widget::text::caption(&self.info.summary)
-                        .height(Length::Fixed(28.0))
-                        .into(),
-                    //TODO: Combine origins
-                    widget::text::caption(self.info.origin_opt.as_deref().unwrap_or("")).into(),
-                ])
-                .into(),
+                column.into(),
             ])
             .align_items(Alignment::Center)
             .spacing(spacing.space_s),
         )
         .center_y()
-        .width(Length::Fixed(240.0 + (spacing.space_s as f32) * 2.0))
+        .width(Length::Fill)
         .height(Length::Fixed(62.0 + (spacing.space_xxs as f32) * 2.0))
         .padding([spacing.space_xxs, spacing.space_s])
         .style(theme::Container::Card)
         .into()
     }
-}
-
-#[derive(Clone, Debug)]
-pub struct Selected {
-    backend_name: &'static str,
-    id: String,
-    icon: widget::icon::Handle,
-    info: Arc<AppInfo>,
-    screenshot_images: HashMap<usize, widget::image::Handle>,
-    screenshot_shown: usize,
-}
 
-/// The [`App`] stores application-specific state.
-pub struct App {
-    core: Core,
-    config_handler: Option<cosmic_config::Config>,
-    config: Config,
-    locale: String,
-    app_themes: Vec<String>,
+    pub fn grid_view<'a>(
+        &'a self,
+        rating_opt: Option<&OdrsRating>,
+        installed: bool,
+        density: Density,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Element<'a, Message> {
+        widget::container(
+            widget::column::with_children(vec![
+                widget::icon::icon(self.icon.clone())
+                    .size(density.icon_size(ICON_SIZE_GRID))
+                    .into(),
+                widget::container(highlighted_text(
+                    &self.info.name,
+                    self.name_match_opt.as_ref(),
+                    false,
+                ))
+                .center_x()
+                .width(Length::Fill)
+                .height(Length::Fixed(20.0))
+                .into(),
+                widget::row::with_children(vec![
+                    if installed {
+                        widget::icon::from_name("object-select-symbolic")
+                            .size(12)
+                            .into()
+                    } else if !self.info.arch_supported() {
+                        widget::icon::from_name("action-unavailable-symbolic")
+                            .size(12)
+                            .into()
+                    } else {
+                        widget::Space::with_width(Length::Shrink).into()
+                    },
+                    if self.info.sandboxed() {
+                        widget::icon::from_name("security-high-symbolic")
+                            .size(12)
+                            .into()
+                    } else {
+                        widget::Space::with_width(Length::Shrink).into()
+                    },
+                    match rating_opt.and_then(|rating| rating.average()) {
+                        Some(average) => stars_view(average, 12),
+                        None => widget::Space::with_height(Length::Fixed(12.0)).into(),
+                    },
+                ])
+                .align_items(Alignment::Center)
+                .spacing(spacing.space_xxs)
+                .into(),
+                widget::container(highlighted_text(
+                    &self.info.summary,
+                    self.summary_match_opt.as_ref(),
+                    true,
+                ))
+                .center_x()
+                .width(Length::Fill)
+                .height(Length::Fixed(28.0))
+                .into(),
+            ])
+            .align_items(Alignment::Center)
+            .spacing(spacing.space_xxs),
+        )
+        .center_x()
+        .width(Length::Fixed(180.0 + (spacing.space_s as f32) * 2.0))
+        .height(Length::Fixed(
+            density.icon_size(ICON_SIZE_GRID) as f32
+                + 60.0
+                + (spacing.space_xxs as f32) * 3.0
+                + (spacing.space_s as f32) * 2.0,
+        ))
+        .padding(spacing.space_s)
+        .style(theme::Container::Card)
+        .into()
+    }
+}
+
+/// Backend that provides the selected app, there may be more than one
+#[derive(Clone, Debug)]
+pub struct SelectedSource {
+    backend_name: &'static str,
+    icon: widget::icon::Handle,
+    info: Arc<AppInfo>,
+    trust: SourceTrust,
+}
+
+#[derive(Clone, Debug)]
+pub struct Selected {
+    backend_name: &'static str,
+    id: String,
+    icon: widget::icon::Handle,
+    info: Arc<AppInfo>,
+    screenshot_images: HashMap<usize, widget::image::Handle>,
+    screenshot_shown: usize,
+    releases_expanded: bool,
+    content_rating_expanded: bool,
+    sources: Vec<SelectedSource>,
+    source_names: Vec<String>,
+    /// Show the current screenshot in a full window viewer
+    lightbox: bool,
+}
+
+/// Background task shown in the busy indicator
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum BusyTask {
+    Backends,
+    Installed,
+    Updates,
+}
+
+impl BusyTask {
+    fn title(&self) -> String {
+        match self {
+            Self::Backends => fl!("busy-backends"),
+            Self::Installed => fl!("busy-installed"),
+            Self::Updates => fl!("busy-updates"),
+        }
+    }
+}
+
+/// Failed operation shown in an error banner, which can be retried
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ErrorRetry {
+    Backends,
+    Installed,
+}
+
+/// Snapshot of the visible page, used for back and forward navigation
+#[derive(Clone, Debug)]
+pub struct NavState {
+    nav_id: widget::nav_bar::Id,
+    category_results: Option<(&'static str, Vec<SearchResult>)>,
+    developer_results: Option<(String, Option<Vec<SearchResult>>)>,
+    search_active: bool,
+    search_input: String,
+    search_results: Option<(String, Vec<SearchResult>)>,
+    selected_opt: Option<Selected>,
+    scroll_offset: AbsoluteOffset,
+}
+
+/// The [`App`] stores application-specific state.
+pub struct App {
+    core: Core,
+    config_handler: Option<cosmic_config::Config>,
+    config: Config,
+    sync_config_handler: Option<cosmic_config::Config>,
+    sync_config: SyncConfig,
+    /// Locale used for the catalog, from settings or the system
+    locale: String,
+    system_locale: String,
+    app_themes: Vec<String>,
+    /// Start hidden at login, from the autostart entry
+    autostart: bool,
+    densities: Vec<String>,
+    content_age_limits: Vec<String>,
+    download_limits: Vec<String>,
+    parallel_operations: Vec<String>,
+    preferred_formats: Vec<String>,
+    refresh_intervals: Vec<String>,
     backends: Backends,
+    backends_loaded: bool,
+    error_banners: Vec<(ErrorRetry, String)>,
+    busy: HashSet<BusyTask>,
+    window_width: f32,
+    window_height: f32,
+    /// Incremented on each resize, so the window size is only saved after resizing stops
+    window_resize_id: u64,
+    /// App ID from the command line, shown once backends are loaded
+    target_pending_opt: Option<AppTarget>,
     context_page: ContextPage,
+    details_tab_model: widget::segmented_button::SingleSelectModel,
     dialog_pages: VecDeque<DialogPage>,
+    featured: Vec<SearchResult>,
+    featured_images: HashMap<String, widget::image::Handle>,
+    /// Desktop files of installed apps, by desktop ID
+    desktop_entries: HashMap<String, Result<DesktopEntry, String>>,
+    featured_shown: usize,
+    favorites: Vec<SearchResult>,
+    popular: Vec<SearchResult>,
+    recently_added: Vec<SearchResult>,
+    recently_updated: Vec<SearchResult>,
+    offline: bool,
+    ratings: HashMap<String, OdrsRating>,
+    reviews: HashMap<String, Vec<OdrsReview>>,
+    review_error_opt: Option<String>,
+    review_submitting: bool,
+    review_votes: HashSet<u64>,
     key_binds: HashMap<KeyBind, Action>,
+    /// Proprietary apps are shown in the current view despite free software only mode
+    proprietary_shown: bool,
+    /// Show runtimes, libraries, and other packages that are not apps on the installed page
+    system_components_shown: bool,
+    /// Action waiting for a key press to bind it in settings
+    key_bind_capture_opt: Option<Action>,
     nav_model: widget::nav_bar::Model,
     pending_operation_id: u64,
     pending_operations: BTreeMap<u64, (Operation, f32)>,
+    /// Session install requests, waiting for the user to start an install or for the operation
+    /// with the given ID to finish
+    session_requests: Vec<(AppTarget, Option<u64>, SessionReply)>,
+    /// Sizes of disk caches shown in settings, None until measured
+    cache_sizes_opt: Option<HashMap<CacheKind, u64>>,
+    /// Disk usage of backends that report it, None until measured
+    storage_opt: Option<Vec<(&'static str, StorageUsage)>>,
+    /// Optional repositories of backends, None until loaded
+    repos_opt: Option<Vec<(&'static str, Repo)>>,
+    /// Pending operations that have been started, the rest wait for a free slot
+    running_operations: HashSet<u64>,
+    /// Queued operations were authorized, reset when the queue drains
+    operations_authorized: bool,
     failed_operations: BTreeMap<u64, (Operation, String)>,
     search_active: bool,
+    /// Folded long descriptions by backend, only loaded while description search is enabled
+    search_descriptions: HashMap<&'static str, Arc<HashMap<String, String>>>,
+    search_filter: SearchFilter,
+    search_filter_backends: Vec<String>,
+    search_filter_categories: Vec<String>,
+    search_history_selected: Option<usize>,
     search_id: widget::Id,
     search_input: String,
+    catalog_locale_input: String,
+    proxy_input: String,
+    reviews_server_input: String,
     stats: Arc<Vec<(String, u64)>>,
     installed: Option<Vec<(&'static str, Package)>>,
+    installed_filter: String,
     updates: Option<Vec<(&'static str, Package)>>,
+    /// Summary and body of the updates notification being shown
+    updates_notification_opt: Option<(String, String)>,
+    updates_expanded: HashSet<(&'static str, String)>,
     waiting_installed: Vec<(&'static str, String)>,
     waiting_updates: Vec<(&'static str, String)>,
     category_results: Option<(&'static str, Vec<SearchResult>)>,
+    developer_results: Option<(String, Option<Vec<SearchResult>>)>,
+    results_limit: usize,
     search_results: Option<(String, Vec<SearchResult>)>,
+    search_sort: SearchSort,
+    search_sort_names: Vec<String>,
+    search_suggestion_opt: Option<String>,
     selected_opt: Option<Selected>,
+    nav_back: Vec<NavState>,
+    nav_forward: Vec<NavState>,
+    nav_page_states: HashMap<NavPage, NavState>,
+    scroll_id: scrollable::Id,
+    scroll_offset: AbsoluteOffset,
 }
 
 impl App {
-    fn open_desktop_id(&self, mut desktop_id: String) -> Command<Message> {
+    fn open_desktop_id(&self, desktop_id: String) -> Command<Message> {
         Command::perform(
             async move {
                 tokio::task::spawn_blocking(move || {
-                    if !desktop_id.ends_with(".desktop") {
-                        desktop_id.push_str(".desktop");
-                    }
-                    let xdg_dirs = match xdg::BaseDirectories::with_prefix("applications") {
-                        Ok(ok) => ok,
-                        Err(err) => {
-                            log::warn!("failed to find applications xdg directories: {}", err);
-                            return message::none();
-                        }
-                    };
-                    let path = match xdg_dirs.find_data_file(&desktop_id) {
-                        Some(some) => some,
-                        None => {
-                            log::warn!("failed to find desktop file for {:?}", desktop_id);
-                            return message::none();
-                        }
-                    };
-                    let entry = match freedesktop_entry_parser::parse_entry(&path) {
+                    let entry = match DesktopEntry::load(&desktop_id) {
                         Ok(ok) => ok,
                         Err(err) => {
-                            log::warn!("failed to read desktop file {:?}: {}", path, err);
+                            log::warn!("failed to load desktop ID {:?}: {}", desktop_id, err);
                             return message::none();
                         }
                     };
                     //TODO: handlne Terminal=true
-                    let exec = match entry.section("Desktop Entry").attr("Exec") {
-                        Some(some) => some,
-                        None => {
-                            log::warn!("no exec section in {:?}", path);
-                            return message::none();
-                        }
-                    };
                     //TODO: use libcosmic for loading desktop data
-                    cosmic::desktop::spawn_desktop_exec(exec, Vec::<(&str, &str)>::new());
+                    cosmic::desktop::spawn_desktop_exec(&entry.exec, Vec::<(&str, &str)>::new());
                     message::none()
                 })
                 .await
@@ -429,26 +1659,181 @@ impl App {
     fn operation(&mut self, operation: Operation) {
         let id = self.pending_operation_id;
         self.pending_operation_id += 1;
+        if matches!(
+            operation.kind,
+            OperationKind::Install | OperationKind::InstallFile
+        ) {
+            // Session requests are answered when an install of what they asked for finishes
+            let request_i_opt = self
+                .session_requests
+                .iter()
+                .position(|(target, id_opt, _)| {
+                    id_opt.is_none() && self.session_target_matches(target, &operation)
+                });
+            if let Some(request_i) = request_i_opt {
+                self.session_requests[request_i].1 = Some(id);
+            }
+        }
         self.pending_operations.insert(id, (operation, 0.0));
+        self.pending_save();
+        self.operations_start();
+    }
+
+    /// Returns true if the operation installs what a session request asked for
+    fn session_target_matches(&self, target: &AppTarget, operation: &Operation) -> bool {
+        // Searches match the results shown for them, packages not in appstream data are searched
+        let search_matches = |input: &str| match &self.search_results {
+            Some((search_input, results)) if search_input == input => {
+                results.iter().any(|result| {
+                    result.backend_name == operation.backend_name
+                        && result.id == operation.package_id
+                })
+            }
+            _ => false,
+        };
+        match target {
+            AppTarget::Package(pkgname) => {
+                operation.kind == OperationKind::Install
+                    && (operation.info.pkgnames.contains(pkgname) || search_matches(pkgname))
+            }
+            AppTarget::Search(input) => {
+                operation.kind == OperationKind::Install && search_matches(input)
+            }
+            AppTarget::File(path) => {
+                operation.kind == OperationKind::InstallFile
+                    && Path::new(&operation.package_id) == path.as_path()
+            }
+            AppTarget::Id(_) | AppTarget::Snap(_) => false,
+        }
+    }
+
+    /// Answer session requests waiting for an operation
+    fn session_reply(&mut self, id: u64, res: Result<(), String>) {
+        self.session_requests.retain(|(_, id_opt, reply)| {
+            if *id_opt == Some(id) {
+                reply.send(res.clone());
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Start queued operations in order until the parallel operations limit is reached
+    fn operations_start(&mut self) {
+        if self.pending_operations.is_empty() {
+            self.operations_authorized = false;
+            return;
+        }
+        // Operations wait for the authorization subscription
+        if self.config.require_auth && !self.operations_authorized {
+            return;
+        }
+        let limit = self.config.parallel_operations.unwrap_or(usize::MAX);
+        for id in self.pending_operations.keys() {
+            if self.running_operations.len() >= limit {
+                break;
+            }
+            self.running_operations.insert(*id);
+        }
+    }
+
+    fn pending_save(&self) {
+        if let Err(err) = saved_operations_save(self.pending_operations.values().map(|(op, _)| op))
+        {
+            log::warn!("failed to save pending operations: {}", err);
+        }
+    }
+
+    /// Ask to resume operations that were pending when the store last exited
+    fn pending_resume(&mut self) {
+        // Saved operations match the pending ones after backends are reloaded
+        if !self.pending_operations.is_empty()
+            || self
+                .dialog_pages
+                .iter()
+                .any(|page| matches!(page, DialogPage::ResumeOperations(_)))
+        {
+            return;
+        }
+        let saved_operations = match saved_operations_load() {
+            Ok(ok) => ok,
+            Err(err) => {
+                log::warn!("failed to load pending operations: {}", err);
+                return;
+            }
+        };
+        let mut operations = Vec::with_capacity(saved_operations.len());
+        for saved in saved_operations {
+            let Some(backend_name) = self
+                .backend_names()
+                .into_iter()
+                .find(|backend_name| *backend_name == saved.backend_name)
+            else {
+                log::warn!(
+                    "backend {:?} for pending operation not found",
+                    saved.backend_name
+                );
+                continue;
+            };
+            let info = match saved.kind {
+                OperationKind::RemoveUnused => {
+                    Arc::new(AppInfo::package_file(backend_name.to_string()))
+                }
+                OperationKind::InstallFile => {
+                    let name = Path::new(&saved.package_id)
+                        .file_name()
+                        .map_or_else(String::new, |x| x.to_string_lossy().to_string());
+                    Arc::new(AppInfo::package_file(name))
+                }
+                _ => match self.backends[backend_name]
+                    .info_cache()
+                    .infos
+                    .get(&saved.package_id)
+                {
+                    Some(info) => info.clone(),
+                    None => {
+                        log::warn!("app {:?} for pending operation not found", saved.package_id);
+                        continue;
+                    }
+                },
+            };
+            operations.push(Operation {
+                kind: saved.kind,
+                backend_name,
+                package_id: saved.package_id,
+                info,
+            });
+        }
+        // Nothing is started without confirmation, even when started hidden at login
+        if !operations.is_empty() {
+            self.dialog_pages
+                .push_back(DialogPage::ResumeOperations(operations));
+        }
     }
 
     fn category(&self, category: &'static str) -> Command<Message> {
         let backends = self.backends.clone();
         let stats = self.stats.clone();
+        let foreign_architectures = self.config.foreign_architectures;
+        let preferred_format = self.config.preferred_format;
         Command::perform(
             async move {
                 tokio::task::spawn_blocking(move || {
                     let start = Instant::now();
                     let mut results = Vec::<SearchResult>::new();
                     //TODO: par_iter?
-                    for (backend_name, backend) in backends.iter() {
-                        let appstream_cache = backend.info_cache();
+                    for backend_name in sorted_backend_names(&backends, preferred_format) {
+                        let appstream_cache = backends[backend_name].info_cache();
                         let mut backend_results = appstream_cache
                             .infos
                             .par_iter()
                             .filter_map(|(id, info)| {
                                 //TODO: contains doesn't work due to type mismatch
-                                if info.categories.iter().any(|x| x == category) {
+                                if info.categories.iter().any(|x| x == category)
+                                    && !info.is_system_component()
+                                    && (foreign_architectures || info.arch_supported())
+                                {
                                     let weight = stats
                                         .iter()
                                         .position(|(stats_id, _downloads)| stats_id == id)
@@ -456,9 +1841,11 @@ impl App {
                                     Some(SearchResult {
                                         backend_name,
                                         id: id.clone(),
-                                        icon: appstream_cache.icon(info),
+                                        icon: appstream_cache.icon(id, info),
                                         info: info.clone(),
                                         weight,
+                                        name_match_opt: None,
+                                        summary_match_opt: None,
                                     })
                                 } else {
                                     None
@@ -467,12 +1854,8 @@ impl App {
                             .collect();
                         results.append(&mut backend_results);
                     }
-                    results.sort_by(|a, b| match a.weight.cmp(&b.weight) {
-                        cmp::Ordering::Equal => {
-                            lexical_sort::natural_lexical_cmp(&a.info.name, &b.info.name)
-                        }
-                        ordering => ordering,
-                    });
+                    dedup_results(&mut results, preferred_format);
+                    SearchSort::Relevance.sort(&mut results);
                     let duration = start.elapsed();
                     log::info!("searched for category {:?} in {:?}", category, duration);
                     message::app(Message::CategoryResults(category, results))
@@ -484,9 +1867,100 @@ impl App {
         )
     }
 
+    fn developer(&self, developer_name: String) -> Command<Message> {
+        let backends = self.backends.clone();
+        let preferred_format = self.config.preferred_format;
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let developer = search_fold(developer_name.trim());
+                    let mut results = Vec::<SearchResult>::new();
+                    for (backend_name, backend) in backends.iter() {
+                        let appstream_cache = backend.info_cache();
+                        let ids = match appstream_cache.developers.get(&developer) {
+                            Some(some) => some,
+                            None => continue,
+                        };
+                        for id in ids.iter() {
+                            if let Some(info) = appstream_cache.infos.get(id) {
+                                results.push(SearchResult {
+                                    backend_name,
+                                    id: id.clone(),
+                                    icon: appstream_cache.icon(id, info),
+                                    info: info.clone(),
+                                    weight: 0,
+                                    name_match_opt: None,
+                                    summary_match_opt: None,
+                                });
+                            }
+                        }
+                    }
+                    dedup_results(&mut results, preferred_format);
+                    SearchSort::Name.sort(&mut results);
+                    message::app(Message::DeveloperResults(developer_name, results))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
+    fn developer_view(&self, spacing: &cosmic_theme::Spacing) -> Element<Message> {
+        let mut column = widget::column::with_capacity(3)
+            .padding([0, spacing.space_xl])
+            .spacing(spacing.space_xxs)
+            .width(Length::Fill);
+        column = column.push(widget::button::standard(fl!("back")).on_press(Message::NavBack));
+        match &self.developer_results {
+            Some((developer_name, Some(results))) => {
+                column = column.push(
+                    widget::row::with_children(vec![
+                        widget::column::with_children(vec![
+                            widget::text::title2(developer_name).into(),
+                            widget::text::body(fl!("developer-apps", count = results.len())).into(),
+                        ])
+                        .width(Length::Fill)
+                        .into(),
+                        self.view_layout_button(),
+                    ])
+                    .align_items(Alignment::Center),
+                );
+                let mut flex_row = Vec::with_capacity(results.len());
+                for (result_i, result) in results.iter().enumerate() {
+                    if self.result_hidden(&result.info) {
+                        continue;
+                    }
+                    flex_row.push(
+                        widget::mouse_area(self.result_view(result, spacing))
+                            .on_press(Message::SelectDeveloperResult(result_i))
+                            .into(),
+                    );
+                }
+                column = column.push(self.results_layout(flex_row, spacing));
+            }
+            Some((developer_name, None)) => {
+                column = column
+                    .push(widget::text::title2(developer_name))
+                    .push(widget::text::caption(fl!("loading")))
+                    .push(self.skeleton_view(spacing));
+            }
+            None => {}
+        }
+        self.page_scrollable(column)
+    }
+
+    /// Category that searches are constrained to, based on the active nav page
+    fn search_category(&self) -> Option<&'static str> {
+        self.nav_model
+            .active_data::<NavPage>()
+            .and_then(|nav_page| nav_page.category())
+    }
+
     fn search(&self) -> Command<Message> {
         let input = self.search_input.clone();
-        let pattern = regex::escape(&input);
+        let input_folded = search_fold(&input);
+        let pattern = regex::escape(&input_folded);
         let regex = match regex::RegexBuilder::new(&pattern)
             .case_insensitive(true)
             .build()
@@ -497,111 +1971,175 @@ impl App {
                 return Command::none();
             }
         };
-        let backends = self.backends.clone();
-        Command::perform(
-            async move {
-                tokio::task::spawn_blocking(move || {
-                    let start = Instant::now();
-                    let mut results = Vec::<SearchResult>::new();
-                    //TODO: par_iter?
-                    for (backend_name, backend) in backends.iter() {
+        let category_opt = self.search_category();
+        let foreign_architectures = self.config.foreign_architectures;
+        let mime_opt = input
+            .strip_prefix("mime:")
+            .map(|mime| mime.trim().to_ascii_lowercase());
+        let developer_opt = input
+            .strip_prefix("developer:")
+            .map(|developer| search_fold(developer.trim()));
+        let provides_opt = input
+            .strip_prefix("provides:")
+            .map(|provides| provides.trim().to_string());
+        // Each backend is searched separately so slow backends do not delay results from others
+        let mut commands = Vec::with_capacity(self.backends.len());
+        for (backend_name, backend) in self.backends.iter() {
+            let backend_name = *backend_name;
+            let backend = backend.clone();
+            let input = input.clone();
+            let input_folded = input_folded.clone();
+            let regex = regex.clone();
+            let mime_opt = mime_opt.clone();
+            let developer_opt = developer_opt.clone();
+            let provides_opt = provides_opt.clone();
+            let descriptions_opt = if self.search_filter.description {
+                self.search_descriptions.get(backend_name).cloned()
+            } else {
+                None
+            };
+            commands.push(Command::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        let start = Instant::now();
+                        if let Some(provides) = &provides_opt {
+                            let packages = if provides.is_empty() {
+                                Vec::new()
+                            } else {
+                                match backend.what_provides(provides) {
+                                    Ok(ok) => ok,
+                                    Err(err) => {
+                                        log::warn!(
+                                            "failed to find {} packages that provide {:?}: {}",
+                                            backend_name,
+                                            provides,
+                                            err
+                                        );
+                                        Vec::new()
+                                    }
+                                }
+                            };
+                            let results = packages
+                                .into_iter()
+                                .map(|package| SearchResult {
+                                    backend_name,
+                                    id: package.id,
+                                    icon: package.icon,
+                                    info: package.info,
+                                    weight: 0,
+                                    name_match_opt: None,
+                                    summary_match_opt: None,
+                                })
+                                .collect();
+                            let duration = start.elapsed();
+                            log::info!(
+                                "searched {} for packages that provide {:?} in {:?}",
+                                backend_name,
+                                provides,
+                                duration
+                            );
+                            return message::app(Message::SearchResults(
+                                input,
+                                backend_name,
+                                results,
+                                None,
+                            ));
+                        }
                         let appstream_cache = backend.info_cache();
-                        let mut backend_results = appstream_cache
+                        let mut results: Vec<SearchResult> = appstream_cache
                             .infos
                             .par_iter()
                             .filter_map(|(id, info)| {
+                                if !foreign_architectures && !info.arch_supported() {
+                                    return None;
+                                }
+                                if let Some(category) = category_opt {
+                                    //TODO: contains doesn't work due to type mismatch
+                                    if !info.categories.iter().any(|x| x == category) {
+                                        return None;
+                                    }
+                                }
                                 //TODO: fuzzy match (nucleus-matcher?)
-                                let weight_opt = match regex.find(&info.name) {
-                                    Some(mat) => {
-                                        if mat.range().start == 0 {
-                                            if mat.range().end == info.name.len() {
-                                                // Name equals search phrase
-                                                Some(0)
-                                            } else {
-                                                // Name starts with search phrase
-                                                Some(1)
-                                            }
-                                        } else {
-                                            // Name contains search phrase
-                                            Some(2)
-                                        }
+                                let weight = if let Some(mime) = &mime_opt {
+                                    if !mime_matches(mime, info) {
+                                        return None;
                                     }
-                                    None => match regex.find(&info.summary) {
-                                        Some(mat) => {
-                                            if mat.range().start == 0 {
-                                                if mat.range().end == info.summary.len() {
-                                                    // Summary equals search phrase
-                                                    Some(3)
-                                                } else {
-                                                    // Summary starts with search phrase
-                                                    Some(4)
-                                                }
-                                            } else {
-                                                // Summary contains search phrase
-                                                Some(5)
-                                            }
-                                        }
-                                        None => match regex.find(&info.description) {
-                                            Some(mat) => {
-                                                if mat.range().start == 0 {
-                                                    if mat.range().end == info.summary.len() {
-                                                        // Description equals search phrase
-                                                        Some(6)
-                                                    } else {
-                                                        // Description starts with search phrase
-                                                        Some(7)
-                                                    }
-                                                } else {
-                                                    // Description contains search phrase
-                                                    Some(8)
-                                                }
-                                            }
-                                            None => None,
-                                        },
-                                    },
-                                };
-                                if let Some(weight) = weight_opt {
-                                    Some(SearchResult {
-                                        backend_name,
-                                        id: id.clone(),
-                                        icon: appstream_cache.icon(info),
-                                        info: info.clone(),
-                                        weight,
-                                    })
+                                    0
+                                } else if let Some(developer) = &developer_opt {
+                                    if !appstream_cache.developer_matches(developer, id) {
+                                        return None;
+                                    }
+                                    0
                                 } else {
-                                    None
-                                }
+                                    let description_opt = descriptions_opt
+                                        .as_ref()
+                                        .and_then(|descriptions| descriptions.get(id))
+                                        .map(|description| description.as_str());
+                                    search_weight(&regex, &input_folded, id, info, description_opt)?
+                                };
+                                let (name_match_opt, summary_match_opt) =
+                                    match mime_opt.as_ref().or(developer_opt.as_ref()) {
+                                        Some(_) => (None, None),
+                                        None => (
+                                            search_match_range(&regex, &info.name),
+                                            search_match_range(&regex, &info.summary),
+                                        ),
+                                    };
+                                Some(SearchResult {
+                                    backend_name,
+                                    id: id.clone(),
+                                    icon: appstream_cache.icon(id, info),
+                                    info: info.clone(),
+                                    weight,
+                                    name_match_opt,
+                                    summary_match_opt,
+                                })
                             })
                             .collect();
-                        results.append(&mut backend_results);
-                    }
-                    results.sort_by(|a, b| match a.weight.cmp(&b.weight) {
-                        cmp::Ordering::Equal => {
-                            lexical_sort::natural_lexical_cmp(&a.info.name, &b.info.name)
-                        }
-                        ordering => ordering,
-                    });
-                    let duration = start.elapsed();
-                    log::info!("searched for {:?} in {:?}", input, duration);
-                    message::app(Message::SearchResults(input, results))
-                })
-                .await
-                .unwrap_or(message::none())
-            },
-            |x| x,
-        )
+                        SearchSort::Relevance.sort(&mut results);
+                        let duration = start.elapsed();
+                        log::info!(
+                            "searched {} for {:?} in category {:?} in {:?}",
+                            backend_name,
+                            input,
+                            category_opt,
+                            duration
+                        );
+                        let suggestion_opt = if results.is_empty()
+                            && mime_opt.is_none()
+                            && developer_opt.is_none()
+                        {
+                            search_suggestion(appstream_cache, &input_folded)
+                        } else {
+                            None
+                        };
+                        message::app(Message::SearchResults(
+                            input,
+                            backend_name,
+                            results,
+                            suggestion_opt,
+                        ))
+                    })
+                    .await
+                    .unwrap_or(message::none())
+                },
+                |x| x,
+            ));
+        }
+        Command::batch(commands)
     }
 
-    fn update_backends(&self) -> Command<Message> {
+    fn update_backends(&mut self) -> Command<Message> {
+        self.busy.insert(BusyTask::Backends);
         let locale = self.locale.clone();
         Command::perform(
             async move {
                 tokio::task::spawn_blocking(move || {
                     let start = Instant::now();
-                    let backends = backend::backends(&locale);
+                    let (backends, errors) = backend::backends(&locale);
                     let duration = start.elapsed();
                     log::info!("loaded backends in {:?}", duration);
-                    message::app(Message::Backends(backends))
+                    message::app(Message::Backends(backends, errors))
                 })
                 .await
                 .unwrap_or(message::none())
@@ -610,27 +2148,421 @@ impl App {
         )
     }
 
+    /// Load descriptions of backends that do not have them loaded yet, if description search is
+    /// enabled
+    fn update_search_descriptions(&self) -> Command<Message> {
+        if !self.search_filter.description {
+            return Command::none();
+        }
+        let mut commands = Vec::with_capacity(self.backends.len());
+        for (backend_name, backend) in self.backends.iter() {
+            if self.search_descriptions.contains_key(backend_name) {
+                continue;
+            }
+            let backend_name = *backend_name;
+            let backend = backend.clone();
+            commands.push(Command::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        let descriptions = backend.descriptions();
+                        message::app(Message::SearchDescriptions(
+                            backend_name,
+                            Arc::new(descriptions),
+                        ))
+                    })
+                    .await
+                    .unwrap_or(message::none())
+                },
+                |x| x,
+            ));
+        }
+        Command::batch(commands)
+    }
+
     fn update_config(&mut self) -> Command<Message> {
-        cosmic::app::command::set_theme(self.config.app_theme.theme())
+        cosmic::app::command::set_theme(self.sync_config.app_theme.theme())
+    }
+
+    fn update_cache_sizes(&self) -> Command<Message> {
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let cache_sizes = CacheKind::all()
+                        .iter()
+                        .map(|kind| (*kind, kind.size()))
+                        .collect();
+                    message::app(Message::CacheSizes(cache_sizes))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
     }
 
-    fn update_installed(&self) -> Command<Message> {
+    fn update_repos(&self) -> Command<Message> {
         let backends = self.backends.clone();
+        let backend_names = self.backend_names();
         Command::perform(
             async move {
                 tokio::task::spawn_blocking(move || {
-                    let mut installed = Vec::new();
-                    //TODO: par_iter?
-                    for (backend_name, backend) in backends.iter() {
-                        let start = Instant::now();
-                        match backend.installed() {
-                            Ok(packages) => {
-                                for package in packages {
+                    let mut repos = Vec::new();
+                    for backend_name in backend_names {
+                        match backends[backend_name].repos() {
+                            Ok(backend_repos) => {
+                                for repo in backend_repos {
+                                    repos.push((backend_name, repo));
+                                }
+                            }
+                            Err(err) => {
+                                log::warn!(
+                                    "failed to list repositories of {}: {}",
+                                    backend_name,
+                                    err
+                                );
+                            }
+                        }
+                    }
+                    message::app(Message::Repos(repos))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
+    /// Find repositories that differ from imported settings, which are changed after confirmation
+    fn import_repos(&self, repos: Vec<SettingsRepo>) -> Command<Message> {
+        let backends = self.backends.clone();
+        let backend_names = self.backend_names();
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut changes = Vec::new();
+                    for backend_name in backend_names {
+                        let imported: Vec<_> = repos
+                            .iter()
+                            .filter(|repo| repo.backend == backend_name)
+                            .collect();
+                        if imported.is_empty() {
+                            continue;
+                        }
+                        let backend_repos = match backends[backend_name].repos() {
+                            Ok(ok) => ok,
+                            Err(err) => {
+                                log::warn!(
+                                    "failed to list repositories of {}: {}",
+                                    backend_name,
+                                    err
+                                );
+                                continue;
+                            }
+                        };
+                        for repo in imported {
+                            // Repositories that are not known on this system are skipped
+                            let Some(backend_repo) = backend_repos.iter().find(|x| x.id == repo.id)
+                            else {
+                                continue;
+                            };
+                            if backend_repo.enabled == repo.enabled {
+                                continue;
+                            }
+                            changes.push((
+                                backend_name,
+                                Repo {
+                                    enabled: repo.enabled,
+                                    ..backend_repo.clone()
+                                },
+                            ));
+                        }
+                    }
+                    if changes.is_empty() {
+                        message::none()
+                    } else {
+                        message::app(Message::SyncRepos(changes))
+                    }
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
+    fn update_storage(&self) -> Command<Message> {
+        let backends = self.backends.clone();
+        let backend_names = self.backend_names();
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut storage = Vec::with_capacity(backend_names.len());
+                    for backend_name in backend_names {
+                        match backends[backend_name].storage_usage() {
+                            Ok(Some(usage)) => storage.push((backend_name, usage)),
+                            Ok(None) => {}
+                            Err(err) => {
+                                log::warn!(
+                                    "failed to get storage usage of {}: {}",
+                                    backend_name,
+                                    err
+                                );
+                            }
+                        }
+                    }
+                    message::app(Message::Storage(storage))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
+    fn update_ratings(&self) -> Command<Message> {
+        if self.config.reviews_server.is_none() {
+            return Command::none();
+        }
+        Command::perform(
+            async move {
+                match odrs::ratings().await {
+                    Ok(ratings) => message::app(Message::Ratings(ratings)),
+                    Err(err) => {
+                        log::warn!("failed to load ratings: {}", err);
+                        let offline = err
+                            .downcast_ref::<reqwest::Error>()
+                            .map_or(false, |err| err.is_connect() || err.is_timeout());
+                        if offline {
+                            message::app(Message::Offline)
+                        } else {
+                            message::none()
+                        }
+                    }
+                }
+            },
+            |x| x,
+        )
+    }
+
+    /// Ratings use either the component id or the legacy desktop file id
+    fn rating(&self, id: &str) -> Option<&OdrsRating> {
+        self.ratings
+            .get(id)
+            .or_else(|| self.ratings.get(&format!("{}.desktop", id)))
+    }
+
+    fn update_featured(&self) -> Command<Message> {
+        let backends = self.backends.clone();
+        let preferred_format = self.config.preferred_format;
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let backend_names = sorted_backend_names(&backends, preferred_format);
+                    let mut featured = Vec::with_capacity(FEATURED_IDS.len());
+                    for (weight, id) in FEATURED_IDS.iter().enumerate() {
+                        // Use the first backend that provides the app
+                        for backend_name in backend_names.iter() {
+                            let appstream_cache = backends[backend_name].info_cache();
+                            if let Some(info) = appstream_cache
+                                .infos
+                                .get(*id)
+                                .filter(|info| info.arch_supported())
+                            {
+                                featured.push(SearchResult {
+                                    backend_name,
+                                    id: id.to_string(),
+                                    icon: appstream_cache.icon(id, info),
+                                    info: info.clone(),
+                                    weight,
+                                    name_match_opt: None,
+                                    summary_match_opt: None,
+                                });
+                                break;
+                            }
+                        }
+                    }
+                    message::app(Message::Featured(featured))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
+    fn update_popular(&self) -> Command<Message> {
+        if self.backends.is_empty() || self.ratings.is_empty() {
+            return Command::none();
+        }
+        let backends = self.backends.clone();
+        let ratings = self.ratings.clone();
+        let preferred_format = self.config.preferred_format;
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let start = Instant::now();
+                    let backend_names = sorted_backend_names(&backends, preferred_format);
+                    let mut ids = HashSet::new();
+                    let mut popular = Vec::new();
+                    for backend_name in backend_names {
+                        let appstream_cache = backends[backend_name].info_cache();
+                        for (id, info) in appstream_cache.infos.iter() {
+                            if info.kind != AppKind::DesktopApplication || !info.arch_supported() {
+                                continue;
+                            }
+                            // Ratings use either the component id or the legacy desktop file id
+                            let Some(rating) = ratings
+                                .get(id)
+                                .or_else(|| ratings.get(&format!("{}.desktop", id)))
+                            else {
+                                continue;
+                            };
+                            // Use the first backend that provides the app
+                            if !ids.insert(id.clone()) {
+                                continue;
+                            }
+                            popular.push((
+                                rating.total,
+                                SearchResult {
+                                    backend_name,
+                                    id: id.clone(),
+                                    icon: appstream_cache.icon(id, info),
+                                    info: info.clone(),
+                                    weight: 0,
+                                    name_match_opt: None,
+                                    summary_match_opt: None,
+                                },
+                            ));
+                        }
+                    }
+                    popular.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.id.cmp(&b.1.id)));
+                    let popular: Vec<_> = popular
+                        .into_iter()
+                        .take(POPULAR_LIMIT)
+                        .map(|(_total, result)| result)
+                        .collect();
+                    let duration = start.elapsed();
+                    log::info!("found popular apps in {:?}", duration);
+                    message::app(Message::Popular(popular))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
+    /// Find favorites in the loaded backends, using the first backend that provides each app
+    fn update_favorites(&mut self) {
+        let mut favorites = Vec::with_capacity(self.sync_config.favorites.len());
+        for id in self.sync_config.favorites.iter() {
+            for backend_name in self.backend_names() {
+                let appstream_cache = self.backends[backend_name].info_cache();
+                if let Some(info) = appstream_cache.infos.get(id) {
+                    favorites.push(SearchResult {
+                        backend_name,
+                        id: id.clone(),
+                        icon: appstream_cache.icon(id, info),
+                        info: info.clone(),
+                        weight: 0,
+                        name_match_opt: None,
+                        summary_match_opt: None,
+                    });
+                    break;
+                }
+            }
+        }
+        self.favorites = favorites;
+    }
+
+    fn update_recent(&self) -> Command<Message> {
+        let backends = self.backends.clone();
+        let preferred_format = self.config.preferred_format;
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let start = Instant::now();
+                    // Ignore release dates in the future
+                    let now = chrono::Utc::now().timestamp();
+                    let backend_names = sorted_backend_names(&backends, preferred_format);
+                    let mut ids = HashSet::new();
+                    let mut added = Vec::new();
+                    let mut updated = Vec::new();
+                    for backend_name in backend_names {
+                        let appstream_cache = backends[backend_name].info_cache();
+                        for (id, info) in appstream_cache.infos.iter() {
+                            if info.kind != AppKind::DesktopApplication || !info.arch_supported() {
+                                continue;
+                            }
+                            // Use the first backend that provides the app
+                            if !ids.insert(id.clone()) {
+                                continue;
+                            }
+                            let result = SearchResult {
+                                backend_name,
+                                id: id.clone(),
+                                icon: appstream_cache.icon(id, info),
+                                info: info.clone(),
+                                weight: 0,
+                                name_match_opt: None,
+                                summary_match_opt: None,
+                            };
+                            if let Some(timestamp) =
+                                info.added_opt().filter(|timestamp| *timestamp <= now)
+                            {
+                                added.push((timestamp, result.clone()));
+                            }
+                            if let Some(timestamp) =
+                                info.updated_opt().filter(|timestamp| *timestamp <= now)
+                            {
+                                updated.push((timestamp, result));
+                            }
+                        }
+                    }
+                    let newest = |mut results: Vec<(i64, SearchResult)>| -> Vec<SearchResult> {
+                        results.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.id.cmp(&b.1.id)));
+                        results
+                            .into_iter()
+                            .take(RECENT_LIMIT)
+                            .map(|(_timestamp, result)| result)
+                            .collect()
+                    };
+                    let added = newest(added);
+                    let updated = newest(updated);
+                    let duration = start.elapsed();
+                    log::info!("found recent apps in {:?}", duration);
+                    message::app(Message::Recent(added, updated))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
+    fn update_installed(&mut self) -> Command<Message> {
+        self.busy.insert(BusyTask::Installed);
+        let backends = self.backends.clone();
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut installed = Vec::new();
+                    let mut errors = Vec::new();
+                    //TODO: par_iter?
+                    for (backend_name, backend) in backends.iter() {
+                        let start = Instant::now();
+                        match backend.installed() {
+                            Ok(packages) => {
+                                for package in packages {
                                     installed.push((*backend_name, package));
                                 }
                             }
                             Err(err) => {
                                 log::error!("failed to list installed: {}", err);
+                                errors.push(format!(
+                                    "failed to list installed from {}: {}",
+                                    backend_name, err
+                                ));
                             }
                         }
                         let duration = start.elapsed();
@@ -645,7 +2577,7 @@ impl App {
                             lexical_sort::natural_lexical_cmp(&a.1.info.name, &b.1.info.name)
                         }
                     });
-                    message::app(Message::Installed(installed))
+                    message::app(Message::Installed(installed, errors))
                 })
                 .await
                 .unwrap_or(message::none())
@@ -654,7 +2586,8 @@ impl App {
         )
     }
 
-    fn update_updates(&self) -> Command<Message> {
+    fn update_updates(&mut self) -> Command<Message> {
+        self.busy.insert(BusyTask::Updates);
         let backends = self.backends.clone();
         Command::perform(
             async move {
@@ -694,33 +2627,2069 @@ impl App {
         )
     }
 
-    fn update_title(&mut self) -> Command<Message> {
-        self.set_window_title(fl!("cosmic-app-store"))
+    fn update_title(&mut self) -> Command<Message> {
+        self.set_window_title(fl!("cosmic-app-store"))
+    }
+
+    /// Show the app a URI or command line argument refers to
+    fn open_target(&mut self, target: AppTarget) -> Command<Message> {
+        match target {
+            AppTarget::Id(id) => self.update(Message::SelectAppId(None, id)),
+            AppTarget::Package(pkgname) => {
+                let found_opt = self.backend_names().into_iter().find_map(|backend_name| {
+                    let appstream_cache = self.backends[backend_name].info_cache();
+                    let id = appstream_cache.pkgnames.get(&pkgname)?.iter().min()?;
+                    Some((backend_name, id.clone()))
+                });
+                match found_opt {
+                    Some((backend_name, id)) => {
+                        self.update(Message::SelectAppId(Some(backend_name), id))
+                    }
+                    None => {
+                        log::warn!("failed to find app for package {:?}", pkgname);
+                        self.update(Message::SearchRun(pkgname))
+                    }
+                }
+            }
+            AppTarget::Snap(name) => self.update(Message::SearchRun(name)),
+            AppTarget::Search(input) => self.update(Message::SearchRun(input)),
+            // Exported app lists can be opened to restore them
+            AppTarget::File(path) if path.extension().map_or(false, |x| x == "json") => {
+                self.update(Message::AppListImport(path))
+            }
+            AppTarget::File(path) => {
+                self.dialog_pages.push_back(DialogPage::InstallFile(path));
+                Command::none()
+            }
+        }
+    }
+
+    /// Names of loaded backends in a stable order
+    fn backend_names(&self) -> Vec<&'static str> {
+        sorted_backend_names(&self.backends, self.config.preferred_format)
+    }
+
+    /// Returns the new version if the package has an update available
+    fn update_version_opt(&self, backend_name: &str, id: &str) -> Option<&str> {
+        self.updates
+            .as_ref()?
+            .iter()
+            .find(|(update_backend_name, package)| {
+                *update_backend_name == backend_name && package.id == id
+            })
+            .map(|(_, package)| package.version.as_str())
+    }
+
+    fn is_installed(&self, backend_name: &'static str, id: &str) -> bool {
+        //TODO: more efficient checks
+        match &self.installed {
+            Some(installed) => installed.iter().any(|(other_backend_name, package)| {
+                *other_backend_name == backend_name && package.id == id
+            }),
+            None => false,
+        }
+    }
+
+    /// Returns true if the app is hidden by free software only mode
+    fn proprietary_hidden(&self, info: &AppInfo) -> bool {
+        self.config.free_software_only
+            && !self.proprietary_shown
+            && info.license_free_opt() == Some(false)
+    }
+
+    /// Returns true if the app is rated for older ages than the age rating limit, apps without
+    /// a content rating are shown
+    fn age_hidden(&self, info: &AppInfo) -> bool {
+        match (self.config.max_content_age, info.content_age_opt()) {
+            (Some(max_content_age), Some(age)) => age > max_content_age,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the app is hidden from browse and search results
+    fn result_hidden(&self, info: &AppInfo) -> bool {
+        self.proprietary_hidden(info) || self.age_hidden(info)
+    }
+
+    /// Button to show proprietary apps in the current view, when they are hidden store-wide
+    fn proprietary_button(&self) -> Option<Element<Message>> {
+        if !self.config.free_software_only {
+            return None;
+        }
+        Some(
+            widget::button::standard(if self.proprietary_shown {
+                fl!("hide-proprietary")
+            } else {
+                fl!("show-proprietary")
+            })
+            .on_press(Message::ProprietaryShown(!self.proprietary_shown))
+            .into(),
+        )
+    }
+
+    fn search_filter_matches(&self, result: &SearchResult) -> bool {
+        let search_filter = &self.search_filter;
+        if self.result_hidden(&result.info) {
+            return false;
+        }
+        if let Some(backend_name) = search_filter.backend_opt {
+            if result.backend_name != backend_name {
+                return false;
+            }
+        }
+        if let Some(category) = search_filter.category_opt {
+            //TODO: contains doesn't work due to type mismatch
+            if !result.info.categories.iter().any(|x| x == category) {
+                return false;
+            }
+        }
+        if !search_filter.system_components && result.info.is_system_component() {
+            return false;
+        }
+        if search_filter.free_only && !result.info.license_is_free() {
+            return false;
+        }
+        if search_filter.installed_only && !self.is_installed(result.backend_name, &result.id) {
+            return false;
+        }
+        true
+    }
+
+    /// Search results shown with the current filters and their indexes, apps from more than one
+    /// backend are shown once after filtering so the source filter can pick any of them
+    fn search_results_shown<'a>(
+        &self,
+        results: &'a [SearchResult],
+    ) -> Vec<(usize, &'a SearchResult)> {
+        let mut shown: Vec<_> = results
+            .iter()
+            .enumerate()
+            .filter(|(_result_i, result)| self.search_filter_matches(result))
+            .collect();
+        dedup_results_by(
+            &mut shown,
+            self.config.preferred_format,
+            |(_result_i, result)| *result,
+        );
+        shown
+    }
+
+    fn search_history_view(&self) -> Element<Message> {
+        let cosmic_theme::Spacing {
+            space_xl,
+            space_xxs,
+            ..
+        } = self.sync_config.density.spacing();
+
+        let mut column = widget::column::with_capacity(self.config.search_history.len() + 1)
+            .padding([0, space_xl])
+            .spacing(space_xxs)
+            .width(Length::Fill);
+        column = column.push(
+            widget::row::with_children(vec![
+                widget::text::heading(fl!("recent-searches"))
+                    .width(Length::Fill)
+                    .into(),
+                widget::button::text(fl!("clear-history"))
+                    .on_press(Message::SearchHistoryClear)
+                    .into(),
+            ])
+            .align_items(Alignment::Center),
+        );
+        for (history_i, input) in self.config.search_history.iter().enumerate() {
+            column = column.push(
+                widget::button(
+                    widget::row::with_children(vec![
+                        widget::icon::from_name("document-open-recent-symbolic")
+                            .size(16)
+                            .into(),
+                        widget::text(input).into(),
+                    ])
+                    .align_items(Alignment::Center)
+                    .spacing(space_xxs),
+                )
+                .style(if self.search_history_selected == Some(history_i) {
+                    theme::Button::Suggested
+                } else {
+                    theme::Button::MenuItem
+                })
+                .width(Length::Fill)
+                .on_press(Message::SearchHistory(history_i)),
+            );
+        }
+        self.page_scrollable(column)
+    }
+
+    fn page_scrollable<'a>(
+        &self,
+        content: impl Into<Element<'a, Message>>,
+    ) -> Element<'a, Message> {
+        widget::scrollable(content)
+            .id(self.scroll_id.clone())
+            .on_scroll(|viewport| Message::Scroll(viewport.absolute_offset()))
+            .into()
+    }
+
+    /// Find all backends that provide the selected app, so the user can choose where to install from
+    fn update_selected_sources(&mut self) {
+        let Some(selected) = &mut self.selected_opt else {
+            return;
+        };
+        let backend_names = sorted_backend_names(&self.backends, self.config.preferred_format);
+        let mut sources = Vec::with_capacity(backend_names.len());
+        let mut source_names = Vec::with_capacity(backend_names.len());
+        for backend_name in backend_names {
+            let appstream_cache = self.backends[backend_name].info_cache();
+            let Some(info) = appstream_cache.infos.get(&selected.id) else {
+                continue;
+            };
+            source_names.push(match &info.origin_opt {
+                Some(origin) => format!("{} ({})", backend_name, origin),
+                None => backend_name.to_string(),
+            });
+            sources.push(SelectedSource {
+                backend_name,
+                icon: appstream_cache.icon(&selected.id, info),
+                info: info.clone(),
+                trust: self.backends[backend_name].source_trust(info.origin_opt.as_deref()),
+            });
+        }
+        selected.sources = sources;
+        selected.source_names = source_names;
+    }
+
+    fn source_view<'a>(
+        &self,
+        selected: &'a Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Option<Element<'a, Message>> {
+        let source_i = selected
+            .sources
+            .iter()
+            .position(|source| source.backend_name == selected.backend_name)?;
+        let source = &selected.sources[source_i];
+        let source_name = &selected.source_names[source_i];
+
+        let mut details = Vec::with_capacity(4);
+        if let Some(release) = source.info.latest_release_opt() {
+            details.push(fl!("release-version", version = release.version.as_str()));
+        }
+        if let Some(size) = source.info.size_opt {
+            details.push(format_size(size));
+        }
+        let architectures = source.info.architectures();
+        if !architectures.is_empty() {
+            details.push(architectures.join(", "));
+        }
+        details.push(if source.info.sandboxed() {
+            fl!("permissions-sandboxed")
+        } else {
+            fl!("permissions-unrestricted")
+        });
+        match &source.trust {
+            SourceTrust::Signed(Some(name)) => {
+                details.push(fl!("source-signed-by", name = name.as_str()));
+            }
+            SourceTrust::Signed(None) => details.push(fl!("source-signed")),
+            SourceTrust::Unsigned => details.push(fl!("source-unsigned")),
+            SourceTrust::Unknown => {}
+        }
+
+        let mut row = widget::row::with_capacity(3)
+            .align_items(Alignment::Center)
+            .spacing(spacing.space_xs)
+            .push(widget::text::body(fl!("source")));
+        if selected.sources.len() > 1 {
+            row = row.push(widget::dropdown(
+                &selected.source_names,
+                Some(source_i),
+                Message::SelectedSource,
+            ));
+        } else {
+            row = row.push(widget::text::body(source_name));
+        }
+        row = row.push(widget::text::caption(details.join(" · ")));
+        if source.trust == SourceTrust::Unsigned {
+            return Some(
+                widget::column::with_children(vec![
+                    row.into(),
+                    widget::text::body(fl!("source-unsigned-warning")).into(),
+                ])
+                .spacing(spacing.space_xxs)
+                .into(),
+            );
+        }
+        Some(row.into())
+    }
+
+    /// Replace error banners for an operation with new errors, which clears them on success
+    fn error_banners_set(&mut self, retry: ErrorRetry, errors: Vec<String>) {
+        self.error_banners.retain(|(x, _)| *x != retry);
+        for error in errors {
+            self.error_banners.push((retry, error));
+        }
+    }
+
+    fn error_banners_view(&self, spacing: &cosmic_theme::Spacing) -> Option<Element<Message>> {
+        if self.error_banners.is_empty() {
+            return None;
+        }
+        let mut column = widget::column::with_capacity(self.error_banners.len())
+            .padding([0, spacing.space_xl])
+            .spacing(spacing.space_xxs);
+        for (error_i, (_retry, error)) in self.error_banners.iter().enumerate() {
+            column = column.push(
+                widget::container(
+                    widget::row::with_children(vec![
+                        widget::icon::from_name("dialog-error-symbolic")
+                            .size(16)
+                            .into(),
+                        widget::text::body(error).width(Length::Fill).into(),
+                        widget::button::standard(fl!("retry"))
+                            .on_press(Message::ErrorRetry(error_i))
+                            .into(),
+                        widget::button::icon(widget::icon::from_name("window-close-symbolic"))
+                            .on_press(Message::ErrorDismiss(error_i))
+                            .into(),
+                    ])
+                    .align_items(Alignment::Center)
+                    .spacing(spacing.space_s),
+                )
+                .padding([spacing.space_xxs, spacing.space_s])
+                .style(theme::Container::Card),
+            );
+        }
+        Some(column.into())
+    }
+
+    fn nav_state(&self) -> NavState {
+        NavState {
+            nav_id: self.nav_model.active(),
+            category_results: self.category_results.clone(),
+            developer_results: self.developer_results.clone(),
+            search_active: self.search_active,
+            search_input: self.search_input.clone(),
+            search_results: self.search_results.clone(),
+            selected_opt: self.selected_opt.clone(),
+            scroll_offset: self.scroll_offset,
+        }
+    }
+
+    /// Save the visible page before navigating to a new one
+    fn nav_push(&mut self) {
+        let state = self.nav_state();
+        self.nav_back.push(state);
+        if self.nav_back.len() > NAV_HISTORY_LEN {
+            self.nav_back.remove(0);
+        }
+        self.nav_forward.clear();
+        self.scroll_offset = AbsoluteOffset::default();
+    }
+
+    fn nav_restore(&mut self, state: NavState) -> Command<Message> {
+        self.nav_model.activate(state.nav_id);
+        self.category_results = state.category_results;
+        self.developer_results = state.developer_results;
+        self.search_active = state.search_active;
+        self.search_input = state.search_input;
+        self.search_results = state.search_results;
+        self.selected_opt = state.selected_opt;
+        self.scroll_offset = state.scroll_offset;
+        scrollable::scroll_to(self.scroll_id.clone(), self.scroll_offset)
+    }
+
+    fn nav_scroll_top(&self) -> Command<Message> {
+        scrollable::scroll_to(self.scroll_id.clone(), AbsoluteOffset::default())
+    }
+
+    /// Placeholder cards shown while results are loading, so the window does not look frozen
+    fn skeleton_view<'a>(&self, spacing: &cosmic_theme::Spacing) -> Element<'a, Message> {
+        let (width, height) = match self.view_layout() {
+            ViewLayout::Grid => (
+                Length::Fixed(180.0 + (spacing.space_s as f32) * 2.0),
+                Length::Fixed(
+                    self.sync_config.density.icon_size(ICON_SIZE_GRID) as f32
+                        + 60.0
+                        + (spacing.space_xxs as f32) * 3.0
+                        + (spacing.space_s as f32) * 2.0,
+                ),
+            ),
+            ViewLayout::List => (
+                Length::Fill,
+                Length::Fixed(62.0 + (spacing.space_xxs as f32) * 2.0),
+            ),
+        };
+        let mut children = Vec::with_capacity(SKELETON_COUNT);
+        for _ in 0..SKELETON_COUNT {
+            children.push(
+                widget::container(widget::Space::new(width, height))
+                    .style(theme::Container::Card)
+                    .into(),
+            );
+        }
+        self.results_layout(children, spacing)
+    }
+
+    fn loading_view<'a>(&self, spacing: &cosmic_theme::Spacing) -> Element<'a, Message> {
+        widget::column::with_children(vec![
+            widget::text::caption(fl!("loading")).into(),
+            self.skeleton_view(spacing),
+        ])
+        .padding([0, spacing.space_xl])
+        .spacing(spacing.space_xxs)
+        .width(Length::Fill)
+        .into()
+    }
+
+    fn narrow(&self) -> bool {
+        self.window_width < NARROW_MAX_WIDTH
+    }
+
+    /// Configured view layout, falling back to a list on narrow windows
+    fn view_layout(&self) -> ViewLayout {
+        if self.narrow() {
+            ViewLayout::List
+        } else {
+            self.sync_config.view_layout
+        }
+    }
+
+    fn view_layout_button(&self) -> Element<Message> {
+        let (icon_name, view_layout) = match self.sync_config.view_layout {
+            ViewLayout::Grid => ("view-list-symbolic", ViewLayout::List),
+            ViewLayout::List => ("view-grid-symbolic", ViewLayout::Grid),
+        };
+        widget::button::icon(widget::icon::from_name(icon_name).size(16))
+            .on_press(Message::ViewLayout(view_layout))
+            .into()
+    }
+
+    fn result_view<'a>(
+        &'a self,
+        result: &'a SearchResult,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Element<'a, Message> {
+        let installed = self.is_installed(result.backend_name, &result.id);
+        match self.view_layout() {
+            ViewLayout::Grid => result.grid_view(
+                self.rating(&result.id),
+                installed,
+                self.sync_config.density,
+                spacing,
+            ),
+            ViewLayout::List => result.card_view(
+                self.rating(&result.id),
+                installed,
+                self.sync_config.density,
+                self.narrow(),
+                spacing,
+            ),
+        }
+    }
+
+    fn results_layout<'a>(
+        &self,
+        children: Vec<Element<'a, Message>>,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Element<'a, Message> {
+        match self.view_layout() {
+            ViewLayout::Grid => widget::flex_row(children)
+                .column_spacing(spacing.space_xxs)
+                .row_spacing(spacing.space_xxs)
+                .into(),
+            ViewLayout::List => widget::column::with_children(children)
+                .spacing(spacing.space_xxs)
+                .into(),
+        }
+    }
+
+    fn search_sort_dropdown(&self) -> Element<Message> {
+        widget::dropdown(
+            &self.search_sort_names,
+            SearchSort::all()
+                .iter()
+                .position(|search_sort| *search_sort == self.search_sort),
+            |index| {
+                Message::SearchSort(
+                    SearchSort::all()
+                        .get(index)
+                        .copied()
+                        .unwrap_or(SearchSort::Relevance),
+                )
+            },
+        )
+        .into()
+    }
+
+    fn lightbox_view<'a>(
+        &self,
+        selected: &'a Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Element<'a, Message> {
+        let close_button = widget::button::icon(widget::icon::from_name("window-close-symbolic"))
+            .on_press(Message::SelectedLightbox(false));
+        let Some((screenshots, screenshot_shown)) = selected_screenshots(selected) else {
+            return close_button.into();
+        };
+        let (screenshot_i, screenshot) = screenshots[screenshot_shown];
+
+        let mut prev_button =
+            widget::button::icon(widget::icon::from_name("go-previous-symbolic").size(16));
+        if screenshot_shown > 0 {
+            prev_button =
+                prev_button.on_press(Message::SelectedScreenshotShown(screenshot_shown - 1));
+        }
+        let mut next_button =
+            widget::button::icon(widget::icon::from_name("go-next-symbolic").size(16));
+        if screenshot_shown + 1 < screenshots.len() {
+            next_button =
+                next_button.on_press(Message::SelectedScreenshotShown(screenshot_shown + 1));
+        }
+
+        let image_element: Element<_> = match selected.screenshot_images.get(&screenshot_i) {
+            // The viewer supports zooming with the scroll wheel and panning by dragging
+            Some(image) => cosmic::iced::widget::image::viewer(image.clone())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into(),
+            None => widget::container(widget::text::caption(fl!("loading")))
+                .center_x()
+                .center_y()
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into(),
+        };
+
+        widget::column::with_children(vec![
+            widget::row::with_children(vec![
+                widget::text::heading(&screenshot.caption)
+                    .width(Length::Fill)
+                    .into(),
+                widget::text::body(format!("{} / {}", screenshot_shown + 1, screenshots.len()))
+                    .into(),
+                close_button.into(),
+            ])
+            .align_items(Alignment::Center)
+            .spacing(spacing.space_s)
+            .into(),
+            widget::row::with_children(vec![prev_button.into(), image_element, next_button.into()])
+                .align_items(Alignment::Center)
+                .height(Length::Fill)
+                .into(),
+        ])
+        .padding(spacing.space_s)
+        .spacing(spacing.space_xxs)
+        .into()
+    }
+
+    fn screenshots_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Option<Element<Message>> {
+        let (screenshots, screenshot_shown) = selected_screenshots(selected)?;
+        let (screenshot_i, screenshot) = screenshots[screenshot_shown];
+        //TODO: get proper image dimensions
+        let image_height = Length::Fixed(480.0);
+        let mut row = widget::row::with_capacity(3).align_items(Alignment::Center);
+        {
+            let mut button =
+                widget::button::icon(widget::icon::from_name("go-previous-symbolic").size(16));
+            if screenshot_shown > 0 {
+                button = button.on_press(Message::SelectedScreenshotShown(screenshot_shown - 1));
+            }
+            row = row.push(button);
+        }
+        let image_element = if let Some(image) = selected.screenshot_images.get(&screenshot_i) {
+            widget::mouse_area(
+                widget::image(image.clone())
+                    .width(Length::Fill)
+                    .height(image_height),
+            )
+            .on_press(Message::SelectedLightbox(true))
+            .into()
+        } else {
+            widget::container(widget::text::caption(fl!("loading")))
+                .center_x()
+                .center_y()
+                .width(Length::Fill)
+                .height(image_height)
+                .into()
+        };
+        let mut indicators = Vec::with_capacity(screenshots.len());
+        if screenshots.len() > 1 {
+            for screenshot_i in 0..screenshots.len() {
+                indicators.push(
+                    widget::button::icon(
+                        widget::icon::from_name(if screenshot_i == screenshot_shown {
+                            "radio-checked-symbolic"
+                        } else {
+                            "radio-symbolic"
+                        })
+                        .size(8),
+                    )
+                    .on_press(Message::SelectedScreenshotShown(screenshot_i))
+                    .into(),
+                );
+            }
+        }
+        row = row.push(
+            widget::column::with_children(vec![
+                image_element,
+                widget::text::caption(&screenshot.caption).into(),
+                widget::row::with_children(indicators).into(),
+            ])
+            .align_items(Alignment::Center)
+            .spacing(spacing.space_xxs),
+        );
+        {
+            let mut button =
+                widget::button::icon(widget::icon::from_name("go-next-symbolic").size(16));
+            if screenshot_shown + 1 < screenshots.len() {
+                button = button.on_press(Message::SelectedScreenshotShown(screenshot_shown + 1));
+            }
+            row = row.push(button);
+        }
+        Some(row.into())
+    }
+
+    /// Videos are opened in the default video player
+    fn videos_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Option<Element<Message>> {
+        if selected.info.videos.is_empty() {
+            return None;
+        }
+
+        let mut flex_row = Vec::with_capacity(selected.info.videos.len());
+        for video in selected.info.videos.iter() {
+            flex_row.push(
+                widget::button(
+                    widget::column::with_children(vec![
+                        widget::icon::from_name("media-playback-start-symbolic")
+                            .size(48)
+                            .into(),
+                        widget::text::body(if video.caption.is_empty() {
+                            fl!("play-video")
+                        } else {
+                            video.caption.clone()
+                        })
+                        .into(),
+                    ])
+                    .align_items(Alignment::Center)
+                    .spacing(spacing.space_xxs),
+                )
+                .padding(spacing.space_m)
+                .style(theme::Button::Standard)
+                .on_press(Message::OpenUrl(video.url.clone()))
+                .into(),
+            );
+        }
+        Some(
+            widget::column::with_children(vec![
+                widget::text::heading(fl!("videos")).into(),
+                widget::flex_row(flex_row)
+                    .column_spacing(spacing.space_xxs)
+                    .row_spacing(spacing.space_xxs)
+                    .into(),
+            ])
+            .spacing(spacing.space_xxs)
+            .into(),
+        )
+    }
+
+    fn permissions_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Element<Message> {
+        //TODO: show detailed permissions from flatpak metadata
+        let (icon_name, title, body) = if selected.info.sandboxed() {
+            (
+                "security-high-symbolic",
+                fl!("permissions-sandboxed"),
+                fl!("permissions-sandboxed-body"),
+            )
+        } else {
+            (
+                "dialog-warning-symbolic",
+                fl!("permissions-unrestricted"),
+                fl!("permissions-unrestricted-body"),
+            )
+        };
+        widget::row::with_children(vec![
+            widget::icon::from_name(icon_name).size(32).icon().into(),
+            widget::column::with_children(vec![
+                widget::text::heading(title).into(),
+                widget::text::body(body).into(),
+            ])
+            .spacing(spacing.space_xxxs)
+            .into(),
+        ])
+        .align_items(Alignment::Center)
+        .spacing(spacing.space_s)
+        .into()
+    }
+
+    fn urls_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Option<Element<Message>> {
+        let report_url_opt =
+            report::report_url(selected.backend_name, &selected.id, &selected.info);
+        if selected.info.urls.is_empty() && report_url_opt.is_none() {
+            return None;
+        }
+
+        let mut row = widget::row::with_capacity(selected.info.urls.len() + 1)
+            .align_items(Alignment::Center)
+            .spacing(spacing.space_xs);
+        // Donation links are shown first and more prominently
+        for url in selected.info.urls.iter() {
+            if let AppUrl::Donation(_) = url {
+                row = row.push(
+                    widget::button::suggested(fl!("url-donate"))
+                        .on_press(Message::OpenUrl(url.url().to_string())),
+                );
+            }
+        }
+        for url in selected.info.urls.iter() {
+            let title = match url {
+                // Replaced by the report link, which is prefilled when possible
+                AppUrl::BugTracker(_) if report_url_opt.is_some() => continue,
+                AppUrl::BugTracker(_) => fl!("url-bug-tracker"),
+                AppUrl::Contact(_) => fl!("url-contact"),
+                AppUrl::Donation(_) => continue,
+                AppUrl::Faq(_) => fl!("url-faq"),
+                AppUrl::Help(_) => fl!("url-help"),
+                AppUrl::Homepage(_) => fl!("url-homepage"),
+                AppUrl::Translate(_) => fl!("url-translate"),
+            };
+            row = row.push(
+                widget::button::standard(title).on_press(Message::OpenUrl(url.url().to_string())),
+            );
+        }
+        if let Some(report_url) = report_url_opt {
+            row = row.push(
+                widget::button::standard(fl!("report-problem"))
+                    .on_press(Message::OpenUrl(report_url)),
+            );
+        }
+        Some(row.into())
+    }
+
+    fn desktop_entry_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Option<Element<Message>> {
+        if !self.is_installed(selected.backend_name, &selected.id) {
+            return None;
+        }
+        //TODO: what if there are multiple desktop IDs?
+        let desktop_id = selected.info.desktop_ids.first()?;
+        let mut column = widget::column::with_capacity(4)
+            .spacing(spacing.space_xxs)
+            .push(widget::text::heading(fl!("launcher")));
+        match self.desktop_entries.get(desktop_id)? {
+            Ok(entry) => {
+                column = column
+                    .push(
+                        widget::row::with_children(vec![
+                            widget::text::body(fl!(
+                                "desktop-file",
+                                path = entry.path.display().to_string()
+                            ))
+                            .into(),
+                            widget::button::link(fl!("show-in-files"))
+                                .on_press(Message::ShowInFiles(entry.path.clone()))
+                                .padding(0)
+                                .into(),
+                        ])
+                        .align_items(Alignment::Center)
+                        .spacing(spacing.space_xs),
+                    )
+                    .push(widget::text::body(fl!(
+                        "launch-command",
+                        command = entry.exec.as_str()
+                    )))
+                    .push(widget::text::body(match &entry.wrapper_opt {
+                        Some(wrapper) => fl!("launch-wrapper", wrapper = wrapper.as_str()),
+                        None => fl!("launch-no-wrapper"),
+                    }));
+            }
+            Err(err) => {
+                column = column.push(widget::text::body(fl!(
+                    "desktop-file-missing",
+                    error = err.as_str()
+                )));
+            }
+        }
+        Some(column.into())
+    }
+
+    fn requirements_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Option<Element<Message>> {
+        if selected.info.relations.is_empty() {
+            return None;
+        }
+
+        let mut column = widget::column::with_capacity(selected.info.relations.len() + 1)
+            .spacing(spacing.space_xxs)
+            .push(widget::text::heading(fl!("requirements")));
+        for relation in selected.info.relations.iter() {
+            //TODO: check display length and controls
+            let (text, met_opt) = match &relation.requirement {
+                AppRequirement::Control(control) => {
+                    (fl!("requirement-control", control = control.as_str()), None)
+                }
+                AppRequirement::DisplayLength(compare, length) => (
+                    fl!(
+                        "requirement-display",
+                        length = format!("{} {}", compare.symbol(), length)
+                    ),
+                    None,
+                ),
+                AppRequirement::Memory(memory) => (
+                    fl!(
+                        "requirement-memory",
+                        memory = format_size(memory * 1024 * 1024)
+                    ),
+                    system_memory_mib().map(|system_memory| system_memory >= *memory),
+                ),
+            };
+            let mut row = widget::row::with_capacity(3)
+                .align_items(Alignment::Center)
+                .spacing(spacing.space_xs)
+                .push(widget::text::body(text))
+                .push(widget::text::caption(if relation.required {
+                    fl!("required")
+                } else {
+                    fl!("recommended")
+                }));
+            if met_opt == Some(false) {
+                row = row.push(
+                    widget::row::with_children(vec![
+                        widget::icon::from_name("dialog-warning-symbolic")
+                            .size(16)
+                            .into(),
+                        widget::text::caption(fl!("requirement-not-met")).into(),
+                    ])
+                    .align_items(Alignment::Center)
+                    .spacing(spacing.space_xxs),
+                );
+            }
+            column = column.push(row);
+        }
+        Some(column.into())
+    }
+
+    fn license_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Option<Element<Message>> {
+        let license = selected.info.license_opt.as_ref()?;
+        let badge: Element<_> = match selected.info.license_free_opt() {
+            Some(true) => widget::text::heading(fl!("license-free"))
+                .style(theme::Text::Accent)
+                .into(),
+            Some(false) => widget::text::heading(fl!("license-proprietary")).into(),
+            None => widget::text::heading(fl!("license-unknown")).into(),
+        };
+        Some(
+            widget::container(
+                widget::column::with_children(vec![
+                    badge,
+                    widget::text::caption(fl!("license", license = license.as_str())).into(),
+                ])
+                .spacing(spacing.space_xxxs),
+            )
+            .padding([spacing.space_xxs, spacing.space_s])
+            .style(theme::Container::Card)
+            .into(),
+        )
+    }
+
+    /// Known categories of the selected app, linking to their explore pages
+    fn app_categories_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Option<Element<Message>> {
+        let mut children = Vec::with_capacity(selected.info.categories.len());
+        for category in selected.info.categories.iter() {
+            let Some(name) = category_name(category) else {
+                continue;
+            };
+            let nav_page_opt = NavPage::all()
+                .iter()
+                .find(|nav_page| nav_page.category() == Some(category.as_str()));
+            children.push(match nav_page_opt {
+                Some(nav_page) => widget::button::text(name)
+                    .on_press(Message::NavPage(*nav_page))
+                    .into(),
+                None => widget::container(widget::text::caption(name))
+                    .padding([spacing.space_xxxs, spacing.space_xs])
+                    .into(),
+            });
+        }
+        if children.is_empty() {
+            return None;
+        }
+        Some(
+            widget::flex_row(children)
+                .column_spacing(spacing.space_xxs)
+                .row_spacing(spacing.space_xxs)
+                .into(),
+        )
+    }
+
+    fn sandbox_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Element<Message> {
+        let (icon_name, title) = if selected.info.sandboxed() {
+            ("security-high-symbolic", fl!("permissions-sandboxed"))
+        } else {
+            ("dialog-warning-symbolic", fl!("permissions-unrestricted"))
+        };
+        widget::container(
+            widget::row::with_children(vec![
+                widget::icon::from_name(icon_name).size(16).icon().into(),
+                widget::text::heading(title).into(),
+            ])
+            .align_items(Alignment::Center)
+            .spacing(spacing.space_xxs),
+        )
+        .padding([spacing.space_xxs, spacing.space_s])
+        .style(theme::Container::Card)
+        .into()
+    }
+
+    fn verified_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Option<Element<Message>> {
+        let verified = selected.info.verified_opt.as_ref()?;
+        let mut column = widget::column::with_capacity(2)
+            .spacing(spacing.space_xxxs)
+            .push(
+                widget::row::with_children(vec![
+                    widget::icon::from_name("emblem-ok-symbolic")
+                        .size(16)
+                        .into(),
+                    widget::text::heading(fl!("verified"))
+                        .style(theme::Text::Accent)
+                        .into(),
+                ])
+                .align_items(Alignment::Center)
+                .spacing(spacing.space_xxs),
+            );
+        if !verified.is_empty() {
+            column = column.push(widget::text::caption(verified));
+        }
+        Some(
+            widget::container(column)
+                .padding([spacing.space_xxs, spacing.space_s])
+                .style(theme::Container::Card)
+                .into(),
+        )
+    }
+
+    fn content_rating_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Option<Element<Message>> {
+        let age = selected.info.content_age_opt()?;
+        let badge_text = if age == 0 {
+            fl!("content-all-ages")
+        } else {
+            fl!("content-age", age = age)
+        };
+
+        let mut column = widget::column::with_capacity(2).spacing(spacing.space_xxs);
+        column = column.push(widget::button::standard(badge_text).on_press(
+            Message::SelectedContentRatingExpanded(!selected.content_rating_expanded),
+        ));
+        if selected.content_rating_expanded {
+            // Combine attributes into groups, using the most intense state
+            let groups = [
+                ("violence-", fl!("content-violence")),
+                ("drugs-", fl!("content-drugs")),
+                ("sex-", fl!("content-sex")),
+                ("language-", fl!("content-language")),
+                ("money-gambling", fl!("content-gambling")),
+                ("money-purchasing", fl!("content-purchases")),
+                ("money-advertising", fl!("content-advertising")),
+                ("social-", fl!("content-social")),
+            ];
+            let mut breakdown = widget::column::with_capacity(groups.len());
+            for (prefix, title) in groups {
+                let state = selected
+                    .info
+                    .content_rating
+                    .iter()
+                    .filter(|(id, _)| id.starts_with(prefix))
+                    .map(|(_, state)| *state)
+                    .max()
+                    .unwrap_or(AppContentState::None);
+                breakdown = breakdown.push(
+                    widget::row::with_children(vec![
+                        widget::text::body(title).width(Length::Fixed(160.0)).into(),
+                        widget::text::body(match state {
+                            AppContentState::None => fl!("content-none"),
+                            AppContentState::Mild => fl!("content-mild"),
+                            AppContentState::Moderate => fl!("content-moderate"),
+                            AppContentState::Intense => fl!("content-intense"),
+                        })
+                        .into(),
+                    ])
+                    .spacing(spacing.space_s),
+                );
+            }
+            column = column.push(
+                widget::container(breakdown)
+                    .padding(spacing.space_s)
+                    .style(theme::Container::Card),
+            );
+        }
+        Some(column.into())
+    }
+
+    fn releases_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Option<Element<Message>> {
+        if selected.info.releases.is_empty() {
+            return None;
+        }
+
+        // Newest releases first
+        let mut releases: Vec<&AppRelease> = selected.info.releases.iter().collect();
+        releases.sort_by(|a, b| b.timestamp_opt.cmp(&a.timestamp_opt));
+
+        let mut column = widget::column::with_capacity(releases.len() + 2)
+            .spacing(spacing.space_s)
+            .width(Length::Fill);
+        column = column.push(widget::text::title4(fl!("releases")));
+        let shown = if selected.releases_expanded {
+            releases.len()
+        } else {
+            1
+        };
+        for release in releases.iter().take(shown) {
+            let mut release_column = widget::column::with_capacity(3).spacing(spacing.space_xxs);
+            release_column = release_column.push(widget::text::heading(fl!(
+                "release-version",
+                version = release.version.as_str()
+            )));
+            if let Some(date) = release
+                .timestamp_opt
+                .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp, 0))
+            {
+                release_column =
+                    release_column.push(widget::text::caption(date.format("%Y-%m-%d").to_string()));
+            }
+            if let Some(description) = &release.description_opt {
+                release_column = release_column.push(markup_view(description, spacing));
+            }
+            column = column.push(release_column);
+        }
+        if releases.len() > 1 {
+            column = column.push(if selected.releases_expanded {
+                widget::button::link(fl!("hide-older-releases"))
+                    .on_press(Message::SelectedReleasesExpanded(false))
+                    .padding(0)
+            } else {
+                widget::button::link(fl!("show-older-releases"))
+                    .on_press(Message::SelectedReleasesExpanded(true))
+                    .padding(0)
+            });
+        }
+        Some(column.into())
+    }
+
+    fn reviews_view(
+        &self,
+        selected: &Selected,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Element<Message> {
+        let mut column = widget::column::with_capacity(3)
+            .spacing(spacing.space_s)
+            .width(Length::Fill);
+        if self.config.reviews_server.is_none() {
+            return column
+                .push(widget::text::body(fl!("reviews-disabled")))
+                .into();
+        }
+        let can_write = self.reviews.get(&selected.id).map_or(false, |reviews| {
+            reviews.iter().any(|review| review.user_skey.is_some())
+        });
+        let mut write_button = widget::button::standard(fl!("write-review"));
+        if can_write {
+            write_button = write_button.on_press(Message::ReviewWrite);
+        }
+        column = column.push(
+            widget::row::with_children(vec![
+                widget::text::title4(fl!("reviews"))
+                    .width(Length::Fill)
+                    .into(),
+                write_button.into(),
+            ])
+            .align_items(Alignment::Center),
+        );
+        if let Some(rating) = self.rating(&selected.id) {
+            if let Some(average) = rating.average() {
+                column = column.push(
+                    widget::row::with_children(vec![
+                        widget::text::title3(format!("{:.1}", average)).into(),
+                        stars_view(average, 16),
+                        widget::text::caption(fl!("ratings", count = rating.total)).into(),
+                    ])
+                    .align_items(Alignment::Center)
+                    .spacing(spacing.space_xs),
+                );
+            }
+        }
+        match self.reviews.get(&selected.id) {
+            Some(reviews) => {
+                // Skip the placeholder returned when there are no reviews
+                let reviews: Vec<&OdrsReview> = reviews
+                    .iter()
+                    .filter(|review| review.review_id != 0)
+                    .collect();
+                if reviews.is_empty() {
+                    column = column.push(widget::text::body(fl!("no-reviews")));
+                }
+                for review in reviews {
+                    let mut vote_row = widget::row::with_capacity(3)
+                        .align_items(Alignment::Center)
+                        .spacing(spacing.space_xs);
+                    if review.karma_up > 0 {
+                        vote_row = vote_row.push(widget::text::caption(fl!(
+                            "review-helpful-count",
+                            count = review.karma_up
+                        )));
+                    }
+                    if let Some(user_skey) = &review.user_skey {
+                        if self.review_votes.contains(&review.review_id) {
+                            vote_row = vote_row.push(widget::text::caption(fl!("review-voted")));
+                        } else {
+                            vote_row = vote_row
+                                .push(
+                                    widget::button::link(fl!("review-helpful"))
+                                        .on_press(Message::ReviewVote(
+                                            selected.id.clone(),
+                                            user_skey.clone(),
+                                            review.review_id,
+                                            true,
+                                        ))
+                                        .padding(0),
+                                )
+                                .push(
+                                    widget::button::link(fl!("review-not-helpful"))
+                                        .on_press(Message::ReviewVote(
+                                            selected.id.clone(),
+                                            user_skey.clone(),
+                                            review.review_id,
+                                            false,
+                                        ))
+                                        .padding(0),
+                                );
+                        }
+                    }
+                    let mut header = Vec::with_capacity(2);
+                    if let Some(user_display) = &review.user_display {
+                        header.push(user_display.clone());
+                    }
+                    if let Some(date) =
+                        chrono::DateTime::from_timestamp(review.date_created as i64, 0)
+                    {
+                        header.push(date.format("%Y-%m-%d").to_string());
+                    }
+                    column = column.push(
+                        widget::container(
+                            widget::column::with_children(vec![
+                                widget::row::with_children(vec![
+                                    widget::text::heading(review.summary.clone())
+                                        .width(Length::Fill)
+                                        .into(),
+                                    stars_view(review.stars(), 12),
+                                ])
+                                .align_items(Alignment::Center)
+                                .spacing(spacing.space_xs)
+                                .into(),
+                                widget::text::caption(header.join(" · ")).into(),
+                                widget::text::body(review.description.clone())
+                                    .width(Length::Fill)
+                                    .into(),
+                                vote_row.into(),
+                            ])
+                            .spacing(spacing.space_xxs),
+                        )
+                        .padding(spacing.space_s)
+                        .style(theme::Container::Card),
+                    );
+                }
+            }
+            None => {
+                column = column.push(widget::text::caption(fl!("loading")));
+            }
+        }
+        column.into()
+    }
+
+    /// Index of the featured app highlighted today, which changes once a day
+    fn app_of_the_day(&self) -> Option<usize> {
+        if self.featured.is_empty() {
+            return None;
+        }
+        use chrono::Datelike;
+        let days = chrono::Local::now().date_naive().num_days_from_ce();
+        Some(days.rem_euclid(self.featured.len() as i32) as usize)
+    }
+
+    fn app_of_the_day_view(&self) -> Option<Element<Message>> {
+        let featured_i = self.app_of_the_day()?;
+        let result = self.featured.get(featured_i)?;
+
+        let cosmic_theme::Spacing {
+            space_xxs, space_m, ..
+        } = self.sync_config.density.spacing();
+
+        let mut column = widget::column::with_capacity(4)
+            .push(widget::text::caption(fl!("app-of-the-day")))
+            .push(widget::text::title2(&result.info.name))
+            .push(widget::text::body(&result.info.summary))
+            .spacing(space_xxs)
+            .width(Length::Fill);
+        if let Some(average) = self.rating(&result.id).and_then(|rating| rating.average()) {
+            column = column.push(stars_view(average, 16));
+        }
+
+        Some(
+            widget::mouse_area(
+                widget::container(
+                    widget::row::with_children(vec![
+                        widget::icon::icon(result.icon.clone())
+                            .size(ICON_SIZE_DETAILS)
+                            .into(),
+                        column.into(),
+                    ])
+                    .align_items(Alignment::Center)
+                    .spacing(space_m),
+                )
+                .padding(space_m)
+                .width(Length::Fill)
+                .style(theme::Container::Card),
+            )
+            .on_press(Message::SelectFeatured(featured_i))
+            .into(),
+        )
+    }
+
+    fn featured_view(&self) -> Option<Element<Message>> {
+        let result = self.featured.get(self.featured_shown)?;
+
+        let cosmic_theme::Spacing {
+            space_xxs, space_m, ..
+        } = self.sync_config.density.spacing();
+
+        //TODO: get height from theme?
+        let banner_height = Length::Fixed(320.0);
+        let artwork: Element<_> = match result
+            .info
+            .screenshots_for_theme(theme::active().cosmic().is_dark)
+            .first()
+            .and_then(|(_, screenshot)| self.featured_images.get(&screenshot.url))
+        {
+            Some(image) => widget::image(image.clone())
+                .width(Length::Fill)
+                .height(banner_height)
+                .into(),
+            None => {
+                widget::container(widget::icon::icon(result.icon.clone()).size(ICON_SIZE_DETAILS))
+                    .center_x()
+                    .center_y()
+                    .width(Length::Fill)
+                    .height(banner_height)
+                    .into()
+            }
+        };
+
+        let mut prev_button =
+            widget::button::icon(widget::icon::from_name("go-previous-symbolic").size(16));
+        let mut next_button =
+            widget::button::icon(widget::icon::from_name("go-next-symbolic").size(16));
+        if self.featured.len() > 1 {
+            prev_button = prev_button.on_press(Message::FeaturedShown(
+                (self.featured_shown + self.featured.len() - 1) % self.featured.len(),
+            ));
+            next_button = next_button.on_press(Message::FeaturedNext);
+        }
+
+        Some(
+            widget::container(
+                widget::row::with_children(vec![
+                    prev_button.into(),
+                    widget::mouse_area(
+                        widget::column::with_children(vec![
+                            artwork,
+                            widget::row::with_children(vec![
+                                widget::icon::icon(result.icon.clone())
+                                    .size(ICON_SIZE_SEARCH)
+                                    .into(),
+                                widget::column::with_children(vec![
+                                    widget::text::title3(&result.info.name).into(),
+                                    widget::text::body(&result.info.summary).into(),
+                                ])
+                                .into(),
+                            ])
+                            .align_items(Alignment::Center)
+                            .spacing(space_m)
+                            .into(),
+                        ])
+                        .spacing(space_xxs)
+                        .width(Length::Fill),
+                    )
+                    .on_press(Message::SelectFeatured(self.featured_shown))
+                    .into(),
+                    next_button.into(),
+                ])
+                .align_items(Alignment::Center)
+                .spacing(space_xxs),
+            )
+            .padding(space_m)
+            .style(theme::Container::Card)
+            .into(),
+        )
+    }
+
+    /// Categories in a sidebar on wide windows, or a dropdown on narrow windows
+    fn categories_view(&self, spacing: &cosmic_theme::Spacing) -> Element<Message> {
+        let categories = || {
+            NavPage::all()
+                .iter()
+                .filter(|nav_page| nav_page.category().is_some())
+        };
+        if self.window_width >= SIDEBAR_MIN_WIDTH {
+            let mut column = widget::column::with_capacity(categories().count() + 1)
+                .push(widget::text::heading(fl!("categories")))
+                .spacing(spacing.space_xxs)
+                .width(Length::Fixed(160.0));
+            for nav_page in categories() {
+                column = column.push(
+                    widget::button(widget::text::body(nav_page.title()))
+                        .style(theme::Button::Text)
+                        .width(Length::Fill)
+                        .on_press(Message::NavPage(*nav_page)),
+                );
+            }
+            column.into()
+        } else {
+            // The first search filter category is all categories
+            widget::dropdown(&self.search_filter_categories[1..], None, move |index| {
+                match categories().nth(index) {
+                    Some(nav_page) => Message::NavPage(*nav_page),
+                    None => Message::NavPage(NavPage::Explore),
+                }
+            })
+            .into()
+        }
+    }
+
+    fn explore_view(&self) -> Element<Message> {
+        let spacing = self.sync_config.density.spacing();
+        let cosmic_theme::Spacing {
+            space_xl, space_m, ..
+        } = spacing;
+
+        let mut column = widget::column::with_capacity(2)
+            .spacing(space_m)
+            .width(Length::Fill);
+        let wide = self.window_width >= SIDEBAR_MIN_WIDTH;
+        if !wide {
+            column = column.push(self.categories_view(&spacing));
+        }
+        if !self.backends_loaded {
+            // Backends are still loading their appstream caches
+            column = column
+                .push(widget::text::caption(fl!("loading")))
+                .push(self.skeleton_view(&spacing));
+        } else if self.backends.is_empty() {
+            column = column.push(empty_state_view(
+                "dialog-error-symbolic",
+                fl!("no-backends"),
+                fl!("no-backends-body"),
+                if cfg!(feature = "flatpak") {
+                    Some((fl!("add-flathub"), Message::FlathubAdd))
+                } else {
+                    None
+                },
+                &spacing,
+            ));
+        }
+        if self.offline {
+            column = column.push(empty_state_view(
+                "network-offline-symbolic",
+                fl!("offline"),
+                fl!("offline-body"),
+                Some((fl!("retry"), Message::NetworkRetry)),
+                &spacing,
+            ));
+        }
+        if let Some(app_of_the_day) = self.app_of_the_day_view() {
+            column = column.push(app_of_the_day);
+        }
+        if let Some(featured) = self.featured_view() {
+            column = column.push(featured);
+        }
+        if let Some(pinned_searches) = self.pinned_searches_view() {
+            column = column.push(pinned_searches);
+        }
+        if let Some(favorites) = self.section_view(
+            fl!("favorites"),
+            &self.favorites,
+            Message::SelectFavorite,
+            &spacing,
+        ) {
+            column = column.push(favorites);
+        }
+        if let Some(popular) = self.section_view(
+            fl!("popular"),
+            &self.popular,
+            Message::SelectPopular,
+            &spacing,
+        ) {
+            column = column.push(popular);
+        }
+        if let Some(recently_updated) = self.section_view(
+            fl!("recently-updated"),
+            &self.recently_updated,
+            Message::SelectRecentlyUpdated,
+            &spacing,
+        ) {
+            column = column.push(recently_updated);
+        }
+        if let Some(recently_added) = self.section_view(
+            fl!("recently-added"),
+            &self.recently_added,
+            Message::SelectRecentlyAdded,
+            &spacing,
+        ) {
+            column = column.push(recently_added);
+        }
+        if wide {
+            self.page_scrollable(
+                widget::row::with_children(vec![self.categories_view(&spacing), column.into()])
+                    .padding([0, space_xl])
+                    .spacing(space_m),
+            )
+        } else {
+            self.page_scrollable(column.padding([0, space_xl]))
+        }
+    }
+
+    fn section_view<'a>(
+        &'a self,
+        title: String,
+        results: &'a [SearchResult],
+        on_select: fn(usize) -> Message,
+        spacing: &cosmic_theme::Spacing,
+    ) -> Option<Element<'a, Message>> {
+        if results.is_empty() {
+            return None;
+        }
+        let mut flex_row = Vec::with_capacity(results.len());
+        for (result_i, result) in results.iter().enumerate() {
+            if self.result_hidden(&result.info) {
+                continue;
+            }
+            flex_row.push(
+                widget::mouse_area(self.result_view(result, spacing))
+                    .on_press(on_select(result_i))
+                    .into(),
+            );
+        }
+        if flex_row.is_empty() {
+            return None;
+        }
+        Some(
+            widget::column::with_children(vec![
+                widget::text::heading(title).into(),
+                self.results_layout(flex_row, spacing),
+            ])
+            .spacing(spacing.space_xxs)
+            .into(),
+        )
+    }
+
+    fn pinned_searches_view(&self) -> Option<Element<Message>> {
+        if self.sync_config.pinned_searches.is_empty() {
+            return None;
+        }
+
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = self.sync_config.density.spacing();
+
+        let mut flex_row = Vec::with_capacity(self.sync_config.pinned_searches.len());
+        for input in self.sync_config.pinned_searches.iter() {
+            flex_row.push(
+                widget::button(
+                    widget::row::with_children(vec![
+                        widget::icon::from_name("system-search-symbolic")
+                            .size(16)
+                            .into(),
+                        widget::text::body(input).into(),
+                    ])
+                    .align_items(Alignment::Center)
+                    .spacing(space_xxs),
+                )
+                .padding(space_s)
+                .style(theme::Button::Standard)
+                .on_press(Message::SearchRun(input.clone()))
+                .into(),
+            );
+        }
+        Some(
+            widget::column::with_children(vec![
+                widget::text::heading(fl!("pinned-searches")).into(),
+                widget::flex_row(flex_row)
+                    .column_spacing(space_xxs)
+                    .row_spacing(space_xxs)
+                    .into(),
+            ])
+            .spacing(space_xxs)
+            .into(),
+        )
     }
 
-    fn settings(&self) -> Element<Message> {
-        let app_theme_selected = match self.config.app_theme {
-            AppTheme::Dark => 1,
-            AppTheme::Light => 2,
-            AppTheme::System => 0,
+    fn load_more_button(&self) -> Element<Message> {
+        widget::container(
+            widget::button::standard(fl!("load-more")).on_press(Message::ResultsLoadMore),
+        )
+        .center_x()
+        .width(Length::Fill)
+        .into()
+    }
+
+    fn search_filter_view(&self) -> Element<Message> {
+        let search_filter = self.search_filter;
+        let backend_names = self.backend_names();
+        let backend_selected = match search_filter.backend_opt {
+            Some(backend_name) => backend_names
+                .iter()
+                .position(|x| *x == backend_name)
+                .map(|i| i + 1),
+            None => Some(0),
+        };
+        let categories: Vec<&'static str> = NavPage::all()
+            .iter()
+            .filter_map(|nav_page| nav_page.category())
+            .collect();
+        let category_selected = match search_filter.category_opt {
+            Some(category) => categories
+                .iter()
+                .position(|x| *x == category)
+                .map(|i| i + 1),
+            None => Some(0),
         };
-        widget::settings::view_column(vec![widget::settings::view_section(fl!("appearance"))
+        widget::settings::view_column(vec![widget::settings::view_section("")
+            .add(
+                widget::settings::item::builder(fl!("source")).control(widget::dropdown(
+                    &self.search_filter_backends,
+                    backend_selected,
+                    move |index| {
+                        Message::SearchFilter(SearchFilter {
+                            backend_opt: index
+                                .checked_sub(1)
+                                .and_then(|i| backend_names.get(i).copied()),
+                            ..search_filter
+                        })
+                    },
+                )),
+            )
             .add(
-                widget::settings::item::builder(fl!("theme")).control(widget::dropdown(
-                    &self.app_themes,
-                    Some(app_theme_selected),
+                widget::settings::item::builder(fl!("category")).control(widget::dropdown(
+                    &self.search_filter_categories,
+                    category_selected,
                     move |index| {
-                        Message::AppTheme(match index {
-                            1 => AppTheme::Dark,
-                            2 => AppTheme::Light,
-                            _ => AppTheme::System,
+                        Message::SearchFilter(SearchFilter {
+                            category_opt: index
+                                .checked_sub(1)
+                                .and_then(|i| categories.get(i).copied()),
+                            ..search_filter
                         })
                     },
                 )),
             )
+            .add(
+                widget::settings::item::builder(fl!("installed-only")).toggler(
+                    search_filter.installed_only,
+                    move |installed_only| {
+                        Message::SearchFilter(SearchFilter {
+                            installed_only,
+                            ..search_filter
+                        })
+                    },
+                ),
+            )
+            .add(
+                widget::settings::item::builder(fl!("search-descriptions")).toggler(
+                    search_filter.description,
+                    move |description| {
+                        Message::SearchFilter(SearchFilter {
+                            description,
+                            ..search_filter
+                        })
+                    },
+                ),
+            )
+            .add(
+                widget::settings::item::builder(fl!("include-system-components")).toggler(
+                    search_filter.system_components,
+                    move |system_components| {
+                        Message::SearchFilter(SearchFilter {
+                            system_components,
+                            ..search_filter
+                        })
+                    },
+                ),
+            )
+            .add(
+                widget::settings::item::builder(fl!("free-software-only")).toggler(
+                    search_filter.free_only,
+                    move |free_only| {
+                        Message::SearchFilter(SearchFilter {
+                            free_only,
+                            ..search_filter
+                        })
+                    },
+                ),
+            )
+            .add(
+                widget::settings::item::builder(fl!("search-provides"))
+                    .description(fl!("search-provides-description"))
+                    .control(
+                        widget::button::standard(fl!("search-provides-start"))
+                            .on_press(Message::SearchInput("provides:".to_string())),
+                    ),
+            )
             .into()])
         .into()
     }
+
+    /// Cache sizes with buttons to clear them, shown in settings and storage
+    fn cache_section(&self) -> Element<Message> {
+        let mut cache_section = widget::settings::view_section(fl!("cache"));
+        for kind in CacheKind::all() {
+            let title = match kind {
+                CacheKind::Appstream => fl!("cache-appstream"),
+                CacheKind::Icons => fl!("cache-icons"),
+                CacheKind::Screenshots => fl!("cache-screenshots"),
+                CacheKind::Reviews => fl!("cache-reviews"),
+            };
+            let size = match &self.cache_sizes_opt {
+                Some(cache_sizes) => format_size(cache_sizes.get(kind).copied().unwrap_or(0)),
+                None => fl!("calculating"),
+            };
+            let mut clear_button = widget::button::standard(fl!("clear"));
+            if kind.clearable() {
+                clear_button = clear_button.on_press(Message::CacheClear(*kind));
+            }
+            cache_section = cache_section.add(
+                widget::settings::item::builder(title)
+                    .description(size)
+                    .control(clear_button),
+            );
+        }
+        cache_section.into()
+    }
+
+    fn sources_view(&self) -> Element<Message> {
+        let Some(repos) = &self.repos_opt else {
+            return widget::text(fl!("loading")).into();
+        };
+        if repos.is_empty() {
+            return widget::text(fl!("no-optional-sources")).into();
+        }
+        let mut sections = Vec::with_capacity(self.backends.len() + 1);
+        sections.push(widget::text(fl!("optional-sources-notice")).into());
+        for backend_name in self.backend_names() {
+            let mut section = widget::settings::view_section(backend_name);
+            let mut has_repos = false;
+            for (_, repo) in repos.iter().filter(|(x, _)| *x == backend_name) {
+                has_repos = true;
+                let id = repo.id.clone();
+                section = section.add(
+                    widget::settings::item::builder(repo.name.clone())
+                        .description(match repo.license {
+                            RepoLicense::Proprietary => fl!("repo-proprietary"),
+                            RepoLicense::PatentEncumbered => fl!("repo-patent-encumbered"),
+                        })
+                        .toggler(repo.enabled, move |enabled| {
+                            Message::RepoEnable(backend_name, id.clone(), enabled)
+                        }),
+                );
+            }
+            if has_repos {
+                sections.push(section.into());
+            }
+        }
+        widget::settings::view_column(sections).into()
+    }
+
+    fn storage_view(&self) -> Element<Message> {
+        let mut sections = Vec::with_capacity(2);
+        //TODO: show snaps when there is a snap backend
+        match &self.storage_opt {
+            Some(storage) => {
+                for (backend_name, usage) in storage.iter() {
+                    let mut remove_button = widget::button::standard(fl!("remove-unused"));
+                    let removing = self.pending_operations.values().any(|(op, _)| {
+                        op.kind == OperationKind::RemoveUnused && op.backend_name == *backend_name
+                    });
+                    if usage.unused > 0 && !removing {
+                        remove_button =
+                            remove_button.on_press(Message::StorageRemoveUnused(*backend_name));
+                    }
+                    sections.push(
+                        widget::settings::view_section(*backend_name)
+                            .add(
+                                widget::settings::item::builder(fl!("storage-apps"))
+                                    .control(widget::text(format_size(usage.apps))),
+                            )
+                            .add(
+                                widget::settings::item::builder(fl!("storage-runtimes"))
+                                    .description(fl!("storage-runtimes-description"))
+                                    .control(widget::text(format_size(usage.runtimes))),
+                            )
+                            .add(
+                                widget::settings::item::builder(fl!("storage-unused"))
+                                    .description(format_size(usage.unused))
+                                    .control(remove_button),
+                            )
+                            .into(),
+                    );
+                }
+            }
+            None => {
+                sections.push(widget::text(fl!("calculating")).into());
+            }
+        }
+        sections.push(self.cache_section());
+        widget::settings::view_column(sections).into()
+    }
+
+    fn settings(&self) -> Element<Message> {
+        let mut key_binds_section = widget::settings::view_section(fl!("keyboard-shortcuts"));
+        for action in Action::all() {
+            let label = if self.key_bind_capture_opt == Some(*action) {
+                fl!("press-shortcut")
+            } else {
+                match self.config.key_binds.get(action) {
+                    Some(key_bind) => key_bind.to_string(),
+                    None => fl!("shortcut-disabled"),
+                }
+            };
+            key_binds_section =
+                key_binds_section.add(widget::settings::item::builder(action.title()).control(
+                    widget::button::standard(label).on_press(Message::KeyBindCapture(*action)),
+                ));
+        }
+        key_binds_section = key_binds_section.add(
+            widget::settings::item::builder(fl!("reset-shortcuts"))
+                .description(fl!("reset-shortcuts-description"))
+                .control(widget::button::standard(fl!("reset")).on_press(Message::KeyBindsReset)),
+        );
+        let reviews_server_input = self.reviews_server_input.trim().to_string();
+        let app_theme_selected = match self.sync_config.app_theme {
+            AppTheme::Dark => 1,
+            AppTheme::Light => 2,
+            AppTheme::System => 0,
+        };
+        let density_selected = match self.sync_config.density {
+            Density::Comfortable => 0,
+            Density::Compact => 1,
+        };
+        // Limits set outside of the store may not be in the list
+        let content_age_limit_selected = match self.config.max_content_age {
+            Some(max_content_age) => CONTENT_AGE_LIMITS
+                .iter()
+                .position(|x| *x == max_content_age)
+                .map(|i| i + 1),
+            None => Some(0),
+        };
+        let preferred_format_selected = match self.config.preferred_format {
+            PreferredFormat::Flatpak => 0,
+            PreferredFormat::Native => 1,
+        };
+        // Limits set outside of the store may not be in the list
+        let download_limit_selected = match self.config.download_limit {
+            Some(download_limit) => DOWNLOAD_LIMITS
+                .iter()
+                .position(|x| *x == download_limit)
+                .map(|i| i + 1),
+            None => Some(0),
+        };
+        let parallel_operations_selected = match self.config.parallel_operations {
+            Some(1) => 0,
+            Some(2) => 1,
+            Some(4) => 2,
+            _ => 3,
+        };
+        let refresh_interval_selected = match self.config.refresh_interval {
+            RefreshInterval::Hourly => 0,
+            RefreshInterval::Daily => 1,
+            RefreshInterval::Never => 2,
+        };
+        let mut export_button = widget::button::standard(fl!("export"));
+        if self.installed.is_some() {
+            export_button = export_button.on_press(Message::AppListExport);
+        }
+        widget::settings::view_column(vec![
+            widget::settings::view_section(fl!("appearance"))
+                .add(
+                    widget::settings::item::builder(fl!("theme")).control(widget::dropdown(
+                        &self.app_themes,
+                        Some(app_theme_selected),
+                        move |index| {
+                            Message::AppTheme(match index {
+                                1 => AppTheme::Dark,
+                                2 => AppTheme::Light,
+                                _ => AppTheme::System,
+                            })
+                        },
+                    )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("density")).control(widget::dropdown(
+                        &self.densities,
+                        Some(density_selected),
+                        move |index| {
+                            Message::Density(match index {
+                                1 => Density::Compact,
+                                _ => Density::Comfortable,
+                            })
+                        },
+                    )),
+                )
+                .into(),
+            widget::settings::view_section(fl!("software"))
+                .add(
+                    widget::settings::item::builder(fl!("foreign-architectures"))
+                        .description(fl!(
+                            "foreign-architectures-description",
+                            arch = app_info::current_arch()
+                        ))
+                        .toggler(
+                            self.config.foreign_architectures,
+                            Message::ForeignArchitectures,
+                        ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("free-software-only"))
+                        .description(fl!("free-software-only-description"))
+                        .toggler(self.config.free_software_only, Message::FreeSoftwareOnly),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("sources"))
+                        .description(fl!("sources-description"))
+                        .control(
+                            widget::button::standard(fl!("manage"))
+                                .on_press(Message::ToggleContextPage(ContextPage::Sources)),
+                        ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("require-auth"))
+                        .description(fl!("require-auth-description"))
+                        .toggler(self.config.require_auth, Message::RequireAuth),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("content-age-limit-title"))
+                        .description(fl!("content-age-limit-description"))
+                        .control(widget::dropdown(
+                            &self.content_age_limits,
+                            content_age_limit_selected,
+                            move |index| {
+                                Message::ContentAgeLimit(
+                                    index
+                                        .checked_sub(1)
+                                        .and_then(|i| CONTENT_AGE_LIMITS.get(i).copied()),
+                                )
+                            },
+                        )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("preferred-format"))
+                        .description(fl!("preferred-format-description"))
+                        .control(widget::dropdown(
+                            &self.preferred_formats,
+                            Some(preferred_format_selected),
+                            move |index| {
+                                Message::PreferredFormat(match index {
+                                    1 => PreferredFormat::Native,
+                                    _ => PreferredFormat::Flatpak,
+                                })
+                            },
+                        )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("catalog-language"))
+                        .description(fl!("catalog-language-description"))
+                        .control(
+                            widget::text_input(&self.system_locale, &self.catalog_locale_input)
+                                .on_input(Message::CatalogLocaleInput)
+                                .on_submit(Message::CatalogLocaleSubmit),
+                        ),
+                )
+                .into(),
+            widget::settings::view_section(fl!("backup"))
+                .add(
+                    widget::settings::item::builder(fl!("export-app-list"))
+                        .description(fl!("export-app-list-description"))
+                        .control(export_button),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("import-app-list"))
+                        .description(fl!(
+                            "import-app-list-description",
+                            path = app_list_path().display().to_string()
+                        ))
+                        .control(
+                            widget::button::standard(fl!("import"))
+                                .on_press(Message::AppListImport(app_list_path())),
+                        ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("export-settings"))
+                        .description(fl!("export-settings-description"))
+                        .control(
+                            widget::button::standard(fl!("export")).on_press(Message::SyncExport),
+                        ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("import-settings"))
+                        .description(fl!(
+                            "import-settings-description",
+                            path = sync_file_path().display().to_string()
+                        ))
+                        .control(
+                            widget::button::standard(fl!("import"))
+                                .on_press(Message::SyncImport(sync_file_path())),
+                        ),
+                )
+                .into(),
+            self.cache_section(),
+            widget::settings::view_section(fl!("network"))
+                .add(
+                    widget::settings::item::builder(fl!("proxy"))
+                        .description(fl!("proxy-description"))
+                        .control(
+                            widget::text_input(fl!("proxy-placeholder"), &self.proxy_input)
+                                .on_input(Message::ProxyInput)
+                                .on_submit(Message::ProxySubmit),
+                        ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("download-limit"))
+                        .description(fl!("download-limit-description"))
+                        .control(widget::dropdown(
+                            &self.download_limits,
+                            download_limit_selected,
+                            move |index| {
+                                Message::DownloadLimit(
+                                    index
+                                        .checked_sub(1)
+                                        .and_then(|i| DOWNLOAD_LIMITS.get(i).copied()),
+                                )
+                            },
+                        )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("parallel-operations"))
+                        .description(fl!("parallel-operations-description"))
+                        .control(widget::dropdown(
+                            &self.parallel_operations,
+                            Some(parallel_operations_selected),
+                            move |index| {
+                                Message::ParallelOperations(match index {
+                                    0 => Some(1),
+                                    1 => Some(2),
+                                    2 => Some(4),
+                                    _ => None,
+                                })
+                            },
+                        )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("reviews"))
+                        .description(fl!("reviews-description"))
+                        .toggler(self.config.reviews_server.is_some(), move |enabled| {
+                            // An empty server uses the default instead of disabling reviews
+                            Message::ReviewsServer(enabled.then(|| {
+                                if reviews_server_input.is_empty() {
+                                    odrs::ODRS_URL.to_string()
+                                } else {
+                                    reviews_server_input.clone()
+                                }
+                            }))
+                        }),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("reviews-server")).control(
+                        widget::text_input(odrs::ODRS_URL, &self.reviews_server_input)
+                            .on_input(Message::ReviewsServerInput)
+                            .on_submit(Message::ReviewsServerSubmit),
+                    ),
+                )
+                .into(),
+            widget::settings::view_section(fl!("notifications"))
+                .add(
+                    widget::settings::item::builder(fl!("update-notifications"))
+                        .toggler(self.config.notifications, Message::UpdatesNotifications),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("tray"))
+                        .toggler(self.config.tray, Message::Tray),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("refresh-interval"))
+                        .description(fl!("refresh-interval-description"))
+                        .control(widget::dropdown(
+                            &self.refresh_intervals,
+                            Some(refresh_interval_selected),
+                            move |index| {
+                                Message::RefreshInterval(match index {
+                                    0 => RefreshInterval::Hourly,
+                                    2 => RefreshInterval::Never,
+                                    _ => RefreshInterval::Daily,
+                                })
+                            },
+                        )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("autostart"))
+                        .description(fl!("autostart-description"))
+                        .toggler(self.autostart, Message::Autostart),
+                )
+                .into(),
+            key_binds_section.into(),
+        ])
+        .into()
+    }
 }
 
 /// Implement [`Application`] to integrate with COSMIC.
@@ -747,12 +4716,41 @@ impl Application for App {
 
     /// Creates the application, and optionally emits command on initialize.
     fn init(core: Core, flags: Self::Flags) -> (Self, Command<Self::Message>) {
-        let locale = sys_locale::get_locale().unwrap_or_else(|| {
+        let system_locale = sys_locale::get_locale().unwrap_or_else(|| {
             log::warn!("failed to get system locale, falling back to en-US");
             String::from("en-US")
         });
+        let locale = flags
+            .config
+            .catalog_locale
+            .clone()
+            .unwrap_or_else(|| system_locale.clone());
 
         let app_themes = vec![fl!("match-desktop"), fl!("dark"), fl!("light")];
+        let densities = vec![fl!("comfortable"), fl!("compact")];
+        let mut content_age_limits = vec![fl!("content-all-ages")];
+        for age in CONTENT_AGE_LIMITS {
+            content_age_limits.push(fl!("content-age-limit", age = age));
+        }
+        let mut download_limits = vec![fl!("no-limit")];
+        for download_limit in DOWNLOAD_LIMITS {
+            download_limits.push(fl!("per-second", size = format_size(download_limit)));
+        }
+        let parallel_operations = vec![
+            fl!("one-at-a-time"),
+            fl!("two-at-a-time"),
+            fl!("four-at-a-time"),
+            fl!("no-limit"),
+        ];
+        let preferred_formats = vec![fl!("prefer-flatpak"), fl!("prefer-native")];
+        let refresh_intervals = vec![fl!("hourly"), fl!("daily"), fl!("never")];
+
+        let mut search_filter_categories = vec![fl!("all-categories")];
+        for nav_page in NavPage::all() {
+            if nav_page.category().is_some() {
+                search_filter_categories.push(nav_page.title());
+            }
+        }
 
         let mut nav_model = widget::nav_bar::Model::default();
         for &nav_page in NavPage::all() {
@@ -761,14 +4759,27 @@ impl Application for App {
                 .icon(nav_page.icon())
                 .text(nav_page.title())
                 .data::<NavPage>(nav_page)
+                // Separate top level pages from categories
+                .divider_above(nav_page == NavPage::Create)
                 .id();
-            if nav_page == NavPage::Installed {
-                // Activate installed page by default
-                //TODO: different default page, save last page?
+            if nav_page == flags.config.nav_page {
+                // Activate the last visited page, explore by default
                 nav_model.activate(id);
             }
         }
 
+        let mut details_tab_model = widget::segmented_button::SingleSelectModel::default();
+        for &details_tab in DetailsTab::all() {
+            let id = details_tab_model
+                .insert()
+                .text(details_tab.title())
+                .data::<DetailsTab>(details_tab)
+                .id();
+            if details_tab == DetailsTab::Overview {
+                details_tab_model.activate(id);
+            }
+        }
+
         let stats = {
             let start = Instant::now();
             match bitcode::decode::<Vec<(String, u64)>>(include_bytes!(
@@ -786,43 +4797,158 @@ impl Application for App {
             }
         };
 
+        http::set_proxy(&flags.config.proxy);
+        http::set_download_limit(flags.config.download_limit);
+        odrs::set_server(flags.config.reviews_server.as_deref());
+
+        let (window_width, window_height) = flags
+            .config
+            .window_size
+            .map_or((1024.0, 768.0), |(width, height)| {
+                (width as f32, height as f32)
+            });
+
         let mut app = App {
             core,
             config_handler: flags.config_handler,
             config: flags.config,
+            sync_config_handler: flags.sync_config_handler,
+            sync_config: flags.sync_config,
             locale,
+            system_locale,
             app_themes,
+            autostart: autostart::enabled(),
+            densities,
+            content_age_limits,
+            download_limits,
+            parallel_operations,
+            preferred_formats,
+            refresh_intervals,
             backends: Backends::new(),
+            backends_loaded: false,
+            error_banners: Vec::new(),
+            busy: HashSet::new(),
+            // Saved or default window size until the first resize event
+            window_width,
+            window_height,
+            window_resize_id: 0,
+            target_pending_opt: flags.target_opt,
             context_page: ContextPage::Settings,
+            details_tab_model,
             dialog_pages: VecDeque::new(),
-            key_binds: key_binds(),
+            featured: Vec::new(),
+            featured_images: HashMap::new(),
+            desktop_entries: HashMap::new(),
+            featured_shown: 0,
+            favorites: Vec::new(),
+            popular: Vec::new(),
+            recently_added: Vec::new(),
+            recently_updated: Vec::new(),
+            offline: false,
+            ratings: HashMap::new(),
+            reviews: HashMap::new(),
+            review_error_opt: None,
+            review_submitting: false,
+            review_votes: HashSet::new(),
+            key_binds: key_binds(&flags.config.key_binds),
+            key_bind_capture_opt: None,
+            proprietary_shown: false,
+            system_components_shown: false,
             nav_model,
             pending_operation_id: 0,
             pending_operations: BTreeMap::new(),
+            session_requests: Vec::new(),
+            running_operations: HashSet::new(),
+            operations_authorized: false,
+            cache_sizes_opt: None,
+            storage_opt: None,
+            repos_opt: None,
             failed_operations: BTreeMap::new(),
             search_active: false,
+            search_descriptions: HashMap::new(),
+            search_filter: SearchFilter::default(),
+            search_filter_backends: vec![fl!("all-sources")],
+            search_filter_categories,
+            search_history_selected: None,
             search_id: widget::Id::unique(),
             search_input: String::new(),
+            catalog_locale_input: flags.config.catalog_locale.clone().unwrap_or_default(),
+            proxy_input: flags.config.proxy.clone(),
+            reviews_server_input: flags
+                .config
+                .reviews_server
+                .clone()
+                .unwrap_or_else(|| odrs::ODRS_URL.to_string()),
             stats,
             installed: None,
+            installed_filter: String::new(),
             updates: None,
+            updates_notification_opt: None,
+            updates_expanded: HashSet::new(),
             waiting_installed: Vec::new(),
             waiting_updates: Vec::new(),
             category_results: None,
+            developer_results: None,
+            results_limit: RESULTS_PAGE_SIZE,
             search_results: None,
+            search_sort: SearchSort::Relevance,
+            search_suggestion_opt: None,
+            search_sort_names: SearchSort::all()
+                .iter()
+                .map(|search_sort| search_sort.title())
+                .collect(),
             selected_opt: None,
+            nav_back: Vec::new(),
+            nav_forward: Vec::new(),
+            nav_page_states: HashMap::new(),
+            scroll_id: scrollable::Id::unique(),
+            scroll_offset: AbsoluteOffset::default(),
         };
 
-        let command = Command::batch([app.update_title(), app.update_backends()]);
-        (app, command)
+        let mut commands = vec![
+            app.update_title(),
+            app.update_backends(),
+            app.update_ratings(),
+        ];
+        if flags.hidden {
+            commands.push(window::change_mode(window::Id::MAIN, window::Mode::Hidden));
+        } else if app.config.window_maximized {
+            commands.push(window::maximize(window::Id::MAIN, true));
+        }
+        (app, Command::batch(commands))
     }
 
     fn nav_model(&self) -> Option<&widget::nav_bar::Model> {
         Some(&self.nav_model)
     }
 
+    /// Raise the window when another instance is launched, opening any URI it was given
+    fn dbus_activation(&mut self, msg: DbusActivationMessage) -> Command<Message> {
+        if let DbusActivationDetails::ActivateAction { action, .. } = msg.msg {
+            match AppTarget::from_uri(&action) {
+                Some(target) => {
+                    return Command::batch([
+                        self.update(Message::OpenTarget(target)),
+                        self.update(Message::WindowActivate),
+                    ]);
+                }
+                None => {
+                    log::warn!("unsupported activation action {:?}", action);
+                }
+            }
+        }
+        // The window is hidden when started at login
+        self.update(Message::WindowActivate)
+    }
+
     fn on_escape(&mut self) -> Command<Message> {
-        if self.core.window.show_context {
+        if self.key_bind_capture_opt.is_some() {
+            // Cancel binding a key
+            self.key_bind_capture_opt = None;
+        } else if let Some(selected) = self.selected_opt.as_mut().filter(|x| x.lightbox) {
+            // Close screenshot viewer if open
+            selected.lightbox = false;
+        } else if self.core.window.show_context {
             // Close context drawer if open
             self.core.window.show_context = false;
         } else if self.search_active {
@@ -834,20 +4960,48 @@ impl Application for App {
     }
 
     fn on_nav_select(&mut self, id: widget::nav_bar::Id) -> Command<Message> {
+        // Remembered so the store opens on the same page next time
+        if let Some(nav_page) = self.nav_model.data::<NavPage>(id).copied() {
+            if nav_page != self.config.nav_page {
+                if let Some(config_handler) = &self.config_handler {
+                    if let Err(err) = self.config.set_nav_page(config_handler, nav_page) {
+                        log::warn!("failed to save config \"nav_page\": {}", err);
+                    }
+                }
+            }
+        }
+        // Proprietary apps are only shown for the view they were requested in
+        self.proprietary_shown = false;
+        let active_id = self.nav_model.active();
+        let state = self.nav_state();
+        self.nav_push();
+        // Each page keeps its own state, selecting the active page again returns to its start
+        if id != active_id {
+            if let Some(nav_page) = self.nav_model.data::<NavPage>(active_id).copied() {
+                self.nav_page_states.insert(nav_page, state);
+            }
+            if let Some(state) = self
+                .nav_model
+                .data::<NavPage>(id)
+                .and_then(|nav_page| self.nav_page_states.remove(nav_page))
+            {
+                return self.nav_restore(state);
+            }
+        }
         self.category_results = None;
+        self.developer_results = None;
         self.search_active = false;
         self.search_results = None;
         self.selected_opt = None;
         self.nav_model.activate(id);
-        //TODO: do not preserve scroll on page change
         if let Some(category) = self
             .nav_model
             .active_data::<NavPage>()
             .and_then(|nav_page| nav_page.category())
         {
-            return self.category(category);
+            return Command::batch([self.nav_scroll_top(), self.category(category)]);
         }
-        Command::none()
+        self.nav_scroll_top()
     }
 
     /// Handle application events here.
@@ -860,95 +5014,944 @@ impl Application for App {
                         match paste::paste! { self.config.[<set_ $name>](config_handler, $value) } {
                             Ok(_) => {}
                             Err(err) => {
-                                log::warn!(
-                                    "failed to save config {:?}: {}",
-                                    stringify!($name),
-                                    err
-                                );
+                                log::warn!(
+                                    "failed to save config {:?}: {}",
+                                    stringify!($name),
+                                    err
+                                );
+                            }
+                        }
+                    }
+                    None => {
+                        self.config.$name = $value;
+                        log::warn!(
+                            "failed to save config {:?}: no config handler",
+                            stringify!($name)
+                        );
+                    }
+                }
+            };
+        }
+
+        // Helper for updating sync config values efficiently
+        macro_rules! sync_config_set {
+            ($name: ident, $value: expr) => {
+                match &self.sync_config_handler {
+                    Some(sync_config_handler) => {
+                        match paste::paste! { self.sync_config.[<set_ $name>](sync_config_handler, $value) } {
+                            Ok(_) => {}
+                            Err(err) => {
+                                log::warn!(
+                                    "failed to save sync config {:?}: {}",
+                                    stringify!($name),
+                                    err
+                                );
+                            }
+                        }
+                    }
+                    None => {
+                        self.sync_config.$name = $value;
+                        log::warn!(
+                            "failed to save sync config {:?}: no config handler",
+                            stringify!($name)
+                        );
+                    }
+                }
+            };
+        }
+
+        // Selecting an app navigates to its details page
+        let select = matches!(
+            message,
+            Message::SelectCategoryResult(_)
+                | Message::SelectDeveloperResult(_)
+                | Message::SelectFavorite(_)
+                | Message::SelectFeatured(_)
+                | Message::SelectInstalled(_)
+                | Message::SelectPopular(_)
+                | Message::SelectRecentlyAdded(_)
+                | Message::SelectRecentlyUpdated(_)
+                | Message::SelectSearchResult(_)
+                | Message::SelectAppId(_, _)
+                | Message::SelectUpdates(_)
+        );
+        if select {
+            self.nav_push();
+        }
+
+        match message {
+            Message::AppListExport => {
+                if let Some(installed) = &self.installed {
+                    let app_list = AppList::new(installed);
+                    return Command::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || {
+                                let path = app_list_path();
+                                let res = match app_list.save(&path) {
+                                    Ok(()) => Ok(path),
+                                    Err(err) => {
+                                        log::warn!(
+                                            "failed to export app list to {:?}: {}",
+                                            path,
+                                            err
+                                        );
+                                        Err(err.to_string())
+                                    }
+                                };
+                                message::app(Message::AppListExported(res))
+                            })
+                            .await
+                            .unwrap_or(message::none())
+                        },
+                        |x| x,
+                    );
+                }
+            }
+            Message::AppListExported(res) => {
+                self.dialog_pages
+                    .push_back(DialogPage::AppListExported(res));
+            }
+            Message::AppListImport(path) => {
+                return Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            let res = AppList::load(&path).map_err(|err| {
+                                log::warn!("failed to import app list from {:?}: {}", path, err);
+                                err.to_string()
+                            });
+                            message::app(Message::AppListImported(res))
+                        })
+                        .await
+                        .unwrap_or(message::none())
+                    },
+                    |x| x,
+                );
+            }
+            Message::AppListImported(res) => match res {
+                Ok(app_list) => {
+                    let mut missing = Vec::new();
+                    let mut unavailable = 0;
+                    for entry in app_list.apps {
+                        // Prefer the backend the app was exported from
+                        let mut backend_names = self.backend_names();
+                        backend_names.sort_by_key(|x| *x != entry.backend);
+                        if backend_names
+                            .iter()
+                            .any(|backend_name| self.is_installed(*backend_name, &entry.id))
+                        {
+                            continue;
+                        }
+                        let found_opt = backend_names.into_iter().find_map(|backend_name| {
+                            let info = self.backends[backend_name]
+                                .info_cache()
+                                .infos
+                                .get(&entry.id)?;
+                            Some((backend_name, info.clone()))
+                        });
+                        match found_opt {
+                            Some((backend_name, info)) => missing.push(AppListMissing {
+                                backend_name,
+                                id: entry.id,
+                                info,
+                                selected: true,
+                            }),
+                            None => {
+                                log::info!("app {:?} from app list is not available", entry.id);
+                                unavailable += 1;
+                            }
+                        }
+                    }
+                    self.dialog_pages.push_back(DialogPage::AppListImport {
+                        missing,
+                        unavailable,
+                    });
+                }
+                Err(err) => {
+                    self.dialog_pages
+                        .push_back(DialogPage::AppListImportFailed(err));
+                }
+            },
+            Message::AppListImportInstall => {
+                if let Some(DialogPage::AppListImport { missing, .. }) =
+                    self.dialog_pages.pop_front()
+                {
+                    for app in missing.into_iter().filter(|app| app.selected) {
+                        self.operation(Operation {
+                            kind: OperationKind::Install,
+                            backend_name: app.backend_name,
+                            package_id: app.id,
+                            info: app.info,
+                        });
+                    }
+                }
+            }
+            Message::AppListImportToggle(app_i) => {
+                if let Some(DialogPage::AppListImport { missing, .. }) =
+                    self.dialog_pages.front_mut()
+                {
+                    if let Some(app) = missing.get_mut(app_i) {
+                        app.selected = !app.selected;
+                    }
+                }
+            }
+            Message::AppTheme(app_theme) => {
+                sync_config_set!(app_theme, app_theme);
+                return self.update_config();
+            }
+            Message::Density(density) => {
+                sync_config_set!(density, density);
+            }
+            Message::Backends(backends, errors) => {
+                self.backends = backends;
+                self.backends_loaded = true;
+                self.busy.remove(&BusyTask::Backends);
+                self.error_banners_set(ErrorRetry::Backends, errors);
+                self.search_filter_backends = vec![fl!("all-sources")];
+                for backend_name in self.backend_names() {
+                    self.search_filter_backends.push(backend_name.to_string());
+                }
+                self.update_favorites();
+                self.pending_resume();
+                // Descriptions are saved with the appstream cache, which may have changed
+                self.search_descriptions.clear();
+                let mut commands = vec![
+                    self.update_search_descriptions(),
+                    self.update_featured(),
+                    self.update_popular(),
+                    self.update_recent(),
+                    self.update_installed(),
+                    self.update_updates(),
+                ];
+                if let Some(target) = self.target_pending_opt.take() {
+                    commands.push(self.open_target(target));
+                }
+                return Command::batch(commands);
+            }
+            Message::BackendsReload => {
+                return self.update_backends();
+            }
+            Message::Refresh => {
+                let backends = self.backends.clone();
+                return Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            for (backend_name, backend) in backends.iter() {
+                                let start = Instant::now();
+                                match backend.refresh() {
+                                    Ok(()) => {
+                                        let duration = start.elapsed();
+                                        log::info!(
+                                            "refreshed {} metadata in {:?}",
+                                            backend_name,
+                                            duration
+                                        );
+                                    }
+                                    Err(err) => {
+                                        log::warn!(
+                                            "failed to refresh {} metadata: {}",
+                                            backend_name,
+                                            err
+                                        );
+                                    }
+                                }
+                            }
+                            // Reload backends to use the new metadata
+                            message::app(Message::BackendsReload)
+                        })
+                        .await
+                        .unwrap_or(message::none())
+                    },
+                    |x| x,
+                );
+            }
+            Message::ForeignArchitectures(foreign_architectures) => {
+                config_set!(foreign_architectures, foreign_architectures);
+            }
+            Message::FreeSoftwareOnly(free_software_only) => {
+                self.proprietary_shown = false;
+                config_set!(free_software_only, free_software_only);
+                return self.update_featured();
+            }
+            Message::PreferredFormat(preferred_format) => {
+                config_set!(preferred_format, preferred_format);
+                // Lists already shown were deduplicated with the previous preference
+                let mut commands = vec![
+                    self.update_featured(),
+                    self.update_popular(),
+                    self.update_recent(),
+                ];
+                if let Some((category, _)) = self.category_results {
+                    commands.push(self.category(category));
+                }
+                if let Some((developer_name, _)) = &self.developer_results {
+                    commands.push(self.developer(developer_name.clone()));
+                }
+                if self.search_results.is_some() {
+                    commands.push(self.search());
+                }
+                return Command::batch(commands);
+            }
+            Message::ContentAgeLimit(max_content_age) => {
+                return Command::perform(
+                    async move {
+                        match polkit::authorize(polkit::MALCONTENT_ACTION).await {
+                            Ok(true) => message::app(Message::ContentAgeLimitSet(max_content_age)),
+                            Ok(false) => {
+                                log::warn!("not authorized to change the age rating limit");
+                                message::none()
+                            }
+                            Err(err) => {
+                                // The limit is only protected where malcontent is installed
+                                log::info!(
+                                    "failed to check parental controls authorization: {}",
+                                    err
+                                );
+                                message::app(Message::ContentAgeLimitSet(max_content_age))
+                            }
+                        }
+                    },
+                    |x| x,
+                );
+            }
+            Message::OperationsAuthorized(authorized) => {
+                if authorized {
+                    self.operations_authorized = true;
+                    self.operations_start();
+                } else {
+                    let ids: Vec<u64> = self
+                        .pending_operations
+                        .keys()
+                        .filter(|id| !self.running_operations.contains(id))
+                        .copied()
+                        .collect();
+                    for id in ids {
+                        if let Some((op, _)) = self.pending_operations.remove(&id) {
+                            self.failed_operations
+                                .insert(id, (op, fl!("authentication-failed")));
+                            self.dialog_pages.push_back(DialogPage::FailedOperation(id));
+                        }
+                    }
+                    self.pending_save();
+                }
+            }
+            Message::RequireAuth(require_auth) => {
+                if require_auth {
+                    return self.update(Message::RequireAuthSet(require_auth));
+                }
+                // Turning the requirement off needs the same authentication as operations
+                return Command::perform(
+                    async move {
+                        match polkit::authorize(polkit::OPERATION_ACTION).await {
+                            Ok(true) => message::app(Message::RequireAuthSet(require_auth)),
+                            Ok(false) => {
+                                log::warn!("not authorized to stop requiring authentication");
+                                message::none()
+                            }
+                            Err(err) => {
+                                log::warn!("failed to check authorization: {}", err);
+                                message::none()
                             }
                         }
-                    }
-                    None => {
-                        self.config.$name = $value;
-                        log::warn!(
-                            "failed to save config {:?}: no config handler",
-                            stringify!($name)
-                        );
-                    }
+                    },
+                    |x| x,
+                );
+            }
+            Message::RequireAuthSet(require_auth) => {
+                config_set!(require_auth, require_auth);
+                self.operations_start();
+            }
+            Message::ContentAgeLimitSet(max_content_age) => {
+                config_set!(max_content_age, max_content_age);
+                return self.update_featured();
+            }
+            Message::ProprietaryShown(proprietary_shown) => {
+                self.proprietary_shown = proprietary_shown;
+                return self.update_featured();
+            }
+            Message::SystemComponentsShown(system_components_shown) => {
+                self.system_components_shown = system_components_shown;
+            }
+            Message::ParallelOperations(parallel_operations) => {
+                config_set!(parallel_operations, parallel_operations);
+                // Lowering the limit lets running operations finish
+                self.operations_start();
+            }
+            Message::RefreshInterval(refresh_interval) => {
+                config_set!(refresh_interval, refresh_interval);
+            }
+            Message::ErrorDismiss(error_i) => {
+                if error_i < self.error_banners.len() {
+                    self.error_banners.remove(error_i);
                 }
-            };
-        }
-
-        match message {
-            Message::AppTheme(app_theme) => {
-                config_set!(app_theme, app_theme);
-                return self.update_config();
             }
-            Message::Backends(backends) => {
-                self.backends = backends;
-                return Command::batch([self.update_installed(), self.update_updates()]);
+            Message::ErrorRetry(error_i) => {
+                if error_i < self.error_banners.len() {
+                    let (retry, _error) = self.error_banners.remove(error_i);
+                    // Retrying reruns every failed backend, so remove all errors of the same kind
+                    self.error_banners.retain(|(x, _)| *x != retry);
+                    return match retry {
+                        ErrorRetry::Backends => self.update_backends(),
+                        ErrorRetry::Installed => self.update_installed(),
+                    };
+                }
+            }
+            Message::Favorite(id) => {
+                let mut favorites = self.sync_config.favorites.clone();
+                if favorites.contains(&id) {
+                    favorites.retain(|x| x != &id);
+                } else {
+                    favorites.push(id);
+                }
+                sync_config_set!(favorites, favorites);
+                self.update_favorites();
+            }
+            Message::FlathubAdd => {
+                return Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            match process::Command::new("flatpak")
+                                .arg("remote-add")
+                                .arg("--user")
+                                .arg("--if-not-exists")
+                                .arg("flathub")
+                                .arg("https://dl.flathub.org/repo/flathub.flatpakrepo")
+                                .status()
+                            {
+                                Ok(status) if status.success() => {
+                                    message::app(Message::BackendsReload)
+                                }
+                                Ok(status) => {
+                                    log::warn!("failed to add flathub remote: {}", status);
+                                    message::none()
+                                }
+                                Err(err) => {
+                                    log::warn!("failed to run flatpak remote-add: {}", err);
+                                    message::none()
+                                }
+                            }
+                        })
+                        .await
+                        .unwrap_or(message::none())
+                    },
+                    |x| x,
+                );
+            }
+            Message::CacheClear(kind) => {
+                return Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || match kind.clear() {
+                            Ok(()) => message::app(Message::CacheCleared(kind)),
+                            Err(err) => {
+                                log::error!("failed to clear {:?} cache: {}", kind, err);
+                                message::none()
+                            }
+                        })
+                        .await
+                        .unwrap_or(message::none())
+                    },
+                    |x| x,
+                );
+            }
+            Message::CacheCleared(kind) => {
+                // Rebuild caches that the store needs to work
+                let command = match kind {
+                    CacheKind::Appstream => self.update_backends(),
+                    CacheKind::Icons => Command::none(),
+                    CacheKind::Reviews => {
+                        self.ratings.clear();
+                        self.reviews.clear();
+                        self.update_ratings()
+                    }
+                    CacheKind::Screenshots => Command::none(),
+                };
+                return Command::batch([command, self.update_cache_sizes()]);
+            }
+            Message::RepoChanged => {
+                // Apps from the repository are shown after backends reload their metadata
+                return Command::batch([self.update_repos(), self.update_backends()]);
+            }
+            Message::RepoEnable(backend_name, id, enabled) => {
+                let Some(backend) = self.backends.get(backend_name).cloned() else {
+                    return Command::none();
+                };
+                return Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            match backend.repo_enable(&id, enabled) {
+                                Ok(()) => {
+                                    if let Err(err) = backend.refresh() {
+                                        log::warn!(
+                                            "failed to refresh {} metadata: {}",
+                                            backend_name,
+                                            err
+                                        );
+                                    }
+                                }
+                                Err(err) => {
+                                    log::error!(
+                                        "failed to {} repository {}: {}",
+                                        if enabled { "enable" } else { "disable" },
+                                        id,
+                                        err
+                                    );
+                                }
+                            }
+                            message::app(Message::RepoChanged)
+                        })
+                        .await
+                        .unwrap_or(message::none())
+                    },
+                    |x| x,
+                );
+            }
+            Message::Repos(repos) => {
+                self.repos_opt = Some(repos);
+            }
+            Message::Storage(storage) => {
+                self.storage_opt = Some(storage);
+            }
+            Message::StorageRemoveUnused(backend_name) => {
+                // Queued like other operations, so it waits for authorization and running
+                // installs, and failures are shown
+                self.operation(Operation {
+                    kind: OperationKind::RemoveUnused,
+                    backend_name,
+                    package_id: String::new(),
+                    info: Arc::new(AppInfo::package_file(backend_name.to_string())),
+                });
+            }
+            Message::CacheSizes(cache_sizes) => {
+                self.cache_sizes_opt = Some(cache_sizes.into_iter().collect());
             }
-            Message::CategoryResults(category, results) => {
+            Message::CategoryResults(category, mut results) => {
+                self.search_sort.sort(&mut results);
                 self.category_results = Some((category, results));
+                self.results_limit = RESULTS_PAGE_SIZE;
             }
             Message::Config(config) => {
                 if config != self.config {
                     log::info!("update config");
-                    //TODO: update syntax theme by clearing tabs, only if needed
+                    if config.proxy != self.config.proxy {
+                        http::set_proxy(&config.proxy);
+                        self.proxy_input = config.proxy.clone();
+                    }
+                    if config.download_limit != self.config.download_limit {
+                        http::set_download_limit(config.download_limit);
+                    }
+                    let reviews_changed = config.reviews_server != self.config.reviews_server;
+                    let featured_changed = config.free_software_only
+                        != self.config.free_software_only
+                        || config.max_content_age != self.config.max_content_age;
+                    if reviews_changed {
+                        odrs::set_server(config.reviews_server.as_deref());
+                        if let Some(reviews_server) = &config.reviews_server {
+                            self.reviews_server_input = reviews_server.clone();
+                        }
+                        self.ratings.clear();
+                        self.reviews.clear();
+                    }
                     self.config = config;
-                    return self.update_config();
+                    self.key_binds = key_binds(&self.config.key_binds);
+                    let mut commands = Vec::new();
+                    if reviews_changed {
+                        // Cached data came from the old server, ratings reload once it is cleared
+                        commands.push(self.update(Message::CacheClear(CacheKind::Reviews)));
+                    }
+                    if featured_changed {
+                        commands.push(self.update_featured());
+                    }
+                    return Command::batch(commands);
+                }
+            }
+            Message::DetailsTab(id) => {
+                self.details_tab_model.activate(id);
+            }
+            Message::InstallFile(path) => {
+                self.dialog_pages.pop_front();
+                match file_backend_name(&path).filter(|x| self.backends.contains_key(x)) {
+                    Some(backend_name) => {
+                        let name = path
+                            .file_name()
+                            .map_or_else(String::new, |x| x.to_string_lossy().to_string());
+                        self.operation(Operation {
+                            kind: OperationKind::InstallFile,
+                            backend_name,
+                            package_id: path.to_string_lossy().to_string(),
+                            info: Arc::new(AppInfo::package_file(name)),
+                        });
+                    }
+                    None => {
+                        log::warn!("no backend to install {:?}", path);
+                        // Shows that the file is not supported
+                        self.dialog_pages.push_back(DialogPage::InstallFile(path));
+                    }
                 }
             }
             Message::DialogCancel => {
                 self.dialog_pages.pop_front();
+                self.review_error_opt = None;
+            }
+            Message::AppNotFoundSearch(input) => {
+                self.dialog_pages.pop_front();
+                return self.update(Message::SearchRun(input));
             }
-            Message::Installed(installed) => {
+            Message::Featured(mut featured) => {
+                // The app of the day is picked from the apps left after filtering
+                featured.retain(|result| !self.result_hidden(&result.info));
+                self.featured = featured;
+                self.featured_shown = 0;
+            }
+            Message::FeaturedNext => {
+                if !self.featured.is_empty() {
+                    self.featured_shown = (self.featured_shown + 1) % self.featured.len();
+                }
+            }
+            Message::FeaturedScreenshot(url, data) => {
+                self.featured_images
+                    .insert(url, widget::image::Handle::from_memory(data));
+            }
+            Message::FeaturedShown(featured_i) => {
+                if featured_i < self.featured.len() {
+                    self.featured_shown = featured_i;
+                }
+            }
+            Message::Installed(installed, errors) => {
                 self.installed = Some(installed);
                 self.waiting_installed.clear();
+                self.busy.remove(&BusyTask::Installed);
+                self.error_banners_set(ErrorRetry::Installed, errors);
+            }
+            Message::InstalledFilter(installed_filter) => {
+                self.installed_filter = installed_filter;
             }
             Message::Key(modifiers, key) => {
+                if let Some(action) = self.key_bind_capture_opt {
+                    match key {
+                        // Handled by on_escape, which cancels binding
+                        Key::Named(Named::Escape) => {}
+                        Key::Named(Named::Backspace | Named::Delete) if modifiers.is_empty() => {
+                            self.key_bind_capture_opt = None;
+                            let mut config_key_binds = self.config.key_binds.clone();
+                            config_key_binds.remove(&action);
+                            config_set!(key_binds, config_key_binds);
+                            self.key_binds = key_binds(&self.config.key_binds);
+                        }
+                        key => {
+                            if let Some(key_bind) = KeyBind::new(modifiers, key) {
+                                self.key_bind_capture_opt = None;
+                                // A key bind can only be used by one action
+                                let mut config_key_binds = self.config.key_binds.clone();
+                                config_key_binds.retain(|_, x| *x != key_bind);
+                                config_key_binds.insert(action, key_bind);
+                                config_set!(key_binds, config_key_binds);
+                                self.key_binds = key_binds(&self.config.key_binds);
+                            }
+                        }
+                    }
+                    return Command::none();
+                }
+                if self.search_active
+                    && self.search_input.is_empty()
+                    && !self.config.search_history.is_empty()
+                {
+                    // Navigate search history suggestions
+                    let last = self.config.search_history.len() - 1;
+                    match key {
+                        Key::Named(Named::ArrowDown) => {
+                            self.search_history_selected =
+                                Some(match self.search_history_selected {
+                                    Some(i) => cmp::min(i + 1, last),
+                                    None => 0,
+                                });
+                            return Command::none();
+                        }
+                        Key::Named(Named::ArrowUp) => {
+                            self.search_history_selected =
+                                self.search_history_selected.and_then(|i| i.checked_sub(1));
+                            return Command::none();
+                        }
+                        _ => {}
+                    }
+                }
+                if !self.search_active && modifiers.is_empty() {
+                    if let Some(selected) = &mut self.selected_opt {
+                        // Navigate screenshot carousel
+                        match key {
+                            Key::Named(Named::ArrowLeft) => {
+                                selected.screenshot_shown =
+                                    selected.screenshot_shown.saturating_sub(1);
+                                return Command::none();
+                            }
+                            Key::Named(Named::ArrowRight) => {
+                                if selected.screenshot_shown + 1
+                                    < selected
+                                        .info
+                                        .screenshots_for_theme(theme::active().cosmic().is_dark)
+                                        .len()
+                                {
+                                    selected.screenshot_shown += 1;
+                                }
+                                return Command::none();
+                            }
+                            _ => {}
+                        }
+                    }
+                }
                 for (key_bind, action) in self.key_binds.iter() {
                     if key_bind.matches(modifiers, &key) {
                         return self.update(action.message());
                     }
                 }
             }
+            Message::NavBack => match self.nav_back.pop() {
+                Some(state) => {
+                    let current = self.nav_state();
+                    self.nav_forward.push(current);
+                    return self.nav_restore(state);
+                }
+                None => {
+                    // Fall back to closing the details page
+                    self.selected_opt = None;
+                }
+            },
+            Message::NavForward => {
+                if let Some(state) = self.nav_forward.pop() {
+                    let current = self.nav_state();
+                    self.nav_back.push(current);
+                    return self.nav_restore(state);
+                }
+            }
             Message::OpenDesktopId(desktop_id) => {
                 return self.open_desktop_id(desktop_id);
             }
+            Message::CopyLink(url) => {
+                return cosmic::iced::clipboard::write(url);
+            }
+            Message::DesktopEntry(desktop_id, res) => {
+                self.desktop_entries.insert(desktop_id, res);
+            }
+            Message::ShowInFiles(path) => {
+                let dir = path.parent().unwrap_or(&path);
+                match process::Command::new("xdg-open").arg(dir).spawn() {
+                    Ok(_) => {}
+                    Err(err) => {
+                        log::warn!("failed to open {:?}: {}", dir, err);
+                    }
+                }
+            }
+            Message::OpenUrl(url) => match process::Command::new("xdg-open").arg(&url).spawn() {
+                Ok(_) => {}
+                Err(err) => {
+                    log::warn!("failed to open {:?}: {}", url, err);
+                }
+            },
             Message::Operation(kind, backend_name, package_id, info) => {
-                self.operation(Operation {
+                let operation = Operation {
                     kind,
                     backend_name,
                     package_id,
                     info,
-                });
+                };
+                let unsigned = kind == OperationKind::Install
+                    && self.backends.get(backend_name).map_or(false, |backend| {
+                        backend.source_trust(operation.info.origin_opt.as_deref())
+                            == SourceTrust::Unsigned
+                    });
+                let firmware_opt = self
+                    .updates
+                    .as_ref()
+                    .filter(|_| kind == OperationKind::Update)
+                    .and_then(|updates| {
+                        updates.iter().find(|(other_backend_name, package)| {
+                            *other_backend_name == backend_name
+                                && package.id == operation.package_id
+                        })
+                    })
+                    .and_then(|(_, package)| package.firmware_opt.clone());
+                if unsigned {
+                    // Confirm before installing packages that cannot be verified
+                    self.dialog_pages
+                        .push_back(DialogPage::InstallUnsigned(operation));
+                } else if let Some(firmware) = firmware_opt {
+                    // Confirm before flashing firmware, which may need AC power or a restart
+                    self.dialog_pages
+                        .push_back(DialogPage::UpdateFirmware(operation, firmware));
+                } else {
+                    self.operation(operation);
+                }
+            }
+            Message::InstallUnsigned => {
+                if let Some(DialogPage::InstallUnsigned(operation)) = self.dialog_pages.pop_front()
+                {
+                    self.operation(operation);
+                }
+            }
+            Message::ResumeDiscard => {
+                if let Some(DialogPage::ResumeOperations(_)) = self.dialog_pages.pop_front() {
+                    // Forget the saved operations so they are not offered again
+                    self.pending_save();
+                }
+            }
+            Message::ResumeOperations => {
+                if let Some(DialogPage::ResumeOperations(operations)) =
+                    self.dialog_pages.pop_front()
+                {
+                    let mut commands = Vec::with_capacity(operations.len());
+                    for operation in operations {
+                        log::info!(
+                            "resuming {:?} of {:?}",
+                            operation.kind,
+                            operation.package_id
+                        );
+                        // Resumed operations are confirmed like new ones, such as unsigned installs
+                        commands.push(self.update(Message::Operation(
+                            operation.kind,
+                            operation.backend_name,
+                            operation.package_id,
+                            operation.info,
+                        )));
+                    }
+                    return Command::batch(commands);
+                }
+            }
+            Message::UpdateFirmware => {
+                if let Some(DialogPage::UpdateFirmware(operation, _)) =
+                    self.dialog_pages.pop_front()
+                {
+                    self.operation(operation);
+                }
             }
             Message::PendingComplete(id) => {
+                // Desktop files may have been added or removed
+                self.desktop_entries.clear();
+                self.running_operations.remove(&id);
+                let mut commands = Vec::with_capacity(3);
                 if let Some((op, _)) = self.pending_operations.remove(&id) {
-                    self.waiting_installed
-                        .push((op.backend_name, op.package_id.clone()));
-                    self.waiting_updates
-                        .push((op.backend_name, op.package_id.clone()));
+                    self.pending_save();
+                    if op.kind == OperationKind::RemoveUnused {
+                        commands.push(self.update_storage());
+                    } else {
+                        self.waiting_installed
+                            .push((op.backend_name, op.package_id.clone()));
+                        self.waiting_updates
+                            .push((op.backend_name, op.package_id.clone()));
+                    }
                     //TODO: self.complete_operations.insert(id, op);
                 }
-                return Command::batch([self.update_installed(), self.update_updates()]);
+                self.session_reply(id, Ok(()));
+                self.operations_start();
+                commands.push(self.update_installed());
+                commands.push(self.update_updates());
+                return Command::batch(commands);
             }
             Message::PendingError(id, err) => {
                 log::warn!("operation {id} failed: {err}");
+                self.running_operations.remove(&id);
+                self.session_reply(id, Err(err.clone()));
                 if let Some((op, _)) = self.pending_operations.remove(&id) {
+                    self.pending_save();
                     self.failed_operations.insert(id, (op, err));
                     self.dialog_pages.push_back(DialogPage::FailedOperation(id));
                 }
+                self.operations_start();
             }
             Message::PendingProgress(id, new_progress) => {
                 if let Some((_, progress)) = self.pending_operations.get_mut(&id) {
                     *progress = new_progress;
                 }
             }
+            Message::ReviewDraft(new_draft) => {
+                if let Some(DialogPage::WriteReview(draft)) = self.dialog_pages.front_mut() {
+                    *draft = new_draft;
+                }
+            }
+            Message::ReviewSubmit => {
+                if let Some(DialogPage::WriteReview(draft)) = self.dialog_pages.front() {
+                    if !self.review_submitting && draft.is_valid() {
+                        self.review_submitting = true;
+                        self.review_error_opt = None;
+                        let draft = draft.clone();
+                        let locale = self.locale.clone();
+                        return Command::perform(
+                            async move {
+                                let result = odrs::submit(&draft, &locale)
+                                    .await
+                                    .map_err(|err| err.to_string());
+                                message::app(Message::ReviewSubmitted(draft.app_id, result))
+                            },
+                            |x| x,
+                        );
+                    }
+                }
+            }
+            Message::ReviewSubmitted(app_id, result) => {
+                self.review_submitting = false;
+                match result {
+                    Ok(()) => {
+                        if let Some(DialogPage::WriteReview(_)) = self.dialog_pages.front() {
+                            self.dialog_pages.pop_front();
+                        }
+                        // Reviews will be fetched again
+                        self.reviews.remove(&app_id);
+                    }
+                    Err(err) => {
+                        log::warn!("failed to submit review for {:?}: {}", app_id, err);
+                        self.review_error_opt = Some(err);
+                    }
+                }
+            }
+            Message::ReviewVote(app_id, user_skey, review_id, helpful) => {
+                if self.review_votes.insert(review_id) {
+                    return Command::perform(
+                        async move {
+                            if let Err(err) =
+                                odrs::vote(&app_id, &user_skey, review_id, helpful).await
+                            {
+                                log::warn!("failed to vote on review {}: {}", review_id, err);
+                            }
+                            message::none()
+                        },
+                        |x| x,
+                    );
+                }
+            }
+            Message::ReviewWrite => {
+                if let Some(selected) = &self.selected_opt {
+                    let user_skey_opt = self.reviews.get(&selected.id).and_then(|reviews| {
+                        reviews.iter().find_map(|review| review.user_skey.clone())
+                    });
+                    match user_skey_opt {
+                        Some(user_skey) => {
+                            self.review_error_opt = None;
+                            self.dialog_pages
+                                .push_back(DialogPage::WriteReview(OdrsReviewDraft {
+                                    app_id: selected.id.clone(),
+                                    version: selected
+                                        .info
+                                        .releases
+                                        .first()
+                                        .map_or(String::new(), |release| release.version.clone()),
+                                    user_skey,
+                                    ..Default::default()
+                                }));
+                        }
+                        None => {
+                            log::warn!("no ODRS user key for {:?}", selected.id);
+                        }
+                    }
+                }
+            }
+            Message::Reviews(id, reviews) => {
+                self.reviews.insert(id, reviews);
+            }
+            Message::Scroll(scroll_offset) => {
+                self.scroll_offset = scroll_offset;
+            }
             Message::SearchActivate => {
+                self.nav_push();
                 self.selected_opt = None;
                 self.search_active = true;
+                self.search_history_selected = None;
                 return widget::text_input::focus(self.search_id.clone());
             }
             Message::SearchClear => {
@@ -956,6 +5959,114 @@ impl Application for App {
                 self.search_input.clear();
                 self.search_results = None;
             }
+            Message::Popular(popular) => {
+                self.popular = popular;
+            }
+            Message::NetworkRetry => {
+                self.offline = false;
+                return self.update_ratings();
+            }
+            Message::Offline => {
+                self.offline = true;
+            }
+            Message::Ratings(ratings) => {
+                self.ratings = ratings;
+                self.offline = false;
+                return self.update_popular();
+            }
+            Message::Recent(recently_added, recently_updated) => {
+                self.recently_added = recently_added;
+                self.recently_updated = recently_updated;
+            }
+            Message::NavPage(nav_page) => {
+                let entity_opt = self
+                    .nav_model
+                    .iter()
+                    .find(|entity| self.nav_model.data::<NavPage>(*entity) == Some(&nav_page));
+                if let Some(entity) = entity_opt {
+                    return self.on_nav_select(entity);
+                }
+            }
+            Message::OpenTarget(target) => {
+                let mut commands = vec![window::gain_focus(window::Id::MAIN)];
+                if self.backends_loaded {
+                    commands.push(self.open_target(target));
+                } else {
+                    self.target_pending_opt = Some(target);
+                }
+                return Command::batch(commands);
+            }
+            Message::SessionRequest(target, reply) => {
+                // Only one request is shown at a time, earlier ones that did not lead to an
+                // install are cancelled
+                self.session_requests.retain(|(_, id_opt, reply)| {
+                    if id_opt.is_none() {
+                        reply.send(Err("replaced by a newer request".to_string()));
+                    }
+                    id_opt.is_some()
+                });
+                self.session_requests.push((target.clone(), None, reply));
+                return self.update(Message::OpenTarget(target));
+            }
+            Message::ResultsLoadMore => {
+                self.results_limit += RESULTS_PAGE_SIZE;
+            }
+            Message::Developer(developer_name) => {
+                self.nav_push();
+                self.selected_opt = None;
+                self.search_active = false;
+                self.search_results = None;
+                self.developer_results = Some((developer_name.clone(), None));
+                return Command::batch([self.nav_scroll_top(), self.developer(developer_name)]);
+            }
+            Message::DeveloperResults(developer_name, results) => {
+                if let Some((current_developer_name, current_results)) = &mut self.developer_results
+                {
+                    if current_developer_name == &developer_name {
+                        *current_results = Some(results);
+                    }
+                }
+            }
+            Message::SearchDescriptions(backend_name, descriptions) => {
+                if self.search_filter.description {
+                    self.search_descriptions.insert(backend_name, descriptions);
+                    if !self.search_input.is_empty() {
+                        return self.search();
+                    }
+                }
+            }
+            Message::SearchFilter(search_filter) => {
+                let description_changed =
+                    search_filter.description != self.search_filter.description;
+                self.search_filter = search_filter;
+                if description_changed {
+                    if self.search_filter.description {
+                        // Search runs again as descriptions are loaded
+                        return self.update_search_descriptions();
+                    }
+                    self.search_descriptions.clear();
+                    // Only changing the matched fields requires searching again
+                    if !self.search_input.is_empty() {
+                        return self.search();
+                    }
+                }
+            }
+            Message::SearchHistory(history_i) => {
+                if let Some(input) = self.config.search_history.get(history_i).cloned() {
+                    self.nav_push();
+                    self.search_active = true;
+                    self.search_history_selected = None;
+                    self.search_input = input;
+                    return Command::batch([
+                        widget::text_input::focus(self.search_id.clone()),
+                        self.search(),
+                    ]);
+                }
+            }
+            Message::SearchHistoryClear => {
+                self.search_history_selected = None;
+                config_set!(search_history, Vec::new());
+            }
             Message::SearchInput(input) => {
                 if input != self.search_input {
                     self.search_input = input;
@@ -965,9 +6076,25 @@ impl Application for App {
                     }
                 }
             }
-            Message::SearchResults(input, results) => {
+            Message::SearchResults(input, backend_name, mut results, suggestion_opt) => {
                 if input == self.search_input {
-                    self.search_results = Some((input, results));
+                    match &mut self.search_results {
+                        Some((old_input, old_results)) if old_input == &input => {
+                            // Merge with results from other backends for the same input
+                            old_results.retain(|result| result.backend_name != backend_name);
+                            old_results.append(&mut results);
+                            self.search_sort.sort(old_results);
+                            if self.search_suggestion_opt.is_none() {
+                                self.search_suggestion_opt = suggestion_opt;
+                            }
+                        }
+                        _ => {
+                            self.search_sort.sort(&mut results);
+                            self.search_results = Some((input, results));
+                            self.search_suggestion_opt = suggestion_opt;
+                            self.results_limit = RESULTS_PAGE_SIZE;
+                        }
+                    }
                 } else {
                     log::warn!(
                         "received {} results for {:?} after search changed to {:?}",
@@ -977,8 +6104,45 @@ impl Application for App {
                     );
                 }
             }
+            Message::SearchPin(input) => {
+                let mut pinned_searches = self.sync_config.pinned_searches.clone();
+                if pinned_searches.contains(&input) {
+                    pinned_searches.retain(|x| x != &input);
+                } else {
+                    pinned_searches.push(input);
+                }
+                sync_config_set!(pinned_searches, pinned_searches);
+            }
+            Message::SearchRun(input) => {
+                self.nav_push();
+                self.selected_opt = None;
+                self.search_active = true;
+                self.search_input = input;
+                return Command::batch([
+                    widget::text_input::focus(self.search_id.clone()),
+                    self.search(),
+                ]);
+            }
+            Message::SearchSort(search_sort) => {
+                self.search_sort = search_sort;
+                if let Some((_category, results)) = &mut self.category_results {
+                    search_sort.sort(results);
+                }
+                if let Some((_input, results)) = &mut self.search_results {
+                    search_sort.sort(results);
+                }
+            }
             Message::SearchSubmit => {
-                if !self.search_input.is_empty() {
+                if self.search_input.is_empty() {
+                    if let Some(history_i) = self.search_history_selected {
+                        return self.update(Message::SearchHistory(history_i));
+                    }
+                } else {
+                    let mut search_history = self.config.search_history.clone();
+                    search_history.retain(|x| x != &self.search_input);
+                    search_history.insert(0, self.search_input.clone());
+                    search_history.truncate(SEARCH_HISTORY_LEN);
+                    config_set!(search_history, search_history);
                     return self.search();
                 }
             }
@@ -996,6 +6160,11 @@ impl Application for App {
                                 info: package.info,
                                 screenshot_images: HashMap::new(),
                                 screenshot_shown: 0,
+                                releases_expanded: false,
+                                content_rating_expanded: false,
+                                sources: Vec::new(),
+                                source_names: Vec::new(),
+                                lightbox: false,
                             });
                         }
                         None => {
@@ -1021,19 +6190,45 @@ impl Application for App {
                                 info: package.info,
                                 screenshot_images: HashMap::new(),
                                 screenshot_shown: 0,
-                            });
+                                releases_expanded: false,
+                                content_rating_expanded: false,
+                                sources: Vec::new(),
+                                source_names: Vec::new(),
+                                lightbox: false,
+                            });
+                        }
+                        None => {
+                            log::error!("failed to find updates package with index {}", updates_i);
+                        }
+                    }
+                }
+            }
+            Message::SelectCategoryResult(result_i) => {
+                if let Some((_category, results)) = &self.category_results {
+                    match results.get(result_i) {
+                        Some(result) => {
+                            self.selected_opt = Some(Selected {
+                                backend_name: result.backend_name,
+                                id: result.id.clone(),
+                                icon: result.icon.clone(),
+                                info: result.info.clone(),
+                                screenshot_images: HashMap::new(),
+                                screenshot_shown: 0,
+                                releases_expanded: false,
+                                content_rating_expanded: false,
+                                sources: Vec::new(),
+                                source_names: Vec::new(),
+                                lightbox: false,
+                            })
                         }
                         None => {
-                            log::error!("failed to find updates package with index {}", updates_i);
+                            log::error!("failed to find category result with index {}", result_i);
                         }
                     }
                 }
             }
-            Message::SelectNone => {
-                self.selected_opt = None;
-            }
-            Message::SelectCategoryResult(result_i) => {
-                if let Some((_category, results)) = &self.category_results {
+            Message::SelectDeveloperResult(result_i) => {
+                if let Some((_developer_name, Some(results))) = &self.developer_results {
                     match results.get(result_i) {
                         Some(result) => {
                             self.selected_opt = Some(Selected {
@@ -1043,14 +6238,122 @@ impl Application for App {
                                 info: result.info.clone(),
                                 screenshot_images: HashMap::new(),
                                 screenshot_shown: 0,
+                                releases_expanded: false,
+                                content_rating_expanded: false,
+                                sources: Vec::new(),
+                                source_names: Vec::new(),
+                                lightbox: false,
                             })
                         }
                         None => {
-                            log::error!("failed to find category result with index {}", result_i);
+                            log::error!("failed to find developer result with index {}", result_i);
                         }
                     }
                 }
             }
+            Message::SelectFeatured(featured_i) => match self.featured.get(featured_i) {
+                Some(result) => {
+                    self.selected_opt = Some(Selected {
+                        backend_name: result.backend_name,
+                        id: result.id.clone(),
+                        icon: result.icon.clone(),
+                        info: result.info.clone(),
+                        screenshot_images: HashMap::new(),
+                        screenshot_shown: 0,
+                        releases_expanded: false,
+                        content_rating_expanded: false,
+                        sources: Vec::new(),
+                        source_names: Vec::new(),
+                        lightbox: false,
+                    });
+                }
+                None => {
+                    log::error!("failed to find featured app with index {}", featured_i);
+                }
+            },
+            Message::SelectFavorite(result_i) => match self.favorites.get(result_i) {
+                Some(result) => {
+                    self.selected_opt = Some(Selected {
+                        backend_name: result.backend_name,
+                        id: result.id.clone(),
+                        icon: result.icon.clone(),
+                        info: result.info.clone(),
+                        screenshot_images: HashMap::new(),
+                        screenshot_shown: 0,
+                        releases_expanded: false,
+                        content_rating_expanded: false,
+                        sources: Vec::new(),
+                        source_names: Vec::new(),
+                        lightbox: false,
+                    });
+                }
+                None => {
+                    log::error!("failed to find favorite app with index {}", result_i);
+                }
+            },
+            Message::SelectPopular(result_i) => match self.popular.get(result_i) {
+                Some(result) => {
+                    self.selected_opt = Some(Selected {
+                        backend_name: result.backend_name,
+                        id: result.id.clone(),
+                        icon: result.icon.clone(),
+                        info: result.info.clone(),
+                        screenshot_images: HashMap::new(),
+                        screenshot_shown: 0,
+                        releases_expanded: false,
+                        content_rating_expanded: false,
+                        sources: Vec::new(),
+                        source_names: Vec::new(),
+                        lightbox: false,
+                    });
+                }
+                None => {
+                    log::error!("failed to find popular app with index {}", result_i);
+                }
+            },
+            Message::SelectRecentlyAdded(result_i) => match self.recently_added.get(result_i) {
+                Some(result) => {
+                    self.selected_opt = Some(Selected {
+                        backend_name: result.backend_name,
+                        id: result.id.clone(),
+                        icon: result.icon.clone(),
+                        info: result.info.clone(),
+                        screenshot_images: HashMap::new(),
+                        screenshot_shown: 0,
+                        releases_expanded: false,
+                        content_rating_expanded: false,
+                        sources: Vec::new(),
+                        source_names: Vec::new(),
+                        lightbox: false,
+                    });
+                }
+                None => {
+                    log::error!("failed to find recently added app with index {}", result_i);
+                }
+            },
+            Message::SelectRecentlyUpdated(result_i) => match self.recently_updated.get(result_i) {
+                Some(result) => {
+                    self.selected_opt = Some(Selected {
+                        backend_name: result.backend_name,
+                        id: result.id.clone(),
+                        icon: result.icon.clone(),
+                        info: result.info.clone(),
+                        screenshot_images: HashMap::new(),
+                        screenshot_shown: 0,
+                        releases_expanded: false,
+                        content_rating_expanded: false,
+                        sources: Vec::new(),
+                        source_names: Vec::new(),
+                        lightbox: false,
+                    });
+                }
+                None => {
+                    log::error!(
+                        "failed to find recently updated app with index {}",
+                        result_i
+                    );
+                }
+            },
             Message::SelectSearchResult(result_i) => {
                 if let Some((_input, results)) = &self.search_results {
                     match results.get(result_i) {
@@ -1062,6 +6365,11 @@ impl Application for App {
                                 info: result.info.clone(),
                                 screenshot_images: HashMap::new(),
                                 screenshot_shown: 0,
+                                releases_expanded: false,
+                                content_rating_expanded: false,
+                                sources: Vec::new(),
+                                source_names: Vec::new(),
+                                lightbox: false,
                             })
                         }
                         None => {
@@ -1070,6 +6378,52 @@ impl Application for App {
                     }
                 }
             }
+            Message::SelectAppId(backend_name_opt, id) => {
+                // Component IDs may or may not include the .desktop suffix
+                let mut ids = vec![id.clone()];
+                match id.strip_suffix(".desktop") {
+                    Some(stripped) => ids.push(stripped.to_string()),
+                    None => ids.push(format!("{}.desktop", id)),
+                }
+                let mut backend_names = self.backend_names();
+                if let Some(backend_name) = backend_name_opt {
+                    backend_names.sort_by_key(|x| *x != backend_name);
+                }
+                let found_opt = backend_names.into_iter().find_map(|backend_name| {
+                    let appstream_cache = self.backends[backend_name].info_cache();
+                    ids.iter().find_map(|id| {
+                        let info = appstream_cache.infos.get(id)?;
+                        Some((
+                            backend_name,
+                            id.clone(),
+                            appstream_cache.icon(id, info),
+                            info.clone(),
+                        ))
+                    })
+                });
+                match found_opt {
+                    Some((backend_name, id, icon, info)) => {
+                        self.search_active = false;
+                        self.selected_opt = Some(Selected {
+                            backend_name,
+                            id,
+                            icon,
+                            info,
+                            screenshot_images: HashMap::new(),
+                            screenshot_shown: 0,
+                            releases_expanded: false,
+                            content_rating_expanded: false,
+                            sources: Vec::new(),
+                            source_names: Vec::new(),
+                            lightbox: false,
+                        });
+                    }
+                    None => {
+                        log::warn!("failed to find app with ID {:?}", id);
+                        self.dialog_pages.push_back(DialogPage::AppNotFound(id));
+                    }
+                }
+            }
             Message::SelectedScreenshot(i, url, data) => {
                 if let Some(selected) = &mut self.selected_opt {
                     if let Some(screenshot) = selected.info.screenshots.get(i) {
@@ -1081,11 +6435,248 @@ impl Application for App {
                     }
                 }
             }
+            Message::SelectedContentRatingExpanded(content_rating_expanded) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    selected.content_rating_expanded = content_rating_expanded;
+                }
+            }
+            Message::SelectedReleasesExpanded(releases_expanded) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    selected.releases_expanded = releases_expanded;
+                }
+            }
+            Message::SelectedLightbox(lightbox) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    selected.lightbox = lightbox;
+                }
+            }
             Message::SelectedScreenshotShown(i) => {
                 if let Some(selected) = &mut self.selected_opt {
                     selected.screenshot_shown = i;
                 }
             }
+            Message::SelectedSource(i) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    match selected.sources.get(i).cloned() {
+                        Some(source) => {
+                            selected.backend_name = source.backend_name;
+                            selected.icon = source.icon;
+                            selected.info = source.info;
+                            selected.screenshot_images.clear();
+                            selected.screenshot_shown = 0;
+                        }
+                        None => {
+                            log::error!("failed to find source with index {}", i);
+                        }
+                    }
+                }
+            }
+            Message::SyncConfig(sync_config) => {
+                if sync_config != self.sync_config {
+                    log::info!("update sync config");
+                    self.sync_config = sync_config;
+                    self.update_favorites();
+                    return self.update_config();
+                }
+            }
+            Message::SyncExport => {
+                let backends = self.backends.clone();
+                let backend_names = self.backend_names();
+                let config = self.config.clone();
+                let sync_config = self.sync_config.clone();
+                return Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            let mut repos = Vec::new();
+                            for backend_name in backend_names {
+                                match backends[backend_name].repos() {
+                                    Ok(backend_repos) => {
+                                        for repo in backend_repos {
+                                            repos.push(SettingsRepo {
+                                                backend: backend_name.to_string(),
+                                                id: repo.id,
+                                                enabled: repo.enabled,
+                                            });
+                                        }
+                                    }
+                                    Err(err) => {
+                                        log::warn!(
+                                            "failed to list repositories of {}: {}",
+                                            backend_name,
+                                            err
+                                        );
+                                    }
+                                }
+                            }
+                            let settings_file = SettingsFile::new(&config, &sync_config, repos);
+                            let path = sync_file_path();
+                            let res = match settings_file.save(&path) {
+                                Ok(()) => Ok(path),
+                                Err(err) => {
+                                    log::warn!("failed to export settings to {:?}: {}", path, err);
+                                    Err(err.to_string())
+                                }
+                            };
+                            message::app(Message::SyncExported(res))
+                        })
+                        .await
+                        .unwrap_or(message::none())
+                    },
+                    |x| x,
+                );
+            }
+            Message::SyncExported(res) => {
+                self.dialog_pages.push_back(DialogPage::SyncExported(res));
+            }
+            Message::SyncImport(path) => {
+                return Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            let res = SettingsFile::load(&path).map_err(|err| {
+                                log::warn!("failed to import settings from {:?}: {}", path, err);
+                                err.to_string()
+                            });
+                            message::app(Message::SyncImported(res))
+                        })
+                        .await
+                        .unwrap_or(message::none())
+                    },
+                    |x| x,
+                );
+            }
+            Message::SyncImported(res) => match res {
+                Ok(settings_file) => {
+                    let SettingsFile {
+                        sync_config,
+                        config_opt,
+                        repos,
+                        ..
+                    } = settings_file;
+                    match &self.sync_config_handler {
+                        Some(sync_config_handler) => {
+                            if let Err(err) = sync_config.write_entry(sync_config_handler) {
+                                log::warn!("failed to save sync config: {}", err);
+                            }
+                        }
+                        None => {
+                            log::warn!("failed to save sync config: no config handler");
+                        }
+                    }
+                    self.sync_config = sync_config;
+                    self.update_favorites();
+                    let mut commands = vec![self.update_config()];
+                    if let Some(config) = config_opt {
+                        let config = self.config.imported(config);
+                        match &self.config_handler {
+                            Some(config_handler) => {
+                                if let Err(err) = config.write_entry(config_handler) {
+                                    log::warn!("failed to save config: {}", err);
+                                }
+                            }
+                            None => {
+                                log::warn!("failed to save config: no config handler");
+                            }
+                        }
+                        self.catalog_locale_input =
+                            config.catalog_locale.clone().unwrap_or_default();
+                        let locale = config
+                            .catalog_locale
+                            .clone()
+                            .unwrap_or_else(|| self.system_locale.clone());
+                        commands.push(self.update(Message::Config(config)));
+                        if locale != self.locale {
+                            self.locale = locale;
+                            commands.push(self.update_backends());
+                        }
+                    }
+                    if !repos.is_empty() {
+                        commands.push(self.import_repos(repos));
+                    }
+                    return Command::batch(commands);
+                }
+                Err(err) => {
+                    self.dialog_pages
+                        .push_back(DialogPage::SyncImportFailed(err));
+                }
+            },
+            Message::SyncRepos(changes) => {
+                // Sources are only changed after confirmation, as they decide what can be installed
+                self.dialog_pages.push_back(DialogPage::SyncRepos(changes));
+            }
+            Message::SyncReposApply => {
+                let Some(DialogPage::SyncRepos(changes)) = self.dialog_pages.pop_front() else {
+                    return Command::none();
+                };
+                let backends = self.backends.clone();
+                return Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            let mut changed_backends = Vec::new();
+                            for (backend_name, repo) in changes {
+                                let Some(backend) = backends.get(backend_name) else {
+                                    continue;
+                                };
+                                match backend.repo_enable(&repo.id, repo.enabled) {
+                                    Ok(()) => {
+                                        if !changed_backends.contains(&backend_name) {
+                                            changed_backends.push(backend_name);
+                                        }
+                                    }
+                                    Err(err) => {
+                                        log::error!(
+                                            "failed to {} repository {}: {}",
+                                            if repo.enabled { "enable" } else { "disable" },
+                                            repo.id,
+                                            err
+                                        );
+                                    }
+                                }
+                            }
+                            if changed_backends.is_empty() {
+                                return message::none();
+                            }
+                            for backend_name in changed_backends {
+                                if let Err(err) = backends[backend_name].refresh() {
+                                    log::warn!(
+                                        "failed to refresh {} metadata: {}",
+                                        backend_name,
+                                        err
+                                    );
+                                }
+                            }
+                            message::app(Message::RepoChanged)
+                        })
+                        .await
+                        .unwrap_or(message::none())
+                    },
+                    |x| x,
+                );
+            }
+            Message::Escape => {
+                return self.on_escape();
+            }
+            Message::InstallSelected => {
+                if let Some(selected) = &self.selected_opt {
+                    if selected.info.arch_supported()
+                        && !self.is_installed(selected.backend_name, &selected.id)
+                    {
+                        return self.update(Message::Operation(
+                            OperationKind::Install,
+                            selected.backend_name,
+                            selected.id.clone(),
+                            selected.info.clone(),
+                        ));
+                    }
+                }
+            }
+            Message::KeyBindCapture(action) => {
+                self.key_bind_capture_opt = Some(action);
+            }
+            Message::KeyBindsReset => {
+                self.key_bind_capture_opt = None;
+                config_set!(key_binds, default_key_binds());
+                self.key_binds = key_binds(&self.config.key_binds);
+            }
             Message::SystemThemeModeChange(_theme_mode) => {
                 return self.update_config();
             }
@@ -1098,14 +6689,215 @@ impl Application for App {
                     self.core.window.show_context = true;
                 }
                 self.set_context_title(context_page.title());
+                if self.core.window.show_context {
+                    match self.context_page {
+                        ContextPage::SearchFilter => {}
+                        ContextPage::Settings => return self.update_cache_sizes(),
+                        ContextPage::Sources => return self.update_repos(),
+                        ContextPage::Storage => {
+                            return Command::batch([
+                                self.update_storage(),
+                                self.update_cache_sizes(),
+                            ]);
+                        }
+                    }
+                }
             }
             Message::Updates(updates) => {
+                let updates_len = updates.len();
+                let updates_len_old = self.updates.as_ref().map_or(0, |x| x.len());
+                if self.config.notifications && updates_len > updates_len_old {
+                    // Mention apps by name, skipping runtimes and other system components
+                    let names: Vec<_> = updates
+                        .iter()
+                        .filter(|(_, package)| !package.info.is_system_component())
+                        .map(|(_, package)| package.info.name.as_str())
+                        .collect();
+                    let body = if names.len() > 3 {
+                        fl!(
+                            "updates-notification-more",
+                            apps = names[..3].join(", "),
+                            count = names.len() - 3
+                        )
+                    } else {
+                        names.join(", ")
+                    };
+                    self.updates_notification_opt =
+                        Some((fl!("updates-notification", count = updates_len), body));
+                }
                 self.updates = Some(updates);
                 self.waiting_updates.clear();
+                self.busy.remove(&BusyTask::Updates);
+
+                // Show number of updates on the nav item
+                let title = if updates_len > 0 {
+                    fl!("updates-count", count = updates_len)
+                } else {
+                    NavPage::Updates.title()
+                };
+                let entities: Vec<_> = self.nav_model.iter().collect();
+                for entity in entities {
+                    if self.nav_model.data::<NavPage>(entity) == Some(&NavPage::Updates) {
+                        self.nav_model.text_set(entity, title.clone());
+                    }
+                }
+            }
+            Message::UpdatesExpand(backend_name, id) => {
+                let key = (backend_name, id);
+                if !self.updates_expanded.remove(&key) {
+                    self.updates_expanded.insert(key);
+                }
+            }
+            Message::UpdateAll => {
+                if let Some(updates) = &self.updates {
+                    let mut operations = Vec::with_capacity(updates.len());
+                    for (backend_name, package) in updates.iter() {
+                        // Firmware is only updated after confirmation
+                        if package.firmware_opt.is_some() {
+                            continue;
+                        }
+                        let pending = self.pending_operations.values().any(|(op, _)| {
+                            op.backend_name == *backend_name && op.package_id == package.id
+                        });
+                        if !pending {
+                            operations.push(Operation {
+                                kind: OperationKind::Update,
+                                backend_name,
+                                package_id: package.id.clone(),
+                                info: package.info.clone(),
+                            });
+                        }
+                    }
+                    for operation in operations {
+                        self.operation(operation);
+                    }
+                }
+            }
+            Message::UpdatesNotifications(notifications) => {
+                config_set!(notifications, notifications);
+            }
+            Message::UpdatesOpen => {
+                self.updates_notification_opt = None;
+                return Command::batch([
+                    window::gain_focus(window::Id::MAIN),
+                    self.update(Message::NavPage(NavPage::Updates)),
+                ]);
+            }
+            Message::ViewLayout(view_layout) => {
+                sync_config_set!(view_layout, view_layout);
+            }
+            Message::Tray(tray) => {
+                config_set!(tray, tray);
+            }
+            Message::Autostart(autostart) => match autostart::set_enabled(autostart) {
+                Ok(()) => {
+                    self.autostart = autostart;
+                }
+                Err(err) => {
+                    log::warn!("failed to set autostart to {}: {}", autostart, err);
+                }
+            },
+            Message::CatalogLocaleInput(catalog_locale_input) => {
+                self.catalog_locale_input = catalog_locale_input;
+            }
+            Message::CatalogLocaleSubmit => {
+                let catalog_locale = self.catalog_locale_input.trim().to_string();
+                let catalog_locale = if catalog_locale.is_empty() {
+                    None
+                } else {
+                    Some(catalog_locale)
+                };
+                let locale = catalog_locale
+                    .clone()
+                    .unwrap_or_else(|| self.system_locale.clone());
+                config_set!(catalog_locale, catalog_locale);
+                if locale != self.locale {
+                    log::info!(
+                        "catalog locale changed from {:?} to {:?}",
+                        self.locale,
+                        locale
+                    );
+                    self.locale = locale;
+                    // Translated strings are resolved when appstream data is loaded
+                    return self.update_backends();
+                }
+            }
+            Message::ProxyInput(proxy_input) => {
+                self.proxy_input = proxy_input;
+            }
+            Message::ProxySubmit => {
+                let proxy = self.proxy_input.trim().to_string();
+                http::set_proxy(&proxy);
+                config_set!(proxy, proxy);
+                // Requests that failed with the old proxy may succeed now
+                return self.update(Message::NetworkRetry);
+            }
+            Message::DownloadLimit(download_limit) => {
+                http::set_download_limit(download_limit);
+                config_set!(download_limit, download_limit);
+            }
+            Message::ReviewsServer(reviews_server) => {
+                if reviews_server != self.config.reviews_server {
+                    odrs::set_server(reviews_server.as_deref());
+                    self.ratings.clear();
+                    self.reviews.clear();
+                    config_set!(reviews_server, reviews_server);
+                    // Cached data came from the old server, ratings reload once it is cleared
+                    return self.update(Message::CacheClear(CacheKind::Reviews));
+                }
+            }
+            Message::ReviewsServerInput(reviews_server_input) => {
+                self.reviews_server_input = reviews_server_input;
+            }
+            Message::ReviewsServerSubmit => {
+                if self.config.reviews_server.is_some() {
+                    let mut reviews_server = self.reviews_server_input.trim().to_string();
+                    if reviews_server.is_empty() {
+                        reviews_server = odrs::ODRS_URL.to_string();
+                        self.reviews_server_input = reviews_server.clone();
+                    }
+                    return self.update(Message::ReviewsServer(Some(reviews_server)));
+                }
+            }
+            Message::WindowActivate => {
+                return Command::batch([
+                    window::change_mode(window::Id::MAIN, window::Mode::Windowed),
+                    window::gain_focus(window::Id::MAIN),
+                ]);
             }
             Message::WindowClose => {
                 return window::close(window::Id::MAIN);
             }
+            Message::WindowMaximized(maximized) => {
+                // The size of a maximized window is not restored, only the maximized state
+                let window_size = Some((self.window_width as u32, self.window_height as u32));
+                if !maximized && window_size != self.config.window_size {
+                    config_set!(window_size, window_size);
+                }
+                if maximized != self.config.window_maximized {
+                    config_set!(window_maximized, maximized);
+                }
+            }
+            Message::WindowResized(width, height) => {
+                self.window_width = width;
+                self.window_height = height;
+                self.window_resize_id += 1;
+                let window_resize_id = self.window_resize_id;
+                return Command::perform(
+                    async move {
+                        tokio::time::sleep(WINDOW_SAVE_DELAY).await;
+                        message::app(Message::WindowSave(window_resize_id))
+                    },
+                    |x| x,
+                );
+            }
+            Message::WindowSave(window_resize_id) => {
+                if window_resize_id == self.window_resize_id {
+                    return window::fetch_maximized(window::Id::MAIN, |maximized| {
+                        message::app(Message::WindowMaximized(maximized))
+                    });
+                }
+            }
             Message::WindowNew => match env::current_exe() {
                 Ok(exe) => match process::Command::new(&exe).spawn() {
                     Ok(_child) => {}
@@ -1119,6 +6911,10 @@ impl Application for App {
             },
         }
 
+        if select {
+            self.update_selected_sources();
+            return self.nav_scroll_top();
+        }
         Command::none()
     }
 
@@ -1128,7 +6924,10 @@ impl Application for App {
         }
 
         Some(match self.context_page {
+            ContextPage::SearchFilter => self.search_filter_view(),
             ContextPage::Settings => self.settings(),
+            ContextPage::Sources => self.sources_view(),
+            ContextPage::Storage => self.storage_view(),
         })
     }
 
@@ -1152,6 +6951,305 @@ impl Application for App {
                         widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
                     )
             }
+            DialogPage::AppListExported(res) => match res {
+                Ok(path) => widget::dialog(fl!("app-list-exported"))
+                    .body(fl!(
+                        "app-list-exported-body",
+                        path = path.display().to_string()
+                    ))
+                    .icon(widget::icon::from_name("document-save").size(64))
+                    .primary_action(
+                        widget::button::standard(fl!("ok")).on_press(Message::DialogCancel),
+                    ),
+                Err(err) => widget::dialog(fl!("app-list-export-failed"))
+                    .body(err)
+                    .icon(widget::icon::from_name("dialog-error").size(64))
+                    .primary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    ),
+            },
+            DialogPage::AppListImport {
+                missing,
+                unavailable,
+            } => {
+                let cosmic_theme::Spacing { space_xxs, .. } = self.sync_config.density.spacing();
+                let mut column =
+                    widget::column::with_capacity(missing.len() + 1).spacing(space_xxs);
+                for (app_i, app) in missing.iter().enumerate() {
+                    column = column.push(widget::checkbox(
+                        format!("{} ({})", app.info.name, app.backend_name),
+                        app.selected,
+                        move |_| Message::AppListImportToggle(app_i),
+                    ));
+                }
+                if *unavailable > 0 {
+                    column = column.push(widget::text::caption(fl!(
+                        "app-list-unavailable",
+                        count = *unavailable
+                    )));
+                }
+                let selected = missing.iter().filter(|app| app.selected).count();
+                let mut install_button =
+                    widget::button::suggested(fl!("install-count", count = selected));
+                if selected > 0 {
+                    install_button = install_button.on_press(Message::AppListImportInstall);
+                }
+                widget::dialog(fl!("import-app-list"))
+                    .body(if missing.is_empty() {
+                        fl!("app-list-nothing-missing")
+                    } else {
+                        fl!("app-list-missing", count = missing.len())
+                    })
+                    .control(widget::scrollable(column).height(Length::Fixed(240.0)))
+                    .primary_action(install_button)
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::AppListImportFailed(err) => widget::dialog(fl!("app-list-import-failed"))
+                .body(err)
+                .icon(widget::icon::from_name("dialog-error").size(64))
+                .primary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::SyncExported(res) => match res {
+                Ok(path) => widget::dialog(fl!("settings-exported"))
+                    .body(fl!(
+                        "settings-exported-body",
+                        path = path.display().to_string()
+                    ))
+                    .icon(widget::icon::from_name("document-save").size(64))
+                    .primary_action(
+                        widget::button::standard(fl!("ok")).on_press(Message::DialogCancel),
+                    ),
+                Err(err) => widget::dialog(fl!("settings-export-failed"))
+                    .body(err)
+                    .icon(widget::icon::from_name("dialog-error").size(64))
+                    .primary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    ),
+            },
+            DialogPage::AppNotFound(id) => {
+                // Search for the last part of the ID, which is usually the app name
+                let input = id
+                    .trim_end_matches(".desktop")
+                    .rsplit('.')
+                    .next()
+                    .unwrap_or(id)
+                    .to_string();
+                widget::dialog(fl!("app-not-found"))
+                    .body(fl!("app-not-found-body", id = id.as_str()))
+                    .icon(widget::icon::from_name("dialog-warning").size(64))
+                    .primary_action(
+                        widget::button::suggested(fl!("search-instead"))
+                            .on_press(Message::AppNotFoundSearch(input)),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::InstallUnsigned(operation) => {
+                let source = match &operation.info.origin_opt {
+                    Some(origin) => format!("{} ({})", operation.backend_name, origin),
+                    None => operation.backend_name.to_string(),
+                };
+                widget::dialog(fl!("install-unsigned", name = operation.info.name.as_str()))
+                    .body(fl!("install-unsigned-body", source = source))
+                    .icon(widget::icon::from_name("dialog-warning").size(64))
+                    .primary_action(
+                        widget::button::destructive(fl!("install-anyway"))
+                            .on_press(Message::InstallUnsigned),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::ResumeOperations(operations) => {
+                let cosmic_theme::Spacing { space_xxs, .. } = self.sync_config.density.spacing();
+                let mut column = widget::column::with_capacity(operations.len()).spacing(space_xxs);
+                for operation in operations.iter() {
+                    column = column.push(widget::text::body(format!(
+                        "{} ({})",
+                        operation.info.name, operation.backend_name
+                    )));
+                }
+                widget::dialog(fl!("resume-operations"))
+                    .body(fl!("resume-operations-body"))
+                    .control(column)
+                    .primary_action(
+                        widget::button::suggested(fl!("resume"))
+                            .on_press(Message::ResumeOperations),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::ResumeDiscard),
+                    )
+            }
+            DialogPage::UpdateFirmware(operation, firmware) => {
+                let spacing = self.sync_config.density.spacing();
+                let version = operation
+                    .info
+                    .latest_release_opt()
+                    .map_or("", |release| release.version.as_str());
+                widget::dialog(fl!("update-firmware", name = operation.info.name.as_str()))
+                    .body(fl!("update-firmware-body"))
+                    .icon(widget::icon::from_name("dialog-warning").size(64))
+                    .control(firmware_view(firmware, version, &spacing))
+                    .primary_action(
+                        widget::button::suggested(fl!("update")).on_press(Message::UpdateFirmware),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::SyncRepos(changes) => {
+                let cosmic_theme::Spacing { space_xxs, .. } = self.sync_config.density.spacing();
+                let mut column = widget::column::with_capacity(changes.len()).spacing(space_xxs);
+                for (backend_name, repo) in changes.iter() {
+                    let name = format!("{} ({})", repo.name, backend_name);
+                    column = column.push(widget::text::body(if repo.enabled {
+                        fl!("import-repo-enable", name = name)
+                    } else {
+                        fl!("import-repo-disable", name = name)
+                    }));
+                }
+                widget::dialog(fl!("import-repos"))
+                    .body(fl!("import-repos-body"))
+                    .icon(widget::icon::from_name("dialog-warning").size(64))
+                    .control(column)
+                    .primary_action(
+                        widget::button::suggested(fl!("import-repos-apply"))
+                            .on_press(Message::SyncReposApply),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::SyncImportFailed(err) => widget::dialog(fl!("settings-import-failed"))
+                .body(err)
+                .icon(widget::icon::from_name("dialog-error").size(64))
+                .primary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::InstallFile(path) => {
+                let name = path
+                    .file_name()
+                    .map_or_else(String::new, |x| x.to_string_lossy().to_string());
+                match file_backend_name(path).filter(|x| self.backends.contains_key(x)) {
+                    Some(backend_name) => widget::dialog(fl!("install-file", name = name))
+                        .body(if path.extension().map_or(false, |x| x == "flatpakrepo") {
+                            fl!("install-file-repo-body")
+                        } else {
+                            fl!("install-file-body", source = backend_name)
+                        })
+                        .icon(widget::icon::from_name("package-x-generic").size(64))
+                        .primary_action(
+                            widget::button::suggested(fl!("install"))
+                                .on_press(Message::InstallFile(path.clone())),
+                        )
+                        .secondary_action(
+                            widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                        ),
+                    None => widget::dialog(fl!("install-file-unsupported", name = name))
+                        .body(fl!(
+                            "install-file-unsupported-body",
+                            format = path
+                                .extension()
+                                .map_or_else(String::new, |x| x.to_string_lossy().to_string())
+                        ))
+                        .icon(widget::icon::from_name("dialog-error").size(64))
+                        .primary_action(
+                            widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                        ),
+                }
+            }
+            DialogPage::WriteReview(draft) => {
+                let cosmic_theme::Spacing {
+                    space_xxs, space_s, ..
+                } = self.sync_config.density.spacing();
+
+                let mut stars_row = widget::row::with_capacity(5);
+                for stars in 1..=5 {
+                    stars_row = stars_row.push(
+                        widget::button::icon(
+                            widget::icon::from_name(if stars <= draft.stars {
+                                "starred-symbolic"
+                            } else {
+                                "non-starred-symbolic"
+                            })
+                            .size(24),
+                        )
+                        .on_press(Message::ReviewDraft(OdrsReviewDraft {
+                            stars,
+                            ..draft.clone()
+                        })),
+                    );
+                }
+
+                let mut column = widget::column::with_capacity(5)
+                    .spacing(space_s)
+                    .push(stars_row)
+                    .push(
+                        widget::text_input(fl!("review-name"), &draft.user_display).on_input(
+                            move |user_display| {
+                                Message::ReviewDraft(OdrsReviewDraft {
+                                    user_display,
+                                    ..draft.clone()
+                                })
+                            },
+                        ),
+                    )
+                    .push(
+                        widget::column::with_children(vec![
+                            widget::text_input(fl!("review-summary"), &draft.summary)
+                                .on_input(move |summary| {
+                                    Message::ReviewDraft(OdrsReviewDraft {
+                                        summary,
+                                        ..draft.clone()
+                                    })
+                                })
+                                .into(),
+                            widget::text::caption(fl!(
+                                "review-summary-limit",
+                                max = odrs::SUMMARY_MAX
+                            ))
+                            .into(),
+                        ])
+                        .spacing(space_xxs),
+                    )
+                    .push(
+                        widget::column::with_children(vec![
+                            widget::text_input(fl!("review-description"), &draft.description)
+                                .on_input(move |description| {
+                                    Message::ReviewDraft(OdrsReviewDraft {
+                                        description,
+                                        ..draft.clone()
+                                    })
+                                })
+                                .into(),
+                            widget::text::caption(fl!(
+                                "review-description-limit",
+                                min = odrs::DESCRIPTION_MIN,
+                                max = odrs::DESCRIPTION_MAX
+                            ))
+                            .into(),
+                        ])
+                        .spacing(space_xxs),
+                    );
+                if let Some(err) = &self.review_error_opt {
+                    column = column.push(widget::text::body(err));
+                }
+
+                let mut submit_button = widget::button::suggested(fl!("submit"));
+                if draft.is_valid() && !self.review_submitting {
+                    submit_button = submit_button.on_press(Message::ReviewSubmit);
+                }
+                widget::dialog(fl!("write-review"))
+                    .control(column)
+                    .primary_action(submit_button)
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
         };
 
         Some(dialog.into())
@@ -1159,7 +7257,15 @@ impl Application for App {
 
     fn header_start(&self) -> Vec<Element<Message>> {
         vec![if self.search_active {
-            widget::text_input::search_input("", &self.search_input)
+            let placeholder = match self
+                .nav_model
+                .active_data::<NavPage>()
+                .filter(|nav_page| nav_page.category().is_some())
+            {
+                Some(nav_page) => fl!("search-in-category", category = nav_page.title()),
+                None => String::new(),
+            };
+            widget::text_input::search_input(placeholder, &self.search_input)
                 .width(Length::Fixed(240.0))
                 .id(self.search_id.clone())
                 .on_clear(Message::SearchClear)
@@ -1173,9 +7279,26 @@ impl Application for App {
         }]
     }
 
+    fn header_end(&self) -> Vec<Element<Message>> {
+        // Show the first running task, in order of importance
+        let Some(busy_task) = self.busy.iter().min() else {
+            return Vec::new();
+        };
+        let spacing = self.sync_config.density.spacing();
+        vec![widget::row::with_children(vec![
+            widget::icon::from_name("process-working-symbolic")
+                .size(16)
+                .into(),
+            widget::text::caption(busy_task.title()).into(),
+        ])
+        .align_items(Alignment::Center)
+        .spacing(spacing.space_xxs)
+        .into()]
+    }
+
     /// Creates a view after each update.
     fn view(&self) -> Element<Self::Message> {
-        let spacing = theme::active().cosmic().spacing;
+        let spacing = self.sync_config.density.spacing();
         let cosmic_theme::Spacing {
             space_xl,
             space_m,
@@ -1186,6 +7309,13 @@ impl Application for App {
         } = spacing;
 
         let content: Element<_> = match &self.selected_opt {
+            None if self.search_active
+                && self.search_input.is_empty()
+                && !self.config.search_history.is_empty() =>
+            {
+                self.search_history_view()
+            }
+            Some(selected) if selected.lightbox => self.lightbox_view(selected, &spacing),
             Some(selected) => {
                 //TODO: more efficient checks
                 let mut waiting_refresh = false;
@@ -1230,12 +7360,13 @@ impl Application for App {
                     }
                 }
 
+                let narrow = self.narrow();
                 let mut column = widget::column::with_capacity(2)
-                    .padding([0, space_xl])
+                    .padding([0, if narrow { space_s } else { space_xl }])
                     .spacing(space_m)
                     .width(Length::Fill);
-                column = column
-                    .push(widget::button::standard(fl!("back")).on_press(Message::SelectNone));
+                column =
+                    column.push(widget::button::standard(fl!("back")).on_press(Message::NavBack));
                 let mut buttons = Vec::with_capacity(2);
                 if let Some(progress) = progress_opt {
                     //TODO: get height from theme?
@@ -1272,6 +7403,14 @@ impl Application for App {
                             ))
                             .into(),
                     );
+                } else if !selected.info.arch_supported() {
+                    buttons.push(
+                        widget::text::body(fl!(
+                            "arch-unsupported",
+                            arch = app_info::current_arch()
+                        ))
+                        .into(),
+                    );
                 } else {
                     buttons.push(
                         widget::button::suggested(fl!("install"))
@@ -1284,147 +7423,318 @@ impl Application for App {
                             .into(),
                     )
                 }
-                column = column.push(
-                    widget::row::with_children(vec![
-                        widget::icon::icon(selected.icon.clone())
-                            .size(ICON_SIZE_DETAILS)
-                            .into(),
-                        widget::column::with_children(vec![
-                            widget::text::title2(&selected.info.name).into(),
-                            widget::text(&selected.info.summary).into(),
-                            widget::vertical_space(Length::Fixed(space_s.into())).into(),
-                            widget::row::with_children(buttons).spacing(space_xs).into(),
-                        ])
+                buttons.push(
+                    widget::button::standard(if self.sync_config.is_favorite(&selected.id) {
+                        fl!("remove-favorite")
+                    } else {
+                        fl!("add-favorite")
+                    })
+                    .on_press(Message::Favorite(selected.id.clone()))
+                    .into(),
+                );
+                buttons.push(
+                    widget::button::standard(fl!("copy-link"))
+                        .on_press(Message::CopyLink(app_link(selected)))
                         .into(),
-                    ])
-                    .align_items(Alignment::Center)
-                    .spacing(space_m),
                 );
-                //TODO: proper image scroller
-                if let Some(screenshot) = selected.info.screenshots.get(selected.screenshot_shown) {
-                    //TODO: get proper image dimensions
-                    let image_height = Length::Fixed(480.0);
-                    let mut row = widget::row::with_capacity(3).align_items(Alignment::Center);
-                    {
-                        let mut button = widget::button::icon(
-                            widget::icon::from_name("go-previous-symbolic").size(16),
-                        );
-                        if selected.screenshot_shown > 0 {
-                            button = button.on_press(Message::SelectedScreenshotShown(
-                                selected.screenshot_shown - 1,
-                            ));
-                        }
-                        row = row.push(button);
-                    }
-                    let image_element = if let Some(image) =
-                        selected.screenshot_images.get(&selected.screenshot_shown)
-                    {
-                        widget::image(image.clone())
-                            .width(Length::Fill)
-                            .height(image_height)
-                            .into()
+                let buttons: Element<_> = if narrow {
+                    // Wrap buttons instead of truncating them on narrow windows
+                    widget::flex_row(buttons)
+                        .column_spacing(space_xs)
+                        .row_spacing(space_xs)
+                        .into()
+                } else {
+                    widget::row::with_children(buttons).spacing(space_xs).into()
+                };
+                let header_icon = widget::icon::icon(selected.icon.clone()).size(ICON_SIZE_DETAILS);
+                let header_info = widget::column::with_children(vec![
+                    widget::text::title2(&selected.info.name).into(),
+                    if selected.info.developer_name.is_empty() {
+                        widget::Space::with_height(Length::Shrink).into()
                     } else {
-                        widget::Space::new(Length::Fill, image_height).into()
-                    };
-                    row = row.push(
-                        widget::column::with_children(vec![
-                            image_element,
-                            widget::text::caption(&screenshot.caption).into(),
-                        ])
-                        .align_items(Alignment::Center),
+                        widget::button::link(selected.info.developer_name.clone())
+                            .on_press(Message::Developer(selected.info.developer_name.clone()))
+                            .padding(0)
+                            .into()
+                    },
+                    widget::text(&selected.info.summary).into(),
+                    widget::vertical_space(Length::Fixed(space_s.into())).into(),
+                    buttons,
+                ]);
+                if narrow {
+                    column = column.push(
+                        widget::column::with_children(vec![header_icon.into(), header_info.into()])
+                            .spacing(space_s),
                     );
-                    {
-                        let mut button = widget::button::icon(
-                            widget::icon::from_name("go-next-symbolic").size(16),
-                        );
-                        if selected.screenshot_shown + 1 < selected.info.screenshots.len() {
-                            button = button.on_press(Message::SelectedScreenshotShown(
-                                selected.screenshot_shown + 1,
-                            ));
+                } else {
+                    column = column.push(
+                        widget::row::with_children(vec![header_icon.into(), header_info.into()])
+                            .align_items(Alignment::Center)
+                            .spacing(space_m),
+                    );
+                }
+                if let Some(source) = self.source_view(selected, &spacing) {
+                    column = column.push(source);
+                }
+                let mut badges = Vec::with_capacity(4);
+                badges.push(self.sandbox_view(selected, &spacing));
+                if let Some(verified) = self.verified_view(selected, &spacing) {
+                    badges.push(verified);
+                }
+                if let Some(license) = self.license_view(selected, &spacing) {
+                    badges.push(license);
+                }
+                if let Some(content_rating) = self.content_rating_view(selected, &spacing) {
+                    badges.push(content_rating);
+                }
+                if !badges.is_empty() {
+                    if narrow {
+                        column =
+                            column.push(widget::column::with_children(badges).spacing(space_s));
+                    } else {
+                        column = column.push(widget::row::with_children(badges).spacing(space_s));
+                    }
+                }
+                if let Some(categories) = self.app_categories_view(selected, &spacing) {
+                    column = column.push(categories);
+                }
+                column = column.push(
+                    widget::tab_bar::horizontal(&self.details_tab_model)
+                        .on_activate(Message::DetailsTab),
+                );
+                match self
+                    .details_tab_model
+                    .active_data::<DetailsTab>()
+                    .map_or(DetailsTab::Overview, |details_tab| *details_tab)
+                {
+                    DetailsTab::Overview => {
+                        if let Some(urls) = self.urls_view(selected, &spacing) {
+                            column = column.push(urls);
+                        }
+                        column =
+                            column.push(markup_view(&selected.info.description_markup, &spacing));
+                        if let Some(requirements) = self.requirements_view(selected, &spacing) {
+                            column = column.push(requirements);
+                        }
+                        if let Some(desktop_entry) = self.desktop_entry_view(selected, &spacing) {
+                            column = column.push(desktop_entry);
+                        }
+                    }
+                    DetailsTab::Screenshots => {
+                        let videos_opt = self.videos_view(selected, &spacing);
+                        match self.screenshots_view(selected, &spacing) {
+                            Some(screenshots) => {
+                                column = column.push(screenshots);
+                            }
+                            None if videos_opt.is_none() => {
+                                column = column.push(widget::text::body(fl!("no-screenshots")));
+                            }
+                            None => {}
+                        }
+                        if let Some(videos) = videos_opt {
+                            column = column.push(videos);
                         }
-                        row = row.push(button);
                     }
-                    column = column.push(row);
+                    DetailsTab::Reviews => {
+                        column = column.push(self.reviews_view(selected, &spacing));
+                    }
+                    DetailsTab::Releases => {
+                        column = column.push(
+                            self.releases_view(selected, &spacing)
+                                .unwrap_or_else(|| widget::text::body(fl!("no-releases")).into()),
+                        );
+                    }
+                    DetailsTab::Permissions => {
+                        column = column.push(self.permissions_view(selected, &spacing));
+                    }
                 }
-                //TODO: parse markup in description
-                column =
-                    column.push(widget::text::body(&selected.info.description).width(Length::Fill));
-                //TODO: description, releases, etc.
-                widget::scrollable(column).into()
+                self.page_scrollable(column)
             }
             None => match &self.search_results {
                 Some((input, results)) => {
-                    //TODO: paging or dynamic load
-                    let results_len = cmp::min(results.len(), 256);
-
                     let mut column = widget::column::with_capacity(2)
                         .padding([0, space_xl])
                         .spacing(space_xxs)
                         .width(Length::Fill);
                     //TODO: back button?
+                    // Only results shown with the current filters are counted
+                    let shown = self.search_results_shown(results);
+                    let count = shown.len();
                     //TODO: translate
-                    column = column.push(widget::text(format!(
-                        "{} results for {:?}",
-                        results.len(),
-                        input
-                    )));
-                    let mut flex_row = Vec::with_capacity(results_len);
-                    for (result_i, result) in results.iter().take(results_len).enumerate() {
+                    let results_text = widget::text(match self.search_category() {
+                        Some(category) => format!(
+                            "{} results for {:?} in category {:?}",
+                            count, input, category
+                        ),
+                        None => format!("{} results for {:?}", count, input),
+                    });
+                    column = column.push(
+                        widget::row::with_children(vec![
+                            results_text.width(Length::Fill).into(),
+                            widget::button::standard(
+                                if self.sync_config.pinned_searches.contains(input) {
+                                    fl!("unpin-search")
+                                } else {
+                                    fl!("pin-search")
+                                },
+                            )
+                            .on_press(Message::SearchPin(input.clone()))
+                            .into(),
+                            self.search_sort_dropdown(),
+                            self.view_layout_button(),
+                            widget::button::standard(fl!("search-filters"))
+                                .on_press(Message::ToggleContextPage(ContextPage::SearchFilter))
+                                .into(),
+                        ])
+                        .push_maybe(self.proprietary_button())
+                        .align_items(Alignment::Center),
+                    );
+                    let mut flex_row = Vec::with_capacity(cmp::min(count, self.results_limit));
+                    let mut has_more = false;
+                    for (result_i, result) in shown {
+                        if flex_row.len() >= self.results_limit {
+                            has_more = true;
+                            break;
+                        }
                         flex_row.push(
-                            widget::mouse_area(result.card_view(&spacing))
+                            widget::mouse_area(self.result_view(result, &spacing))
                                 .on_press(Message::SelectSearchResult(result_i))
                                 .into(),
                         );
                     }
-                    column = column.push(
-                        widget::flex_row(flex_row)
-                            .column_spacing(space_xxs)
-                            .row_spacing(space_xxs),
-                    );
-                    widget::scrollable(column).into()
+                    column = column.push(self.results_layout(flex_row, &spacing));
+                    if has_more {
+                        column = column.push(self.load_more_button());
+                    }
+                    if count == 0 {
+                        column = column.push(empty_state_view(
+                            "system-search-symbolic",
+                            fl!("no-results"),
+                            fl!("no-results-body"),
+                            Some((fl!("clear-search"), Message::SearchClear)),
+                            &spacing,
+                        ));
+                        if let Some(suggestion) = &self.search_suggestion_opt {
+                            column = column.push(
+                                widget::button::link(fl!(
+                                    "did-you-mean",
+                                    suggestion = suggestion.as_str()
+                                ))
+                                .on_press(Message::SearchInput(suggestion.clone())),
+                            );
+                        }
+                    }
+                    self.page_scrollable(column)
                 }
+                None if self.developer_results.is_some() => self.developer_view(&spacing),
                 None => match self
                     .nav_model
                     .active_data::<NavPage>()
-                    .map_or(NavPage::Installed, |nav_page| *nav_page)
+                    .map_or(NavPage::Explore, |nav_page| *nav_page)
                 {
+                    NavPage::Explore => self.explore_view(),
                     NavPage::Installed => match &self.installed {
+                        Some(installed) if installed.is_empty() => {
+                            self.page_scrollable(empty_state_view(
+                                "applications-other-symbolic",
+                                fl!("no-installed"),
+                                fl!("no-installed-body"),
+                                Some((fl!("explore-apps"), Message::NavPage(NavPage::Explore))),
+                                &spacing,
+                            ))
+                        }
                         Some(installed) => {
                             let mut column = widget::column::with_capacity(2)
                                 .padding([0, space_xl])
                                 .spacing(space_xxs)
                                 .width(Length::Fill);
+                            // Native backends return many packages that are not apps
+                            let installed_shown = |package: &Package| {
+                                self.system_components_shown || !package.info.is_system_component()
+                            };
+                            let installed_count = installed
+                                .iter()
+                                .filter(|(_, package)| installed_shown(package))
+                                .count();
                             //TODO: translate
-                            column = column.push(widget::text(format!(
-                                "{} installed applications",
-                                installed.len(),
-                            )));
+                            column = column.push(
+                                widget::row::with_children(vec![
+                                    widget::text(format!(
+                                        "{} installed applications",
+                                        installed_count,
+                                    ))
+                                    .width(Length::Fill)
+                                    .into(),
+                                    widget::text_input::search_input(
+                                        fl!("filter-installed"),
+                                        &self.installed_filter,
+                                    )
+                                    .width(Length::Fixed(240.0))
+                                    .on_clear(Message::InstalledFilter(String::new()))
+                                    .on_input(Message::InstalledFilter)
+                                    .into(),
+                                    widget::button::standard(if self.system_components_shown {
+                                        fl!("hide-system-packages")
+                                    } else {
+                                        fl!("show-system-packages")
+                                    })
+                                    .on_press(Message::SystemComponentsShown(
+                                        !self.system_components_shown,
+                                    ))
+                                    .into(),
+                                    widget::button::standard(fl!("storage"))
+                                        .on_press(Message::ToggleContextPage(ContextPage::Storage))
+                                        .into(),
+                                    self.view_layout_button(),
+                                ])
+                                .align_items(Alignment::Center),
+                            );
+                            let installed_filter = search_fold(self.installed_filter.trim());
                             let mut flex_row = Vec::with_capacity(installed.len());
-                            for (installed_i, (_backend_name, package)) in
+                            for (installed_i, (backend_name, package)) in
                                 installed.iter().enumerate()
                             {
-                                flex_row.push(
-                                    widget::mouse_area(package.card_view(vec![], &spacing))
-                                        .on_press(Message::SelectInstalled(installed_i))
+                                if !installed_shown(package) {
+                                    continue;
+                                }
+                                if !installed_filter.is_empty()
+                                    && !package.info.search_text.name.contains(&installed_filter)
+                                    && !package.info.search_text.summary.contains(&installed_filter)
+                                {
+                                    continue;
+                                }
+                                let mut controls = Vec::with_capacity(1);
+                                if let Some(version) =
+                                    self.update_version_opt(backend_name, &package.id)
+                                {
+                                    // Backends may not report the new version, only show it if it differs
+                                    controls.push(
+                                        widget::text::caption(
+                                            if version.is_empty() || version == package.version {
+                                                fl!("update-available")
+                                            } else {
+                                                fl!("update-available-version", version = version)
+                                            },
+                                        )
+                                        .style(theme::Text::Accent)
                                         .into(),
+                                    );
+                                }
+                                flex_row.push(
+                                    widget::mouse_area(package.card_view(
+                                        controls,
+                                        self.view_layout(),
+                                        self.sync_config.density,
+                                        &spacing,
+                                    ))
+                                    .on_press(Message::SelectInstalled(installed_i))
+                                    .into(),
                                 );
                             }
-                            column = column.push(
-                                widget::flex_row(flex_row)
-                                    .column_spacing(space_xxs)
-                                    .row_spacing(space_xxs),
-                            );
-                            widget::scrollable(column).into()
-                        }
-                        None => {
-                            let mut column = widget::column::with_capacity(1)
-                                .padding([0, space_xl])
-                                .spacing(space_xxs)
-                                .width(Length::Fill);
-                            //TODO: translate
-                            column = column.push(widget::text("Loading"));
-                            widget::scrollable(column).into()
+                            column = column.push(self.results_layout(flex_row, &spacing));
+                            self.page_scrollable(column)
                         }
+                        None => self.page_scrollable(self.loading_view(&spacing)),
                     },
                     //TODO: reduce duplication
                     NavPage::Updates => match &self.updates {
@@ -1462,7 +7772,11 @@ impl Application for App {
                                         break;
                                     }
                                 }
-                                let controls = if let Some(progress) = progress_opt {
+                                let release_opt = package.info.latest_release_opt();
+                                let expanded = self
+                                    .updates_expanded
+                                    .contains(&(*backend_name, package.id.clone()));
+                                let mut controls = if let Some(progress) = progress_opt {
                                     vec![widget::progress_bar(0.0..=100.0, progress)
                                         .height(Length::Fixed(4.0))
                                         .into()]
@@ -1478,75 +7792,129 @@ impl Application for App {
                                         ))
                                         .into()]
                                 };
-                                flex_row.push(
-                                    widget::mouse_area(package.card_view(controls, &spacing))
-                                        .on_press(Message::SelectUpdates(updates_i))
+                                if release_opt.is_some() {
+                                    controls.push(
+                                        widget::button::standard(if expanded {
+                                            fl!("hide-release-notes")
+                                        } else {
+                                            fl!("release-notes")
+                                        })
+                                        .on_press(Message::UpdatesExpand(
+                                            backend_name,
+                                            package.id.clone(),
+                                        ))
                                         .into(),
-                                );
+                                    );
+                                }
+                                let card = widget::mouse_area(package.card_view(
+                                    controls,
+                                    self.view_layout(),
+                                    self.sync_config.density,
+                                    &spacing,
+                                ))
+                                .on_press(Message::SelectUpdates(updates_i));
+                                //TODO: use backend changelogs when appstream releases are missing
+                                match release_opt.filter(|_| expanded) {
+                                    Some(release) => {
+                                        let mut notes = widget::column::with_capacity(2)
+                                            .push(widget::text::heading(fl!(
+                                                "release-version",
+                                                version = release.version.as_str()
+                                            )))
+                                            .spacing(space_xxs);
+                                        if let Some(firmware) = &package.firmware_opt {
+                                            notes = notes.push(firmware_view(
+                                                firmware,
+                                                &release.version,
+                                                &spacing,
+                                            ));
+                                        }
+                                        if let Some(description) = &release.description_opt {
+                                            notes = notes.push(markup_view(description, &spacing));
+                                        }
+                                        flex_row.push(
+                                            widget::column::with_children(vec![
+                                                card.into(),
+                                                widget::container(notes)
+                                                    .max_width(360.0 + 2.0 * spacing.space_s as f32)
+                                                    .padding(spacing.space_s)
+                                                    .style(theme::Container::Card)
+                                                    .into(),
+                                            ])
+                                            .spacing(space_xxs)
+                                            .into(),
+                                        );
+                                    }
+                                    None => {
+                                        flex_row.push(card.into());
+                                    }
+                                }
                             }
-                            column = column.push(
-                                widget::flex_row(flex_row)
-                                    .column_spacing(space_xxs)
-                                    .row_spacing(space_xxs),
-                            );
-                            widget::scrollable(column).into()
-                        }
-                        None => {
-                            let mut column = widget::column::with_capacity(1)
-                                .padding([0, space_xl])
-                                .spacing(space_xxs)
-                                .width(Length::Fill);
-                            //TODO: translate
-                            column = column.push(widget::text("Loading"));
-                            widget::scrollable(column).into()
+                            column = column.push(self.results_layout(flex_row, &spacing));
+                            self.page_scrollable(column)
                         }
+                        None => self.page_scrollable(self.loading_view(&spacing)),
                     },
                     //TODO: reduce duplication
                     _ => match &self.category_results {
                         Some((category, results)) => {
-                            //TODO: paging or dynamic load
-                            let results_len = cmp::min(results.len(), 256);
-
                             let mut column = widget::column::with_capacity(2)
                                 .padding([0, space_xl])
                                 .spacing(space_xxs)
                                 .width(Length::Fill);
                             //TODO: back button?
                             //TODO: translate
-                            column = column.push(widget::text(format!(
-                                "{} results in category {:?}",
-                                results.len(),
-                                category
-                            )));
-                            let mut flex_row = Vec::with_capacity(results_len);
-                            for (result_i, result) in results.iter().take(results_len).enumerate() {
+                            column = column.push(
+                                widget::row::with_children(vec![
+                                    widget::text(format!(
+                                        "{} results in category {:?}",
+                                        results.len(),
+                                        category
+                                    ))
+                                    .width(Length::Fill)
+                                    .into(),
+                                    self.search_sort_dropdown(),
+                                    self.view_layout_button(),
+                                ])
+                                .push_maybe(self.proprietary_button())
+                                .align_items(Alignment::Center),
+                            );
+                            let mut flex_row =
+                                Vec::with_capacity(cmp::min(results.len(), self.results_limit));
+                            let mut has_more = false;
+                            for (result_i, result) in results.iter().enumerate() {
+                                if self.result_hidden(&result.info) {
+                                    continue;
+                                }
+                                if flex_row.len() >= self.results_limit {
+                                    has_more = true;
+                                    break;
+                                }
                                 flex_row.push(
-                                    widget::mouse_area(result.card_view(&spacing))
+                                    widget::mouse_area(self.result_view(result, &spacing))
                                         .on_press(Message::SelectCategoryResult(result_i))
                                         .into(),
                                 );
                             }
-                            column = column.push(
-                                widget::flex_row(flex_row)
-                                    .column_spacing(space_xxs)
-                                    .row_spacing(space_xxs),
-                            );
-                            widget::scrollable(column).into()
-                        }
-                        None => {
-                            let mut column = widget::column::with_capacity(1)
-                                .padding([0, space_xl])
-                                .spacing(space_xxs)
-                                .width(Length::Fill);
-                            //TODO: translate
-                            column = column.push(widget::text("Loading"));
-                            widget::scrollable(column).into()
+                            column = column.push(self.results_layout(flex_row, &spacing));
+                            if has_more {
+                                column = column.push(self.load_more_button());
+                            }
+                            self.page_scrollable(column)
                         }
+                        None => self.page_scrollable(self.loading_view(&spacing)),
                     },
                 },
             },
         };
 
+        let content = match self.error_banners_view(&spacing) {
+            Some(error_banners) => widget::column::with_children(vec![error_banners, content])
+                .spacing(space_xxs)
+                .into(),
+            None => content,
+        };
+
         // Uncomment to debug layout:
         //content.explain(cosmic::iced::Color::WHITE)
         content
@@ -1554,6 +7922,10 @@ impl Application for App {
 
     fn subscription(&self) -> Subscription<Self::Message> {
         struct ConfigSubscription;
+        struct InhibitSubscription;
+        struct OperationsAuthSubscription;
+        struct SessionSubscription;
+        struct SyncConfigSubscription;
         struct ThemeSubscription;
 
         let mut subscriptions = vec![
@@ -1561,6 +7933,15 @@ impl Application for App {
                 Event::Keyboard(KeyEvent::KeyPressed { key, modifiers, .. }) => {
                     Some(Message::Key(modifiers, key))
                 }
+                Event::Mouse(MouseEvent::ButtonPressed(MouseButton::Back)) => {
+                    Some(Message::NavBack)
+                }
+                Event::Mouse(MouseEvent::ButtonPressed(MouseButton::Forward)) => {
+                    Some(Message::NavForward)
+                }
+                Event::Window(_id, window::Event::Resized { width, height }) => {
+                    Some(Message::WindowResized(width as f32, height as f32))
+                }
                 _ => None,
             }),
             cosmic_config::config_subscription(
@@ -1574,6 +7955,17 @@ impl Application for App {
                 }
                 Message::SystemThemeModeChange(update.config)
             }),
+            cosmic_config::config_subscription(
+                TypeId::of::<SyncConfigSubscription>(),
+                SYNC_CONFIG_ID.into(),
+                SYNC_CONFIG_VERSION,
+            )
+            .map(|update| {
+                if !update.errors.is_empty() {
+                    log::debug!("errors loading sync config: {:?}", update.errors);
+                }
+                Message::SyncConfig(update.config)
+            }),
             cosmic_config::config_subscription::<_, cosmic_theme::ThemeMode>(
                 TypeId::of::<ThemeSubscription>(),
                 cosmic_theme::THEME_MODE_ID.into(),
@@ -1585,9 +7977,77 @@ impl Application for App {
                 }
                 Message::SystemThemeModeChange(update.config)
             }),
+            subscription::channel(
+                TypeId::of::<SessionSubscription>(),
+                16,
+                |msg_tx| async move {
+                    // Keep the connection alive so the interface stays registered
+                    let _connection_opt = match session::serve(msg_tx).await {
+                        Ok(connection) => Some(connection),
+                        Err(err) => {
+                            log::warn!("failed to serve PackageKit session interface: {}", err);
+                            None
+                        }
+                    };
+                    loop {
+                        tokio::time::sleep(time::Duration::new(1, 0)).await;
+                    }
+                },
+            ),
         ];
 
+        if self.config.require_auth
+            && !self.operations_authorized
+            && !self.pending_operations.is_empty()
+        {
+            // Fails closed, operations are not run if polkit is not available
+            subscriptions.push(subscription::channel(
+                TypeId::of::<OperationsAuthSubscription>(),
+                16,
+                |mut msg_tx| async move {
+                    let authorized = match polkit::authorize(polkit::OPERATION_ACTION).await {
+                        Ok(authorized) => authorized,
+                        Err(err) => {
+                            log::warn!("failed to authorize operations: {}", err);
+                            false
+                        }
+                    };
+                    let _ = msg_tx.send(Message::OperationsAuthorized(authorized)).await;
+                    loop {
+                        tokio::time::sleep(time::Duration::new(1, 0)).await;
+                    }
+                },
+            ));
+        }
+
+        if !self.pending_operations.is_empty() {
+            // Removed when the operation queue drains, which releases the inhibitor
+            subscriptions.push(subscription::channel(
+                TypeId::of::<InhibitSubscription>(),
+                16,
+                |_msg_tx| async move {
+                    let _fd_opt =
+                        match inhibit::inhibit(&fl!("cosmic-app-store"), &fl!("inhibit-reason"))
+                            .await
+                        {
+                            Ok(fd) => Some(fd),
+                            Err(err) => {
+                                log::warn!("failed to inhibit suspend: {}", err);
+                                None
+                            }
+                        };
+                    loop {
+                        tokio::time::sleep(time::Duration::new(1, 0)).await;
+                    }
+                },
+            ));
+        }
+
         for (id, (op, _)) in self.pending_operations.iter() {
+            // Queued operations are subscribed to when started
+            if !self.running_operations.contains(id) {
+                continue;
+            }
             //TODO: use recipe?
             let id = *id;
             let backend_opt = self.backends.get(op.backend_name).map(|x| x.clone());
@@ -1640,37 +8100,233 @@ impl Application for App {
             }));
         }
 
+        if let Some(duration) = self.config.refresh_interval.duration_opt() {
+            // Operations may be using the backends, so wait for them to finish
+            if self.backends_loaded && self.pending_operations.is_empty() {
+                subscriptions.push(cosmic::iced::time::every(duration).map(|_| Message::Refresh));
+            }
+        }
+
+        if self.selected_opt.is_none()
+            && self.search_results.is_none()
+            && self.nav_model.active_data::<NavPage>() == Some(&NavPage::Explore)
+        {
+            if self.featured.len() > 1 {
+                subscriptions.push(
+                    cosmic::iced::time::every(FEATURED_INTERVAL).map(|_| Message::FeaturedNext),
+                );
+            }
+
+            if let Some(screenshot) = self.featured.get(self.featured_shown).and_then(|result| {
+                result
+                    .info
+                    .screenshots_for_theme(theme::active().cosmic().is_dark)
+                    .first()
+                    .map(|(_, screenshot)| *screenshot)
+            }) {
+                if !self.featured_images.contains_key(&screenshot.url) {
+                    let url = screenshot.url.clone();
+                    subscriptions.push(subscription::channel(
+                        ("featured", url.clone()),
+                        16,
+                        move |mut msg_tx| async move {
+                            if let Some(data) = screenshot_cache::screenshot_data(&url).await {
+                                let _ = msg_tx.send(Message::FeaturedScreenshot(url, data)).await;
+                            }
+                            loop {
+                                tokio::time::sleep(time::Duration::new(1, 0)).await;
+                            }
+                        },
+                    ));
+                }
+            }
+        }
+
+        if self.config.tray {
+            let updates_len = self.updates.as_ref().map_or(0, |updates| updates.len());
+            let tooltip = if updates_len > 0 {
+                fl!("updates-notification", count = updates_len)
+            } else {
+                fl!("no-updates")
+            };
+            let items = vec![
+                tray::TrayMenuItem {
+                    label: fl!("open-store"),
+                    enabled: true,
+                    message: Message::WindowActivate,
+                },
+                tray::TrayMenuItem {
+                    label: fl!("update-all"),
+                    enabled: updates_len > 0,
+                    message: Message::UpdateAll,
+                },
+            ];
+            // Registered again when the number of updates changes
+            subscriptions.push(subscription::channel(
+                ("tray", updates_len),
+                16,
+                move |msg_tx| async move {
+                    let _connection_opt = match tray::serve(
+                        msg_tx,
+                        fl!("cosmic-app-store"),
+                        tooltip,
+                        updates_len > 0,
+                        Message::WindowActivate,
+                        items,
+                    )
+                    .await
+                    {
+                        Ok(connection) => Some(connection),
+                        Err(err) => {
+                            log::warn!("failed to register status notifier item: {}", err);
+                            None
+                        }
+                    };
+                    loop {
+                        tokio::time::sleep(time::Duration::new(1, 0)).await;
+                    }
+                },
+            ));
+        }
+
+        if self.backends_loaded {
+            let backends = self.backends.clone();
+            let installed: HashSet<String> = self
+                .installed
+                .iter()
+                .flatten()
+                .map(|(_backend_name, package)| package.id.clone())
+                .collect();
+            // Registered again when installed apps change
+            subscriptions.push(subscription::channel(
+                ("search-provider", installed.len()),
+                16,
+                move |msg_tx| async move {
+                    let _connection_opt = match search_provider::serve(
+                        msg_tx,
+                        backends,
+                        installed,
+                        fl!("install-with-app-store"),
+                    )
+                    .await
+                    {
+                        Ok(connection) => Some(connection),
+                        Err(err) => {
+                            log::warn!("failed to serve search provider: {}", err);
+                            None
+                        }
+                    };
+                    loop {
+                        tokio::time::sleep(time::Duration::new(1, 0)).await;
+                    }
+                },
+            ));
+        }
+
+        if let Some((summary, body)) = &self.updates_notification_opt {
+            let summary = summary.clone();
+            let body = body.clone();
+            subscriptions.push(subscription::channel(
+                ("updates-notification", summary.clone(), body.clone()),
+                16,
+                move |mut msg_tx| async move {
+                    match notification::notify(
+                        &fl!("cosmic-app-store"),
+                        &summary,
+                        &body,
+                        &fl!("view-updates"),
+                    )
+                    .await
+                    {
+                        Ok(()) => {
+                            let _ = msg_tx.send(Message::UpdatesOpen).await;
+                        }
+                        Err(err) => {
+                            log::warn!("failed to show updates notification: {}", err);
+                        }
+                    }
+                    loop {
+                        tokio::time::sleep(time::Duration::new(1, 0)).await;
+                    }
+                },
+            ));
+        }
+
         if let Some(selected) = &self.selected_opt {
-            for (screenshot_i, screenshot) in selected.info.screenshots.iter().enumerate() {
-                let url = screenshot.url.clone();
+            if let Some(desktop_id) = selected.info.desktop_ids.first() {
+                if !self.desktop_entries.contains_key(desktop_id)
+                    && self.is_installed(selected.backend_name, &selected.id)
+                {
+                    let desktop_id = desktop_id.clone();
+                    subscriptions.push(subscription::channel(
+                        ("desktop-entry", desktop_id.clone()),
+                        16,
+                        move |mut msg_tx| async move {
+                            let res = {
+                                let desktop_id = desktop_id.clone();
+                                tokio::task::spawn_blocking(move || {
+                                    DesktopEntry::load(&desktop_id).map_err(|err| {
+                                        log::warn!(
+                                            "failed to load desktop ID {:?}: {}",
+                                            desktop_id,
+                                            err
+                                        );
+                                        err.to_string()
+                                    })
+                                })
+                                .await
+                                .unwrap_or_else(|err| Err(err.to_string()))
+                            };
+                            let _ = msg_tx.send(Message::DesktopEntry(desktop_id, res)).await;
+                            loop {
+                                tokio::time::sleep(time::Duration::new(1, 0)).await;
+                            }
+                        },
+                    ));
+                }
+            }
+
+            if self.config.reviews_server.is_some() && !self.reviews.contains_key(&selected.id) {
+                let id = selected.id.clone();
+                //TODO: use installed version if available
+                let version = selected
+                    .info
+                    .releases
+                    .first()
+                    .map_or(String::new(), |release| release.version.clone());
+                let locale = self.locale.clone();
                 subscriptions.push(subscription::channel(
-                    url.clone(),
+                    ("reviews", id.clone()),
                     16,
                     move |mut msg_tx| async move {
-                        log::info!("fetch screenshot {}", url);
-                        match reqwest::get(&url).await {
-                            Ok(response) => match response.bytes().await {
-                                Ok(bytes) => {
-                                    log::info!(
-                                        "fetched screenshot from {}: {} bytes",
-                                        url,
-                                        bytes.len()
-                                    );
-                                    let _ = msg_tx
-                                        .send(Message::SelectedScreenshot(
-                                            screenshot_i,
-                                            url,
-                                            bytes.to_vec(),
-                                        ))
-                                        .await;
-                                }
-                                Err(err) => {
-                                    log::warn!("failed to read screenshot from {}: {}", url, err);
-                                }
-                            },
+                        let reviews = match odrs::reviews(&id, &version, &locale).await {
+                            Ok(ok) => ok,
                             Err(err) => {
-                                log::warn!("failed to request screenshot from {}: {}", url, err);
+                                log::warn!("failed to load reviews for {:?}: {}", id, err);
+                                Vec::new()
                             }
+                        };
+                        let _ = msg_tx.send(Message::Reviews(id, reviews)).await;
+                        loop {
+                            tokio::time::sleep(time::Duration::new(1, 0)).await;
+                        }
+                    },
+                ));
+            }
+
+            for (screenshot_i, screenshot) in selected
+                .info
+                .screenshots_for_theme(theme::active().cosmic().is_dark)
+            {
+                let url = screenshot.url.clone();
+                subscriptions.push(subscription::channel(
+                    url.clone(),
+                    16,
+                    move |mut msg_tx| async move {
+                        if let Some(data) = screenshot_cache::screenshot_data(&url).await {
+                            let _ = msg_tx
+                                .send(Message::SelectedScreenshot(screenshot_i, url, data))
+                                .await;
                         }
                         loop {
                             tokio::time::sleep(time::Duration::new(1, 0)).await;
@@ -0,0 +1,66 @@
+// Desktop notifications
+// https://specifications.freedesktop.org/notification-spec/latest/
+
+use cosmic::iced::futures::StreamExt;
+use std::collections::HashMap;
+use zbus::{dbus_proxy, zvariant::Value, Connection};
+
+const APP_ICON: &'static str = "com.system76.CosmicStore";
+// Action invoked when the notification itself is clicked
+const DEFAULT_ACTION: &'static str = "default";
+
+#[dbus_proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+
+    #[dbus_proxy(signal)]
+    fn action_invoked(&self, id: u32, action_key: String) -> zbus::Result<()>;
+}
+
+/// Show a notification with a single action, returning once that action is invoked
+pub async fn notify(
+    app_name: &str,
+    summary: &str,
+    body: &str,
+    action_label: &str,
+) -> zbus::Result<()> {
+    let connection = Connection::session().await?;
+    let proxy = NotificationsProxy::new(&connection).await?;
+    // Listen before sending so the action cannot be missed
+    let mut action_invoked = proxy.receive_action_invoked().await?;
+    let id = proxy
+        .notify(
+            app_name,
+            0,
+            APP_ICON,
+            summary,
+            body,
+            &[DEFAULT_ACTION, action_label],
+            HashMap::new(),
+            -1,
+        )
+        .await?;
+    while let Some(signal) = action_invoked.next().await {
+        let args = signal.args()?;
+        if args.id == id {
+            return Ok(());
+        }
+    }
+    Err(zbus::Error::Failure(
+        "notification signal stream ended".to_string(),
+    ))
+}
@@ -0,0 +1,429 @@
+// Open Desktop Ratings Service client
+// https://odrs.gnome.org
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::sync::RwLock;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    io::Write,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+use tokio::sync::Mutex;
+
+use crate::http;
+
+pub const ODRS_URL: &'static str = "https://odrs.gnome.org/1.0/reviews/api";
+// Ratings summary changes slowly and is large, so only fetch it once a week
+const RATINGS_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const REVIEWS_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+// Minimum time between requests to the ODRS server
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+const REVIEWS_LIMIT: usize = 20;
+// Limits enforced before submitting, longer reviews are rejected by moderation
+pub const SUMMARY_MAX: usize = 70;
+pub const DESCRIPTION_MIN: usize = 15;
+pub const DESCRIPTION_MAX: usize = 3000;
+
+lazy_static::lazy_static! {
+    static ref LAST_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+    static ref SERVER: RwLock<Option<String>> = RwLock::new(Some(ODRS_URL.to_string()));
+}
+
+/// Use a different reviews server, or disable reviews if None. The cache is kept, so it must be
+/// cleared when the user changes the server
+pub fn set_server(server_opt: Option<&str>) {
+    let server_opt = server_opt.map(|server| server.trim_end_matches('/').to_string());
+    let mut guard = match SERVER.write() {
+        Ok(ok) => ok,
+        Err(err) => err.into_inner(),
+    };
+    *guard = server_opt;
+}
+
+fn server() -> Result<String, Box<dyn Error + Send + Sync>> {
+    let guard = match SERVER.read() {
+        Ok(ok) => ok,
+        Err(err) => err.into_inner(),
+    };
+    guard.clone().ok_or_else(|| "reviews are disabled".into())
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct OdrsRating {
+    #[serde(default)]
+    pub star0: u64,
+    #[serde(default)]
+    pub star1: u64,
+    #[serde(default)]
+    pub star2: u64,
+    #[serde(default)]
+    pub star3: u64,
+    #[serde(default)]
+    pub star4: u64,
+    #[serde(default)]
+    pub star5: u64,
+    #[serde(default)]
+    pub total: u64,
+}
+
+impl OdrsRating {
+    /// Average rating from 1.0 to 5.0 stars, star0 is used for reviews without a rating
+    pub fn average(&self) -> Option<f32> {
+        let count = self.star1 + self.star2 + self.star3 + self.star4 + self.star5;
+        if count == 0 {
+            return None;
+        }
+        let sum = self.star1 + 2 * self.star2 + 3 * self.star3 + 4 * self.star4 + 5 * self.star5;
+        Some(sum as f32 / count as f32)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OdrsReview {
+    /// Zero for the placeholder returned when there are no reviews, which only has user_skey
+    #[serde(default)]
+    pub review_id: u64,
+    #[serde(default)]
+    pub app_id: String,
+    /// Rating from 0 to 100
+    #[serde(default)]
+    pub rating: u32,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub user_display: Option<String>,
+    /// Creation date in seconds from the unix epoch
+    #[serde(default)]
+    pub date_created: f64,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub karma_up: i64,
+    #[serde(default)]
+    pub karma_down: i64,
+    /// Key required to vote on this review
+    #[serde(default)]
+    pub user_skey: Option<String>,
+}
+
+impl OdrsReview {
+    /// Rating from 1.0 to 5.0 stars
+    pub fn stars(&self) -> f32 {
+        self.rating as f32 / 20.0
+    }
+}
+
+fn cache_path(name: &str) -> Option<PathBuf> {
+    let cache_dir = dirs::cache_dir()?.join("cosmic-store").join("odrs");
+    if !cache_dir.is_dir() {
+        match fs::create_dir_all(&cache_dir) {
+            Ok(()) => {}
+            Err(err) => {
+                log::warn!(
+                    "failed to create ODRS cache directory {:?}: {}",
+                    cache_dir,
+                    err
+                );
+                return None;
+            }
+        }
+    }
+    Some(cache_dir.join(name))
+}
+
+fn cache_read(path: &PathBuf, max_age: Duration) -> Option<Vec<u8>> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let age = SystemTime::now().duration_since(modified).ok()?;
+    if age > max_age {
+        log::info!("ODRS cache {:?} is outdated", path);
+        return None;
+    }
+    fs::read(path).ok()
+}
+
+fn cache_write(path: &PathBuf, data: &[u8]) {
+    match atomicwrites::AtomicFile::new(path, atomicwrites::OverwriteBehavior::AllowOverwrite)
+        .write(|file| file.write_all(data))
+    {
+        Ok(()) => {}
+        Err(err) => {
+            log::warn!("failed to write ODRS cache {:?}: {}", path, err);
+        }
+    }
+}
+
+async fn rate_limit() {
+    let mut last_request = LAST_REQUEST.lock().await;
+    if let Some(instant) = *last_request {
+        let elapsed = instant.elapsed();
+        if elapsed < REQUEST_INTERVAL {
+            tokio::time::sleep(REQUEST_INTERVAL - elapsed).await;
+        }
+    }
+    *last_request = Some(Instant::now());
+}
+
+async fn get(url: &str) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    rate_limit().await;
+    let response = http::client().get(url).send().await?.error_for_status()?;
+    Ok(http::bytes(response).await?)
+}
+
+async fn post<T: Serialize>(
+    url: &str,
+    request: &T,
+) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    rate_limit().await;
+    let response = http::client()
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_vec(request)?)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(http::bytes(response).await?)
+}
+
+/// Anonymous identifier for this user and machine, as required by ODRS
+pub fn user_hash() -> String {
+    let machine_id = fs::read_to_string("/etc/machine-id").unwrap_or_default();
+    let user_name = std::env::var("USER").unwrap_or_default();
+    let mut hasher = Sha1::new();
+    hasher.update(b"cosmic-store[");
+    hasher.update(user_name.as_bytes());
+    hasher.update(b":");
+    hasher.update(machine_id.trim().as_bytes());
+    hasher.update(b"]");
+    let mut hash = String::with_capacity(40);
+    for byte in hasher.finalize() {
+        hash.push_str(&format!("{:02x}", byte));
+    }
+    hash
+}
+
+/// Name of the distribution, as required by ODRS
+pub fn distro() -> String {
+    if let Ok(os_release) = fs::read_to_string("/etc/os-release") {
+        for line in os_release.lines() {
+            if let Some(name) = line.strip_prefix("NAME=") {
+                return name.trim_matches('"').to_string();
+            }
+        }
+    }
+    "Unknown".to_string()
+}
+
+/// Star ratings of all apps, keyed by app id
+pub async fn ratings() -> Result<HashMap<String, OdrsRating>, Box<dyn Error + Send + Sync>> {
+    let path_opt = cache_path("ratings.json");
+    let data = match path_opt
+        .as_ref()
+        .and_then(|path| cache_read(path, RATINGS_MAX_AGE))
+    {
+        Some(data) => data,
+        None => {
+            log::info!("fetch ODRS ratings");
+            let data = get(&format!("{}/ratings", server()?)).await?;
+            if let Some(path) = &path_opt {
+                cache_write(path, &data);
+            }
+            data
+        }
+    };
+    Ok(serde_json::from_slice(&data)?)
+}
+
+#[derive(Serialize)]
+struct FetchRequest<'a> {
+    user_hash: String,
+    app_id: &'a str,
+    locale: &'a str,
+    distro: String,
+    version: &'a str,
+    limit: usize,
+    compat_ids: Vec<String>,
+}
+
+fn reviews_cache_path(app_id: &str) -> Option<PathBuf> {
+    // App ids are not safe to use as file names without hashing
+    let mut hasher = Sha1::new();
+    hasher.update(app_id.as_bytes());
+    let mut name = String::with_capacity(45);
+    for byte in hasher.finalize() {
+        name.push_str(&format!("{:02x}", byte));
+    }
+    name.push_str(".json");
+    cache_path(&name)
+}
+
+/// Reviews of an app, most relevant first
+pub async fn reviews(
+    app_id: &str,
+    version: &str,
+    locale: &str,
+) -> Result<Vec<OdrsReview>, Box<dyn Error + Send + Sync>> {
+    let server = server()?;
+    let path_opt = reviews_cache_path(app_id);
+    if let Some(data) = path_opt
+        .as_ref()
+        .and_then(|path| cache_read(path, REVIEWS_MAX_AGE))
+    {
+        return Ok(serde_json::from_slice(&data)?);
+    }
+
+    log::info!("fetch ODRS reviews for {:?}", app_id);
+    let request = FetchRequest {
+        user_hash: user_hash(),
+        app_id,
+        locale,
+        distro: distro(),
+        version: if version.is_empty() {
+            "unknown"
+        } else {
+            version
+        },
+        limit: REVIEWS_LIMIT,
+        compat_ids: if app_id.ends_with(".desktop") {
+            Vec::new()
+        } else {
+            vec![format!("{}.desktop", app_id)]
+        },
+    };
+    let data = post(&format!("{}/fetch", server), &request).await?;
+    let reviews: Vec<OdrsReview> = serde_json::from_slice(&data)?;
+    if let Some(path) = &path_opt {
+        cache_write(path, &data);
+    }
+    Ok(reviews)
+}
+
+#[derive(Deserialize)]
+struct ServerResponse {
+    success: bool,
+    #[serde(default)]
+    msg: Option<String>,
+}
+
+async fn post_action<T: Serialize>(
+    action: &str,
+    request: &T,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let data = post(&format!("{}/{}", server()?, action), request).await?;
+    let response: ServerResponse = serde_json::from_slice(&data)?;
+    if response.success {
+        Ok(())
+    } else {
+        Err(response
+            .msg
+            .unwrap_or_else(|| format!("ODRS {} failed", action))
+            .into())
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OdrsReviewDraft {
+    pub app_id: String,
+    pub version: String,
+    /// Key returned when fetching reviews, required by the server
+    pub user_skey: String,
+    pub user_display: String,
+    pub summary: String,
+    pub description: String,
+    /// Rating from 1 to 5 stars, zero if not set
+    pub stars: u8,
+}
+
+impl OdrsReviewDraft {
+    /// Returns true if the draft passes the local moderation constraints
+    pub fn is_valid(&self) -> bool {
+        let summary_len = self.summary.trim().chars().count();
+        let description_len = self.description.trim().chars().count();
+        (1..=5).contains(&self.stars)
+            && summary_len > 0
+            && summary_len <= SUMMARY_MAX
+            && description_len >= DESCRIPTION_MIN
+            && description_len <= DESCRIPTION_MAX
+    }
+}
+
+#[derive(Serialize)]
+struct SubmitRequest<'a> {
+    user_hash: String,
+    user_skey: &'a str,
+    app_id: &'a str,
+    locale: &'a str,
+    distro: String,
+    version: &'a str,
+    user_display: Option<&'a str>,
+    summary: &'a str,
+    description: &'a str,
+    rating: u32,
+}
+
+/// Submit a new review, it may be held for moderation before showing up
+pub async fn submit(
+    draft: &OdrsReviewDraft,
+    locale: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if !draft.is_valid() {
+        return Err("review does not meet moderation constraints".into());
+    }
+    let user_display = draft.user_display.trim();
+    let request = SubmitRequest {
+        user_hash: user_hash(),
+        user_skey: &draft.user_skey,
+        app_id: &draft.app_id,
+        locale,
+        distro: distro(),
+        version: if draft.version.is_empty() {
+            "unknown"
+        } else {
+            &draft.version
+        },
+        user_display: if user_display.is_empty() {
+            None
+        } else {
+            Some(user_display)
+        },
+        summary: draft.summary.trim(),
+        description: draft.description.trim(),
+        rating: draft.stars as u32 * 20,
+    };
+    post_action("submit", &request).await?;
+
+    // Remove cached reviews so the new review is fetched
+    if let Some(path) = reviews_cache_path(&draft.app_id) {
+        let _ = fs::remove_file(path);
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct VoteRequest<'a> {
+    user_hash: String,
+    user_skey: &'a str,
+    app_id: &'a str,
+    review_id: u64,
+}
+
+/// Mark a review as helpful or not helpful, each review can only be voted on once
+pub async fn vote(
+    app_id: &str,
+    user_skey: &str,
+    review_id: u64,
+    helpful: bool,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let request = VoteRequest {
+        user_hash: user_hash(),
+        user_skey,
+        app_id,
+        review_id,
+    };
+    post_action(if helpful { "upvote" } else { "downvote" }, &request).await
+}
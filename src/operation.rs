@@ -1,12 +1,18 @@
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, io::Write, path::PathBuf, sync::Arc};
 
 use crate::AppInfo;
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum OperationKind {
     Install,
+    /// Install a local package file, the package ID is the file path
+    InstallFile,
     Uninstall,
     Update,
+    /// Remove runtimes and extensions that no installed app uses, the package ID is empty and the
+    /// app info is named after the backend
+    RemoveUnused,
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -17,13 +23,71 @@ pub struct Operation {
     pub info: Arc<AppInfo>,
 }
 
+/// Operation saved while pending, so it can be resumed if the store exits before it finishes.
+/// Firmware downloads made by the store continue from their partial files and are checked
+/// against the release checksum before installing. PackageKit and flatpak keep the packages and
+/// objects they already downloaded in their own caches and verify them before installing.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SavedOperation {
+    pub kind: OperationKind,
+    pub backend_name: String,
+    pub package_id: String,
+}
+
+fn saved_operations_path() -> Option<PathBuf> {
+    Some(
+        dirs::cache_dir()?
+            .join("cosmic-store")
+            .join("pending-operations.json"),
+    )
+}
+
+pub fn saved_operations_load() -> Result<Vec<SavedOperation>, Box<dyn Error>> {
+    let path = saved_operations_path().ok_or("no cache directory")?;
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read(&path)?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+pub fn saved_operations_save<'a>(
+    operations: impl Iterator<Item = &'a Operation>,
+) -> Result<(), Box<dyn Error>> {
+    let path = saved_operations_path().ok_or("no cache directory")?;
+    let saved: Vec<_> = operations
+        .map(|op| SavedOperation {
+            kind: op.kind,
+            backend_name: op.backend_name.to_string(),
+            package_id: op.package_id.clone(),
+        })
+        .collect();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let data = serde_json::to_vec(&saved)?;
+    // Written atomically so an exit while saving does not lose all pending operations
+    atomicwrites::AtomicFile::new(&path, atomicwrites::OverwriteBehavior::AllowOverwrite)
+        .write(|file| file.write_all(&data))?;
+    Ok(())
+}
+
 impl Operation {
     pub fn failed_dialog(&self, err: &str) -> (String, String) {
         //TODO: translate
         let verb = match self.kind {
-            OperationKind::Install => "install",
+            OperationKind::Install | OperationKind::InstallFile => "install",
             OperationKind::Uninstall => "uninstall",
             OperationKind::Update => "update",
+            OperationKind::RemoveUnused => {
+                return (
+                    format!("Failed to remove unused {} packages", self.info.name),
+                    format!(
+                        "Failed to remove unused {} packages:\n{err}",
+                        self.info.name
+                    ),
+                );
+            }
         };
         (
             format!("Failed to {verb} {}", self.info.name),
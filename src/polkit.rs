@@ -0,0 +1,52 @@
+// Admin authentication for settings that protect shared machines
+// https://www.freedesktop.org/software/polkit/docs/latest/
+
+use std::collections::HashMap;
+use zbus::{dbus_proxy, zvariant::Value, Connection};
+
+/// Action that malcontent-control uses to change parental controls
+pub const MALCONTENT_ACTION: &'static str = "org.freedesktop.MalcontentControl.administration";
+/// Action for installing and removing apps when authentication is required, from
+/// res/com.system76.CosmicStore.policy
+pub const OPERATION_ACTION: &'static str = "com.system76.CosmicStore.operation";
+/// Show an authentication dialog if needed
+const ALLOW_USER_INTERACTION: u32 = 1;
+
+#[dbus_proxy(
+    interface = "org.freedesktop.PolicyKit1.Authority",
+    default_service = "org.freedesktop.PolicyKit1",
+    default_path = "/org/freedesktop/PolicyKit1/Authority"
+)]
+trait Authority {
+    fn check_authorization(
+        &self,
+        subject: &(&str, HashMap<&str, Value<'_>>),
+        action_id: &str,
+        details: HashMap<&str, &str>,
+        flags: u32,
+        cancellation_id: &str,
+    ) -> zbus::Result<(bool, bool, HashMap<String, String>)>;
+}
+
+/// Ask for authentication, returns an error if polkit is not available or the action is not
+/// installed
+pub async fn authorize(action_id: &str) -> zbus::Result<bool> {
+    let connection = Connection::system().await?;
+    let proxy = AuthorityProxy::new(&connection).await?;
+    // The bus name cannot be reused by another process, unlike a process ID
+    let unique_name = connection
+        .unique_name()
+        .ok_or_else(|| zbus::Error::Failure("no unique name on system bus".to_string()))?;
+    let mut subject_details = HashMap::new();
+    subject_details.insert("name", Value::from(unique_name.as_str()));
+    let (authorized, _challenge, _details) = proxy
+        .check_authorization(
+            &("system-bus-name", subject_details),
+            action_id,
+            HashMap::new(),
+            ALLOW_USER_INTERACTION,
+            "",
+        )
+        .await?;
+    Ok(authorized)
+}
@@ -0,0 +1,73 @@
+// Links for reporting a problem with an app, routed to where the app is maintained
+
+use std::fs;
+
+use crate::{AppInfo, AppUrl};
+
+/// Bug tracker of the distribution, used for packages without their own bug tracker
+fn distro_bug_report_url() -> Option<String> {
+    let os_release = fs::read_to_string("/etc/os-release").ok()?;
+    os_release.lines().find_map(|line| {
+        let url = line.strip_prefix("BUG_REPORT_URL=")?.trim_matches('"');
+        if url.is_empty() {
+            None
+        } else {
+            Some(url.to_string())
+        }
+    })
+}
+
+/// Add a prefilled title and body to new issue links on GitHub
+fn prefill(url: &str, title: &str, body: &str) -> String {
+    let new_url = match url.trim_end_matches('/').strip_suffix("/issues") {
+        Some(repo) if url.starts_with("https://github.com/") => format!("{}/issues/new", repo),
+        // Other trackers do not have a common way to prefill reports
+        _ => return url.to_string(),
+    };
+    match reqwest::Url::parse_with_params(&new_url, &[("title", title), ("body", body)]) {
+        Ok(ok) => ok.to_string(),
+        Err(err) => {
+            log::warn!("failed to parse report URL {:?}: {}", new_url, err);
+            url.to_string()
+        }
+    }
+}
+
+/// URL for reporting a problem with an app, prefilled with the app ID and version if possible
+pub fn report_url(backend_name: &str, id: &str, info: &AppInfo) -> Option<String> {
+    let version = info
+        .releases
+        .first()
+        .map_or("unknown", |release| release.version.as_str());
+    let title = format!("Problem with {}", info.name);
+    let body = format!(
+        "App ID: {}\nVersion: {}\nSource: {}\nDistribution: {}\n\nDescribe the problem:\n",
+        id,
+        version,
+        backend_name,
+        crate::odrs::distro()
+    );
+
+    // Flathub packaging issues are tracked in a repository for each app
+    if backend_name == "flatpak" && info.origin_opt.as_deref() == Some("flathub") {
+        return Some(prefill(
+            &format!("https://github.com/flathub/{}/issues", id),
+            &title,
+            &body,
+        ));
+    }
+
+    let bug_tracker_opt = info.urls.iter().find_map(|url| match url {
+        AppUrl::BugTracker(url) => Some(url.as_str()),
+        _ => None,
+    });
+    if let Some(bug_tracker) = bug_tracker_opt {
+        return Some(prefill(bug_tracker, &title, &body));
+    }
+
+    if backend_name == "packagekit" {
+        return distro_bug_report_url();
+    }
+
+    None
+}
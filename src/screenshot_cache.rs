@@ -0,0 +1,79 @@
+use sha2::{Digest, Sha256};
+use std::{fs, io::Write, path::PathBuf};
+
+use crate::http;
+
+fn screenshot_path(url: &str) -> Option<PathBuf> {
+    let cache_dir = dirs::cache_dir()?.join("cosmic-store").join("screenshots");
+    if !cache_dir.is_dir() {
+        match fs::create_dir_all(&cache_dir) {
+            Ok(()) => {}
+            Err(err) => {
+                log::warn!(
+                    "failed to create screenshot cache directory {:?}: {}",
+                    cache_dir,
+                    err
+                );
+                return None;
+            }
+        }
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(url.as_bytes());
+    let mut name = String::with_capacity(64);
+    for byte in hasher.finalize() {
+        name.push_str(&format!("{:02x}", byte));
+    }
+    Some(cache_dir.join(name))
+}
+
+/// Get screenshot data from the download cache, or download it and add it to the cache
+//TODO: remove old screenshots from the cache
+pub async fn screenshot_data(url: &str) -> Option<Vec<u8>> {
+    let path_opt = screenshot_path(url);
+    if let Some(path) = &path_opt {
+        match fs::read(path) {
+            Ok(data) => {
+                log::info!("loaded screenshot {} from cache {:?}", url, path);
+                return Some(data);
+            }
+            Err(_) => {}
+        }
+    }
+
+    log::info!("fetch screenshot {}", url);
+    let data = match http::client().get(url).send().await {
+        Ok(response) => match response.error_for_status() {
+            Ok(response) => match http::bytes(response).await {
+                Ok(data) => data,
+                Err(err) => {
+                    log::warn!("failed to read screenshot from {}: {}", url, err);
+                    return None;
+                }
+            },
+            Err(err) => {
+                log::warn!("failed to request screenshot from {}: {}", url, err);
+                return None;
+            }
+        },
+        Err(err) => {
+            log::warn!("failed to request screenshot from {}: {}", url, err);
+            return None;
+        }
+    };
+    log::info!("fetched screenshot from {}: {} bytes", url, data.len());
+
+    if let Some(path) = &path_opt {
+        match atomicwrites::AtomicFile::new(path, atomicwrites::OverwriteBehavior::AllowOverwrite)
+            .write(|file| file.write_all(&data))
+        {
+            Ok(()) => {}
+            Err(err) => {
+                log::warn!("failed to write screenshot cache {:?}: {}", path, err);
+            }
+        }
+    }
+
+    Some(data)
+}
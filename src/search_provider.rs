@@ -0,0 +1,175 @@
+// Search provider, used by launchers to offer apps that are not installed
+// https://developer.gnome.org/documentation/tutorials/search-provider.html
+//TODO: add a D-Bus service file once the store can start without showing a window
+
+use cosmic::iced::futures::{channel::mpsc, SinkExt};
+use std::collections::{HashMap, HashSet};
+use zbus::{
+    dbus_interface,
+    zvariant::{OwnedValue, Value},
+    Connection, ConnectionBuilder,
+};
+
+use crate::{
+    search_fold, search_weight, AppIcon, AppInfo, AppKind, AppTarget, AppstreamCache, Backends,
+    Message,
+};
+
+const PROVIDER_NAME: &'static str = "com.system76.CosmicStore.SearchProvider";
+const PROVIDER_PATH: &'static str = "/com/system76/CosmicStore/SearchProvider";
+const RESULTS_LIMIT: usize = 5;
+
+struct SearchProvider {
+    msg_tx: mpsc::Sender<Message>,
+    backends: Backends,
+    /// IDs of installed apps, which are already shown by launchers
+    installed: HashSet<String>,
+    description: String,
+}
+
+impl SearchProvider {
+    fn results(&self, terms: &[String]) -> Vec<String> {
+        let input = terms.join(" ");
+        let input_folded = search_fold(&input);
+        if input_folded.trim().is_empty() {
+            return Vec::new();
+        }
+        let pattern = regex::escape(&input_folded);
+        let regex = match regex::RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+        {
+            Ok(ok) => ok,
+            Err(err) => {
+                log::warn!("failed to parse regex {:?}: {}", pattern, err);
+                return Vec::new();
+            }
+        };
+
+        let mut backend_names: Vec<_> = self.backends.keys().copied().collect();
+        backend_names.sort();
+        let mut ids = HashSet::new();
+        let mut results = Vec::new();
+        for backend_name in backend_names {
+            let appstream_cache = self.backends[backend_name].info_cache();
+            for (id, info) in appstream_cache.infos.iter() {
+                if info.kind != AppKind::DesktopApplication
+                    || !info.arch_supported()
+                    || self.installed.contains(id)
+                {
+                    continue;
+                }
+                // Use the first backend that provides the app
+                if ids.contains(id) {
+                    continue;
+                }
+                if let Some(weight) = search_weight(&regex, &input_folded, id, info, None) {
+                    ids.insert(id.clone());
+                    results.push((weight, info.name.clone(), id.clone()));
+                }
+            }
+        }
+        results.sort();
+        results
+            .into_iter()
+            .take(RESULTS_LIMIT)
+            .map(|(_weight, _name, id)| id)
+            .collect()
+    }
+
+    fn info(&self, id: &str) -> Option<(&AppstreamCache, &AppInfo)> {
+        let mut backend_names: Vec<_> = self.backends.keys().copied().collect();
+        backend_names.sort();
+        backend_names.into_iter().find_map(|backend_name| {
+            let appstream_cache = self.backends[backend_name].info_cache();
+            let info = appstream_cache.infos.get(id)?;
+            Some((appstream_cache, info.as_ref()))
+        })
+    }
+
+    async fn open(&self, target: AppTarget) {
+        let mut msg_tx = self.msg_tx.clone();
+        if let Err(err) = msg_tx.send(Message::OpenTarget(target)).await {
+            log::warn!("failed to open search result: {}", err);
+        }
+        // The window may be hidden when started at login
+        if let Err(err) = msg_tx.send(Message::WindowActivate).await {
+            log::warn!("failed to activate window: {}", err);
+        }
+    }
+}
+
+/// Icon name or path, in the format used for serialized GIcons
+fn gicon(appstream_cache: &AppstreamCache, info: &AppInfo) -> Option<String> {
+    info.icons.iter().find_map(|info_icon| match info_icon {
+        AppIcon::Cached(name, width, height, scale) => appstream_cache
+            .icon_path(info.origin_opt.as_deref(), name, *width, *height, *scale)
+            .map(|path| path.display().to_string()),
+        AppIcon::Stock(stock) => Some(stock.clone()),
+    })
+}
+
+#[dbus_interface(name = "org.gnome.Shell.SearchProvider2")]
+impl SearchProvider {
+    fn get_initial_result_set(&self, terms: Vec<String>) -> Vec<String> {
+        self.results(&terms)
+    }
+
+    fn get_subsearch_result_set(
+        &self,
+        _previous_results: Vec<String>,
+        terms: Vec<String>,
+    ) -> Vec<String> {
+        // Results are limited, so searching again may find better matches
+        self.results(&terms)
+    }
+
+    fn get_result_metas(&self, ids: Vec<String>) -> Vec<HashMap<String, OwnedValue>> {
+        ids.into_iter()
+            .filter_map(|id| {
+                let (appstream_cache, info) = self.info(&id)?;
+                let mut meta = HashMap::new();
+                meta.insert("id".to_string(), Value::from(id.as_str()).into());
+                meta.insert("name".to_string(), Value::from(info.name.as_str()).into());
+                meta.insert(
+                    "description".to_string(),
+                    Value::from(self.description.as_str()).into(),
+                );
+                if let Some(gicon) = gicon(appstream_cache, info) {
+                    meta.insert("gicon".to_string(), Value::from(gicon).into());
+                }
+                Some(meta)
+            })
+            .collect()
+    }
+
+    async fn activate_result(&self, id: String, _terms: Vec<String>, _timestamp: u32) {
+        self.open(AppTarget::Id(id)).await
+    }
+
+    async fn launch_search(&self, terms: Vec<String>, _timestamp: u32) {
+        self.open(AppTarget::Search(terms.join(" "))).await
+    }
+}
+
+/// Serve the search provider, the connection must be kept alive for it to stay registered
+pub async fn serve(
+    msg_tx: mpsc::Sender<Message>,
+    backends: Backends,
+    installed: HashSet<String>,
+    description: String,
+) -> zbus::Result<Connection> {
+    ConnectionBuilder::session()?
+        .name(PROVIDER_NAME)?
+        .serve_at(
+            PROVIDER_PATH,
+            SearchProvider {
+                msg_tx,
+                backends,
+                installed,
+                description,
+            },
+        )?
+        .build()
+        .await
+}
@@ -0,0 +1,179 @@
+// PackageKit session interface, used by applications to request installs
+// https://www.freedesktop.org/software/PackageKit/gtk-doc/api-reference.html
+
+use cosmic::iced::futures::{
+    channel::{mpsc, oneshot},
+    SinkExt,
+};
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+use zbus::{dbus_interface, fdo, Connection, ConnectionBuilder};
+
+use crate::{AppTarget, Message};
+
+const SESSION_NAME: &'static str = "org.freedesktop.PackageKit";
+const SESSION_PATH: &'static str = "/org/freedesktop/PackageKit";
+
+/// Sends the result of a session request once the install it started has finished
+#[derive(Clone)]
+pub struct SessionReply(Arc<Mutex<Option<oneshot::Sender<Result<(), String>>>>>);
+
+impl SessionReply {
+    /// Reply to the request, only the first reply is sent
+    pub fn send(&self, res: Result<(), String>) {
+        let tx_opt = match self.0.lock() {
+            Ok(mut guard) => guard.take(),
+            Err(err) => {
+                log::warn!("failed to lock session reply: {}", err);
+                None
+            }
+        };
+        if let Some(tx) = tx_opt {
+            // The caller may have stopped waiting
+            let _ = tx.send(res);
+        }
+    }
+}
+
+impl fmt::Debug for SessionReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionReply").finish_non_exhaustive()
+    }
+}
+
+struct Modify {
+    msg_tx: mpsc::Sender<Message>,
+}
+
+impl Modify {
+    /// Show the app matching each item in turn, as the store handles one app at a time, and wait
+    /// for the install of it that the user starts to finish
+    async fn open(&self, targets: impl IntoIterator<Item = AppTarget>) -> fdo::Result<()> {
+        let targets: Vec<_> = targets.into_iter().collect();
+        if targets.is_empty() {
+            return Err(fdo::Error::InvalidArgs("no items requested".to_string()));
+        }
+        for target in targets {
+            self.open_one(target).await?;
+        }
+        Ok(())
+    }
+
+    async fn open_one(&self, target: AppTarget) -> fdo::Result<()> {
+        // Interface methods take a shared reference so requests do not block each other
+        let mut msg_tx = self.msg_tx.clone();
+        let (reply_tx, reply_rx) = oneshot::channel();
+        msg_tx
+            .send(Message::SessionRequest(
+                target,
+                SessionReply(Arc::new(Mutex::new(Some(reply_tx)))),
+            ))
+            .await
+            .map_err(|err| fdo::Error::Failed(err.to_string()))?;
+        // The window may be hidden when started at login
+        msg_tx
+            .send(Message::WindowActivate)
+            .await
+            .map_err(|err| fdo::Error::Failed(err.to_string()))?;
+        match reply_rx.await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(err)) => Err(fdo::Error::Failed(err)),
+            Err(_) => Err(fdo::Error::Failed("request was cancelled".to_string())),
+        }
+    }
+}
+
+#[dbus_interface(name = "org.freedesktop.PackageKit.Modify")]
+impl Modify {
+    async fn install_package_names(
+        &self,
+        _xid: u32,
+        packages: Vec<String>,
+        _interaction: String,
+    ) -> fdo::Result<()> {
+        self.open(packages.into_iter().map(AppTarget::Package))
+            .await
+    }
+
+    async fn install_mime_types(
+        &self,
+        _xid: u32,
+        mime_types: Vec<String>,
+        _interaction: String,
+    ) -> fdo::Result<()> {
+        self.open(
+            mime_types
+                .into_iter()
+                .map(|mime| AppTarget::Search(format!("mime:{}", mime))),
+        )
+        .await
+    }
+
+    async fn install_provide_files(
+        &self,
+        _xid: u32,
+        files: Vec<String>,
+        _interaction: String,
+    ) -> fdo::Result<()> {
+        // Backends are asked which packages provide the file
+        self.open(
+            files
+                .into_iter()
+                .map(|file| AppTarget::Search(format!("provides:{}", file))),
+        )
+        .await
+    }
+
+    async fn install_package_files(
+        &self,
+        _xid: u32,
+        files: Vec<String>,
+        _interaction: String,
+    ) -> fdo::Result<()> {
+        self.open(files.into_iter().map(|file| AppTarget::File(file.into())))
+            .await
+    }
+
+    async fn install_fontconfig_resources(
+        &self,
+        _xid: u32,
+        resources: Vec<String>,
+        _interaction: String,
+    ) -> fdo::Result<()> {
+        // Resources such as ":lang=ja" are provided by packages as "font(:lang=ja)"
+        self.open(
+            resources
+                .into_iter()
+                .map(|resource| AppTarget::Search(format!("provides:font({})", resource))),
+        )
+        .await
+    }
+
+    async fn install_gstreamer_resources(
+        &self,
+        _xid: u32,
+        resources: Vec<String>,
+        _interaction: String,
+    ) -> fdo::Result<()> {
+        // Resources are a description and the provide, such as
+        // "MPEG-4 AAC decoder|gstreamer1(decoder-audio/mpeg)(mpegversion=4)()(64bit)"
+        self.open(resources.into_iter().map(|resource| {
+            let provide = resource
+                .split_once('|')
+                .map_or(resource.as_str(), |(_, provide)| provide);
+            AppTarget::Search(format!("provides:{}", provide))
+        }))
+        .await
+    }
+}
+
+/// Serve the session interface, the connection must be kept alive for it to stay registered
+pub async fn serve(msg_tx: mpsc::Sender<Message>) -> zbus::Result<Connection> {
+    ConnectionBuilder::session()?
+        .name(SESSION_NAME)?
+        .serve_at(SESSION_PATH, Modify { msg_tx })?
+        .build()
+        .await
+}
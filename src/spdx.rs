@@ -0,0 +1,229 @@
+// Minimal SPDX license expression parsing to classify licenses as free or proprietary
+// https://spdx.github.io/spdx-spec/v2.3/SPDX-license-expressions/
+
+// Licenses approved by the OSI or FSF, without -only, -or-later, or + suffixes
+const FREE_LICENSES: &'static [&'static str] = &[
+    "0BSD",
+    "AFL-2.1",
+    "AFL-3.0",
+    "AGPL-3.0",
+    "Apache-1.1",
+    "Apache-2.0",
+    "APSL-2.0",
+    "Artistic-1.0-Perl",
+    "Artistic-2.0",
+    "BitTorrent-1.1",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "BSD-3-Clause-Clear",
+    "BSL-1.0",
+    "CC-BY-3.0",
+    "CC-BY-4.0",
+    "CC-BY-SA-3.0",
+    "CC-BY-SA-4.0",
+    "CC0-1.0",
+    "CDDL-1.0",
+    "CECILL-2.0",
+    "CECILL-2.1",
+    "CPAL-1.0",
+    "curl",
+    "ECL-2.0",
+    "EFL-2.0",
+    "EPL-1.0",
+    "EPL-2.0",
+    "EUPL-1.1",
+    "EUPL-1.2",
+    "FSFAP",
+    "FTL",
+    "GFDL-1.1",
+    "GFDL-1.2",
+    "GFDL-1.3",
+    "GPL-1.0",
+    "GPL-2.0",
+    "GPL-3.0",
+    "HPND",
+    "IJG",
+    "Imlib2",
+    "IPA",
+    "ISC",
+    "LGPL-2.0",
+    "LGPL-2.1",
+    "LGPL-3.0",
+    "Libpng",
+    "LPPL-1.3c",
+    "MIT",
+    "MIT-0",
+    "MPL-1.1",
+    "MPL-2.0",
+    "MS-PL",
+    "MS-RL",
+    "NCSA",
+    "ODbL-1.0",
+    "OFL-1.1",
+    "OpenSSL",
+    "OSL-3.0",
+    "PHP-3.01",
+    "PSF-2.0",
+    "Python-2.0",
+    "QPL-1.0",
+    "Ruby",
+    "SGI-B-2.0",
+    "SISSL",
+    "Sleepycat",
+    "Unicode-DFS-2016",
+    "Unlicense",
+    "UPL-1.0",
+    "Vim",
+    "W3C",
+    "WTFPL",
+    "X11",
+    "XFree86-1.1",
+    "Zlib",
+    "ZPL-2.0",
+    "ZPL-2.1",
+];
+
+fn license_is_free(id: &str) -> Option<bool> {
+    if id.starts_with("LicenseRef-proprietary") {
+        return Some(false);
+    }
+    let base = id
+        .trim_end_matches('+')
+        .trim_end_matches("-only")
+        .trim_end_matches("-or-later");
+    if FREE_LICENSES
+        .iter()
+        .any(|free| free.eq_ignore_ascii_case(base))
+    {
+        Some(true)
+    } else {
+        // Unknown licenses and other license references could be either
+        None
+    }
+}
+
+fn tokenize(expression: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start_opt = None;
+    for (i, c) in expression.char_indices() {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            if let Some(start) = start_opt.take() {
+                tokens.push(&expression[start..i]);
+            }
+            if !c.is_whitespace() {
+                tokens.push(&expression[i..i + 1]);
+            }
+        } else if start_opt.is_none() {
+            start_opt = Some(i);
+        }
+    }
+    if let Some(start) = start_opt {
+        tokens.push(&expression[start..]);
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    index: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.index).copied()
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek()?;
+        self.index += 1;
+        Some(token)
+    }
+
+    fn peek_operator(&self, operator: &str) -> bool {
+        self.peek()
+            .map_or(false, |token| token.eq_ignore_ascii_case(operator))
+    }
+
+    // Both three valued, None is unknown
+    fn or_expression(&mut self) -> Result<Option<bool>, String> {
+        let mut value = self.and_expression()?;
+        while self.peek_operator("OR") {
+            self.index += 1;
+            let other = self.and_expression()?;
+            value = match (value, other) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            };
+        }
+        Ok(value)
+    }
+
+    fn and_expression(&mut self) -> Result<Option<bool>, String> {
+        let mut value = self.atom()?;
+        while self.peek_operator("AND") {
+            self.index += 1;
+            let other = self.atom()?;
+            value = match (value, other) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            };
+        }
+        Ok(value)
+    }
+
+    fn atom(&mut self) -> Result<Option<bool>, String> {
+        match self.next() {
+            Some("(") => {
+                let value = self.or_expression()?;
+                match self.next() {
+                    Some(")") => Ok(value),
+                    other => Err(format!("expected ')', found {:?}", other)),
+                }
+            }
+            Some(")") => Err("unexpected ')'".to_string()),
+            Some(id) => {
+                // Exceptions only grant additional permissions
+                if self.peek_operator("WITH") {
+                    self.index += 1;
+                    if self.next().is_none() {
+                        return Err("missing exception after WITH".to_string());
+                    }
+                }
+                Ok(license_is_free(id))
+            }
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+}
+
+/// Returns Some(true) if the SPDX expression allows use under a free software license,
+/// Some(false) if it is proprietary, and None if unknown or not parsable
+pub fn is_free(expression: &str) -> Option<bool> {
+    let mut parser = Parser {
+        tokens: tokenize(expression),
+        index: 0,
+    };
+    if parser.tokens.is_empty() {
+        return None;
+    }
+    match parser.or_expression() {
+        Ok(value) => {
+            if parser.index == parser.tokens.len() {
+                value
+            } else {
+                log::debug!("trailing tokens in license expression {:?}", expression);
+                None
+            }
+        }
+        Err(err) => {
+            log::debug!(
+                "failed to parse license expression {:?}: {}",
+                expression,
+                err
+            );
+            None
+        }
+    }
+}
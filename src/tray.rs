@@ -0,0 +1,237 @@
+// Status area indicator, shown by panels that support status notifier items
+// https://www.freedesktop.org/wiki/Specifications/StatusNotifierItem/
+
+use cosmic::iced::futures::{channel::mpsc, SinkExt};
+use std::{collections::HashMap, process};
+use zbus::{
+    dbus_interface, dbus_proxy,
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, StructureBuilder, Value},
+    Connection, ConnectionBuilder,
+};
+
+use crate::Message;
+
+const ITEM_PATH: &'static str = "/StatusNotifierItem";
+const MENU_PATH: &'static str = "/MenuBar";
+const ICON_NAME: &'static str = "com.system76.CosmicStore";
+const ATTENTION_ICON_NAME: &'static str = "software-update-available-symbolic";
+// Menu item IDs start at 1, as 0 is the root
+const MENU_ROOT_ID: i32 = 0;
+
+#[dbus_proxy(
+    interface = "org.kde.StatusNotifierWatcher",
+    default_service = "org.kde.StatusNotifierWatcher",
+    default_path = "/StatusNotifierWatcher"
+)]
+trait StatusNotifierWatcher {
+    fn register_status_notifier_item(&self, service: &str) -> zbus::Result<()>;
+}
+
+#[derive(Clone, Debug)]
+pub struct TrayMenuItem {
+    pub label: String,
+    pub enabled: bool,
+    pub message: Message,
+}
+
+struct StatusNotifierItem {
+    msg_tx: mpsc::Sender<Message>,
+    title: String,
+    tooltip: String,
+    attention: bool,
+    activate: Message,
+}
+
+#[dbus_interface(name = "org.kde.StatusNotifierItem")]
+impl StatusNotifierItem {
+    #[dbus_interface(property)]
+    fn category(&self) -> String {
+        "SystemServices".to_string()
+    }
+
+    #[dbus_interface(property)]
+    fn id(&self) -> String {
+        ICON_NAME.to_string()
+    }
+
+    #[dbus_interface(property)]
+    fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    #[dbus_interface(property)]
+    fn status(&self) -> String {
+        if self.attention {
+            "NeedsAttention".to_string()
+        } else {
+            "Active".to_string()
+        }
+    }
+
+    #[dbus_interface(property)]
+    fn icon_name(&self) -> String {
+        ICON_NAME.to_string()
+    }
+
+    #[dbus_interface(property)]
+    fn attention_icon_name(&self) -> String {
+        ATTENTION_ICON_NAME.to_string()
+    }
+
+    #[dbus_interface(property)]
+    fn tool_tip(&self) -> (String, Vec<(i32, i32, Vec<u8>)>, String, String) {
+        (
+            ICON_NAME.to_string(),
+            Vec::new(),
+            self.title.clone(),
+            self.tooltip.clone(),
+        )
+    }
+
+    #[dbus_interface(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    #[dbus_interface(property)]
+    fn menu(&self) -> OwnedObjectPath {
+        ObjectPath::from_static_str_unchecked(MENU_PATH).into()
+    }
+
+    async fn activate(&self, _x: i32, _y: i32) {
+        let _ = self.msg_tx.clone().send(self.activate.clone()).await;
+    }
+
+    async fn secondary_activate(&self, _x: i32, _y: i32) {}
+
+    async fn scroll(&self, _delta: i32, _orientation: String) {}
+}
+
+// https://github.com/AyatanaIndicators/libdbusmenu/blob/master/libdbusmenu-glib/dbus-menu.xml
+struct DbusMenu {
+    msg_tx: mpsc::Sender<Message>,
+    items: Vec<TrayMenuItem>,
+}
+
+impl DbusMenu {
+    fn item_properties(&self, id: i32) -> HashMap<String, OwnedValue> {
+        let mut properties = HashMap::new();
+        if id == MENU_ROOT_ID {
+            properties.insert(
+                "children-display".to_string(),
+                Value::from("submenu").into(),
+            );
+        } else if let Some(item) = self.items.get((id - 1) as usize) {
+            properties.insert("label".to_string(), Value::from(item.label.as_str()).into());
+            properties.insert("enabled".to_string(), Value::from(item.enabled).into());
+        }
+        properties
+    }
+
+    async fn clicked(&self, id: i32) {
+        let Some(item) = self.items.get((id - 1) as usize) else {
+            log::warn!("unknown tray menu item {}", id);
+            return;
+        };
+        if item.enabled {
+            let _ = self.msg_tx.clone().send(item.message.clone()).await;
+        }
+    }
+}
+
+#[dbus_interface(name = "com.canonical.dbusmenu")]
+impl DbusMenu {
+    #[dbus_interface(property)]
+    fn version(&self) -> u32 {
+        3
+    }
+
+    #[dbus_interface(property)]
+    fn status(&self) -> String {
+        "normal".to_string()
+    }
+
+    fn get_layout(
+        &self,
+        parent_id: i32,
+        _recursion_depth: i32,
+        _property_names: Vec<String>,
+    ) -> (u32, (i32, HashMap<String, OwnedValue>, Vec<OwnedValue>)) {
+        // The menu is flat, so only the root has children
+        let mut children = Vec::new();
+        if parent_id == MENU_ROOT_ID {
+            for id in 1..=self.items.len() as i32 {
+                let child = StructureBuilder::new()
+                    .add_field(id)
+                    .add_field(self.item_properties(id))
+                    .add_field(Vec::<OwnedValue>::new())
+                    .build();
+                children.push(Value::from(child).into());
+            }
+        }
+        (0, (parent_id, self.item_properties(parent_id), children))
+    }
+
+    fn get_group_properties(
+        &self,
+        ids: Vec<i32>,
+        _property_names: Vec<String>,
+    ) -> Vec<(i32, HashMap<String, OwnedValue>)> {
+        ids.into_iter()
+            .map(|id| (id, self.item_properties(id)))
+            .collect()
+    }
+
+    async fn event(&self, id: i32, event_id: String, _data: OwnedValue, _timestamp: u32) {
+        if event_id == "clicked" {
+            self.clicked(id).await;
+        }
+    }
+
+    async fn event_group(&self, events: Vec<(i32, String, OwnedValue, u32)>) -> Vec<i32> {
+        for (id, event_id, _data, _timestamp) in events {
+            if event_id == "clicked" {
+                self.clicked(id).await;
+            }
+        }
+        Vec::new()
+    }
+
+    fn about_to_show(&self, _id: i32) -> bool {
+        false
+    }
+
+    fn about_to_show_group(&self, _ids: Vec<i32>) -> (Vec<i32>, Vec<i32>) {
+        (Vec::new(), Vec::new())
+    }
+}
+
+/// Register the status notifier item, the connection must be kept alive for it to stay shown
+pub async fn serve(
+    msg_tx: mpsc::Sender<Message>,
+    title: String,
+    tooltip: String,
+    attention: bool,
+    activate: Message,
+    items: Vec<TrayMenuItem>,
+) -> zbus::Result<Connection> {
+    let name = format!("org.kde.StatusNotifierItem-{}-1", process::id());
+    let connection = ConnectionBuilder::session()?
+        .name(name.as_str())?
+        .serve_at(
+            ITEM_PATH,
+            StatusNotifierItem {
+                msg_tx: msg_tx.clone(),
+                title,
+                tooltip,
+                attention,
+                activate,
+            },
+        )?
+        .serve_at(MENU_PATH, DbusMenu { msg_tx, items })?
+        .build()
+        .await?;
+    let watcher = StatusNotifierWatcherProxy::new(&connection).await?;
+    watcher.register_status_notifier_item(&name).await?;
+    Ok(connection)
+}